@@ -0,0 +1,24 @@
+//! Zone lookups by binary search against the precomputed hash map.
+use std::{fs::File, hint::black_box};
+
+use criterion::{Criterion, criterion_group, criterion_main};
+use zoneinfo_db::{TzDataHeader, TzDataIndexes};
+
+fn lookup(c: &mut Criterion) {
+    let file = File::open("./tests/android/tzdata").unwrap();
+    let header = TzDataHeader::new(&file).unwrap();
+    let indexes = TzDataIndexes::new_android(&file, &header).unwrap();
+    let names: Vec<_> = indexes.timezones().iter().map(|x| x.name.clone()).collect();
+
+    c.bench_function("find_timezone", |b| {
+        b.iter(|| names.iter().filter_map(|x| indexes.find_timezone(black_box(x))).count())
+    });
+    let map = indexes.build_lookup_map();
+    c.bench_function("build_lookup_map", |b| b.iter(|| indexes.build_lookup_map().len()));
+    c.bench_function("lookup_map", |b| {
+        b.iter(|| names.iter().filter_map(|x| map.get(black_box(&**x))).count())
+    });
+}
+
+criterion_group!(benches, lookup);
+criterion_main!(benches);
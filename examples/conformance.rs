@@ -0,0 +1,35 @@
+//! Dump every zone of a `tzdata` file as Android's `ZoneInfo` prints it, to diff against the
+//! reference implementation run on the same file.
+//!
+//! ```sh
+//! cargo run --example conformance --features conformance -- /system/usr/share/zoneinfo/tzdata
+//! ```
+use std::{
+    io,
+    process::ExitCode,
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+use zoneinfo_db::{ZoneInfoDb, conformance_dump};
+
+fn main() -> ExitCode {
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    let [path] = &args[..] else {
+        eprintln!("usage: conformance <tzdata>");
+        return ExitCode::from(2);
+    };
+    match run(path) {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(error) => {
+            eprintln!("error: {error}");
+            ExitCode::FAILURE
+        }
+    }
+}
+
+fn run(path: &str) -> Result<(), Box<dyn std::error::Error>> {
+    let db = ZoneInfoDb::from_bytes(std::fs::read(path)?)?;
+    let now = SystemTime::now().duration_since(UNIX_EPOCH)?.as_secs() as i64;
+    conformance_dump(&db, now, io::stdout().lock())?;
+    Ok(())
+}
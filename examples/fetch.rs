@@ -0,0 +1,60 @@
+//! Download a `tzdata` file over plain HTTP and print the current offset of a zone.
+//!
+//! ```sh
+//! cargo run --example fetch -- http://example.com/tzdata Asia/Shanghai
+//! ```
+//!
+//! Only the download is platform-specific: in a browser, fetch the bytes with `fetch()` instead
+//! and hand them to [`ZoneInfoDb::from_bytes`] the same way.
+use std::{
+    io::{Read, Write},
+    net::TcpStream,
+    process::ExitCode,
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+use zoneinfo_db::ZoneInfoDb;
+
+fn main() -> ExitCode {
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    let [url, zone] = &args[..] else {
+        eprintln!("usage: fetch <http://host[:port]/path> <zone>");
+        return ExitCode::from(2);
+    };
+    match run(url, zone) {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(error) => {
+            eprintln!("error: {error}");
+            ExitCode::FAILURE
+        }
+    }
+}
+
+fn run(url: &str, zone: &str) -> Result<(), Box<dyn std::error::Error>> {
+    let db = ZoneInfoDb::from_bytes(http_get(url)?)?;
+    let Some(info) = db.zone_info(zone.as_bytes())? else {
+        return Err(format!("no zone {zone} in tzdata {}", db.version()).into());
+    };
+    let now = SystemTime::now().duration_since(UNIX_EPOCH)?.as_secs() as i64;
+    let offset = info.offset_at(now);
+    println!("{zone}: {} ({:+} s, dst: {})", offset.abbreviation, offset.seconds, offset.is_dst);
+    Ok(())
+}
+
+/// A minimal HTTP/1.0 GET, enough for a static file server.
+fn http_get(url: &str) -> Result<Vec<u8>, Box<dyn std::error::Error>> {
+    let rest = url.strip_prefix("http://").ok_or("only http:// URLs are supported")?;
+    let (host, path) =
+        rest.split_once('/').map_or((rest, "/"), |(host, _)| (host, &rest[host.len()..]));
+    let address = if host.contains(':') { host.to_string() } else { format!("{host}:80") };
+    let mut stream = TcpStream::connect(address)?;
+    write!(stream, "GET {path} HTTP/1.0\r\nHost: {host}\r\n\r\n")?;
+    let mut response = Vec::new();
+    stream.read_to_end(&mut response)?;
+    let split = response.windows(4).position(|x| x == b"\r\n\r\n").ok_or("malformed response")?;
+    let status = response.split(|&x| x == b'\n').next().unwrap_or_default();
+    if !status.windows(5).any(|x| x == b" 200 ") {
+        return Err(format!("HTTP {}", String::from_utf8_lossy(status).trim()).into());
+    }
+    Ok(response.split_off(split + 4))
+}
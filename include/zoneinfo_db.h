@@ -0,0 +1,59 @@
+#ifndef ZONEINFO_DB_H
+#define ZONEINFO_DB_H
+
+/* Generated with cbindgen from src/capi.rs, see cbindgen.toml. Do not edit by hand. */
+
+#include <stdarg.h>
+#include <stdbool.h>
+#include <stddef.h>
+#include <stdint.h>
+#include <stdlib.h>
+
+/**
+ * A `tzdata` file loaded into memory, with its header and indexes parsed once.
+ */
+typedef struct ZoneInfoDb ZoneInfoDb;
+
+#ifdef __cplusplus
+extern "C" {
+#endif // __cplusplus
+
+/**
+ * Load the `tzdata` file at `path`, detecting its flavor.
+ *
+ * Returns NULL if the file can't be read or parsed. Release the database with
+ * [`zoneinfo_db_free`].
+ *
+ * # Safety
+ *
+ * `path` must be NULL or a NUL-terminated string.
+ */
+ZoneInfoDb *zoneinfo_db_open(const char *path);
+
+/**
+ * Find the TZif data of the zone `name`, storing its length in `len`.
+ *
+ * Returns NULL if the zone isn't in the database. The data is borrowed from `db` and stays
+ * valid until [`zoneinfo_db_free`].
+ *
+ * # Safety
+ *
+ * `db` must be NULL or returned by [`zoneinfo_db_open`] and not freed, `name` NULL or a
+ * NUL-terminated string, and `len` NULL or valid for writes.
+ */
+const uint8_t *zoneinfo_db_find(const ZoneInfoDb *db, const char *name, size_t *len);
+
+/**
+ * Release a database returned by [`zoneinfo_db_open`]; NULL is ignored.
+ *
+ * # Safety
+ *
+ * `db` must be NULL or returned by [`zoneinfo_db_open`] and not already freed.
+ */
+void zoneinfo_db_free(ZoneInfoDb *db);
+
+#ifdef __cplusplus
+}  // extern "C"
+#endif  // __cplusplus
+
+#endif  /* ZONEINFO_DB_H */
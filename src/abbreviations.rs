@@ -0,0 +1,55 @@
+//! Lookup of zones by time zone abbreviation.
+use std::{collections::BTreeMap, sync::Arc};
+
+use crate::{TzIf, ZoneInfoDb};
+
+impl ZoneInfoDb {
+    /// Names of the zones whose TZif data has a local time type with the abbreviation, e.g.
+    /// `CET`, at any time in their history, in index order; the candidates for a timestamp
+    /// that only carries the abbreviation.
+    ///
+    /// Abbreviations are matched exactly, and aren't unique: `CST` is used in China, Cuba and
+    /// North America. The table of all zones is built on the first call, skipping zones whose
+    /// data fails to parse.
+    pub fn zones_using_abbreviation(&self, abbreviation: &str) -> &[Arc<[u8]>] {
+        let table = self.abbreviations.get_or_init(|| {
+            let mut table = BTreeMap::<_, Vec<_>>::new();
+            for index in self.indexes.timezones() {
+                let Some(Ok(tzif)) = self.tzdata(index).map(TzIf::new) else { continue };
+                let mut abbreviations: Vec<_> =
+                    tzif.local_time_types.into_iter().map(|x| x.abbreviation).collect();
+                abbreviations.sort_unstable();
+                abbreviations.dedup();
+                for abbreviation in abbreviations {
+                    table.entry(abbreviation).or_default().push(index.name.clone());
+                }
+            }
+            table
+        });
+        table.get(abbreviation).map_or(&[], Vec::as_slice)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fs::File;
+
+    use super::*;
+
+    #[test]
+    fn test_zones_using_abbreviation() {
+        let db = ZoneInfoDb::new_ohos(File::open("./tests/ohos/tzdata").unwrap()).unwrap();
+        let cet = db.zones_using_abbreviation("CET");
+        assert!(cet.iter().any(|x| &x[..] == b"Europe/Paris"));
+        assert!(cet.iter().any(|x| &x[..] == b"Africa/Algiers"));
+        assert!(!cet.iter().any(|x| &x[..] == b"Asia/Shanghai"));
+        assert!(cet.is_sorted());
+        let cst = db.zones_using_abbreviation("CST");
+        assert!(cst.iter().any(|x| &x[..] == b"Asia/Shanghai"));
+        assert!(cst.iter().any(|x| &x[..] == b"America/Chicago"));
+        assert!(db.zones_using_abbreviation("cet").is_empty());
+        assert!(db.zones_using_abbreviation("XYZ").is_empty());
+        // The table is built once and then shared.
+        assert!(core::ptr::eq(cet, db.zones_using_abbreviation("CET")));
+    }
+}
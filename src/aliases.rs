@@ -0,0 +1,295 @@
+//! Backward-compatibility zone names, such as `Asia/Calcutta` for `Asia/Kolkata`, behind the
+//! `aliases` feature.
+use alloc::{collections::BTreeMap, sync::Arc, vec::Vec};
+
+#[cfg(feature = "std")]
+use crate::ZoneInfoDb;
+use crate::{TzDataIndex, TzDataIndexes};
+
+/// Links of the tzdb `backward` file, `(alias, canonical)`.
+const BACKWARD: &[(&str, &str)] = &[
+    ("Africa/Asmera", "Africa/Nairobi"),
+    ("Africa/Timbuktu", "Africa/Abidjan"),
+    ("America/Atka", "America/Adak"),
+    ("America/Buenos_Aires", "America/Argentina/Buenos_Aires"),
+    ("America/Catamarca", "America/Argentina/Catamarca"),
+    ("America/Cordoba", "America/Argentina/Cordoba"),
+    ("America/Ensenada", "America/Tijuana"),
+    ("America/Fort_Wayne", "America/Indiana/Indianapolis"),
+    ("America/Godthab", "America/Nuuk"),
+    ("America/Indianapolis", "America/Indiana/Indianapolis"),
+    ("America/Jujuy", "America/Argentina/Jujuy"),
+    ("America/Knox_IN", "America/Indiana/Knox"),
+    ("America/Louisville", "America/Kentucky/Louisville"),
+    ("America/Mendoza", "America/Argentina/Mendoza"),
+    ("America/Porto_Acre", "America/Rio_Branco"),
+    ("America/Santa_Isabel", "America/Tijuana"),
+    ("America/Shiprock", "America/Denver"),
+    ("Antarctica/South_Pole", "Pacific/Auckland"),
+    ("Asia/Ashkhabad", "Asia/Ashgabat"),
+    ("Asia/Calcutta", "Asia/Kolkata"),
+    ("Asia/Chongqing", "Asia/Shanghai"),
+    ("Asia/Chungking", "Asia/Shanghai"),
+    ("Asia/Dacca", "Asia/Dhaka"),
+    ("Asia/Harbin", "Asia/Shanghai"),
+    ("Asia/Istanbul", "Europe/Istanbul"),
+    ("Asia/Kashgar", "Asia/Urumqi"),
+    ("Asia/Katmandu", "Asia/Kathmandu"),
+    ("Asia/Macao", "Asia/Macau"),
+    ("Asia/Rangoon", "Asia/Yangon"),
+    ("Asia/Saigon", "Asia/Ho_Chi_Minh"),
+    ("Asia/Tel_Aviv", "Asia/Jerusalem"),
+    ("Asia/Thimbu", "Asia/Thimphu"),
+    ("Asia/Ujung_Pandang", "Asia/Makassar"),
+    ("Asia/Ulan_Bator", "Asia/Ulaanbaatar"),
+    ("Atlantic/Faeroe", "Atlantic/Faroe"),
+    ("Australia/ACT", "Australia/Sydney"),
+    ("Australia/Canberra", "Australia/Sydney"),
+    ("Australia/LHI", "Australia/Lord_Howe"),
+    ("Australia/NSW", "Australia/Sydney"),
+    ("Australia/North", "Australia/Darwin"),
+    ("Australia/Queensland", "Australia/Brisbane"),
+    ("Australia/South", "Australia/Adelaide"),
+    ("Australia/Tasmania", "Australia/Hobart"),
+    ("Australia/Victoria", "Australia/Melbourne"),
+    ("Australia/West", "Australia/Perth"),
+    ("Australia/Yancowinna", "Australia/Broken_Hill"),
+    ("Brazil/Acre", "America/Rio_Branco"),
+    ("Brazil/DeNoronha", "America/Noronha"),
+    ("Brazil/East", "America/Sao_Paulo"),
+    ("Brazil/West", "America/Manaus"),
+    ("Canada/Atlantic", "America/Halifax"),
+    ("Canada/Central", "America/Winnipeg"),
+    ("Canada/Eastern", "America/Toronto"),
+    ("Canada/Mountain", "America/Edmonton"),
+    ("Canada/Newfoundland", "America/St_Johns"),
+    ("Canada/Pacific", "America/Vancouver"),
+    ("Canada/Saskatchewan", "America/Regina"),
+    ("Canada/Yukon", "America/Whitehorse"),
+    ("Chile/Continental", "America/Santiago"),
+    ("Chile/EasterIsland", "Pacific/Easter"),
+    ("Cuba", "America/Havana"),
+    ("Egypt", "Africa/Cairo"),
+    ("Eire", "Europe/Dublin"),
+    ("Etc/Greenwich", "Etc/GMT"),
+    ("Etc/UCT", "Etc/UTC"),
+    ("Etc/Universal", "Etc/UTC"),
+    ("Etc/Zulu", "Etc/UTC"),
+    ("Europe/Belfast", "Europe/London"),
+    ("Europe/Kiev", "Europe/Kyiv"),
+    ("Europe/Nicosia", "Asia/Nicosia"),
+    ("Europe/Tiraspol", "Europe/Chisinau"),
+    ("GB", "Europe/London"),
+    ("GB-Eire", "Europe/London"),
+    ("Greenwich", "Etc/GMT"),
+    ("Hongkong", "Asia/Hong_Kong"),
+    ("Iran", "Asia/Tehran"),
+    ("Israel", "Asia/Jerusalem"),
+    ("Jamaica", "America/Jamaica"),
+    ("Japan", "Asia/Tokyo"),
+    ("Kwajalein", "Pacific/Kwajalein"),
+    ("Libya", "Africa/Tripoli"),
+    ("Mexico/BajaNorte", "America/Tijuana"),
+    ("Mexico/BajaSur", "America/Mazatlan"),
+    ("Mexico/General", "America/Mexico_City"),
+    ("NZ", "Pacific/Auckland"),
+    ("NZ-CHAT", "Pacific/Chatham"),
+    ("Navajo", "America/Denver"),
+    ("PRC", "Asia/Shanghai"),
+    ("Pacific/Enderbury", "Pacific/Kanton"),
+    ("Pacific/Johnston", "Pacific/Honolulu"),
+    ("Pacific/Ponape", "Pacific/Pohnpei"),
+    ("Pacific/Samoa", "Pacific/Pago_Pago"),
+    ("Pacific/Truk", "Pacific/Chuuk"),
+    ("Pacific/Yap", "Pacific/Chuuk"),
+    ("Poland", "Europe/Warsaw"),
+    ("Portugal", "Europe/Lisbon"),
+    ("ROC", "Asia/Taipei"),
+    ("ROK", "Asia/Seoul"),
+    ("Singapore", "Asia/Singapore"),
+    ("Turkey", "Europe/Istanbul"),
+    ("UCT", "Etc/UTC"),
+    ("US/Alaska", "America/Anchorage"),
+    ("US/Aleutian", "America/Adak"),
+    ("US/Arizona", "America/Phoenix"),
+    ("US/Central", "America/Chicago"),
+    ("US/East-Indiana", "America/Indiana/Indianapolis"),
+    ("US/Eastern", "America/New_York"),
+    ("US/Hawaii", "Pacific/Honolulu"),
+    ("US/Indiana-Starke", "America/Indiana/Knox"),
+    ("US/Michigan", "America/Detroit"),
+    ("US/Mountain", "America/Denver"),
+    ("US/Pacific", "America/Los_Angeles"),
+    ("US/Samoa", "Pacific/Pago_Pago"),
+    ("Universal", "Etc/UTC"),
+    ("W-SU", "Europe/Moscow"),
+    ("Zulu", "Etc/UTC"),
+];
+
+/// Table of zone aliases, mapping each to its canonical name.
+///
+/// [`Aliases::default`] holds the common links of the tzdb `backward` file; entries can be
+/// added or removed at runtime.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Aliases {
+    links: BTreeMap<Arc<[u8]>, Arc<[u8]>>,
+}
+
+impl Default for Aliases {
+    fn default() -> Self {
+        let mut aliases = Self::new();
+        for (alias, canonical) in BACKWARD {
+            aliases.insert(*alias, *canonical);
+        }
+        aliases
+    }
+}
+
+impl Aliases {
+    /// Empty table.
+    pub fn new() -> Self {
+        Self { links: BTreeMap::new() }
+    }
+
+    /// Add or replace an alias.
+    pub fn insert(
+        &mut self,
+        alias: impl Into<Vec<u8>>,
+        canonical: impl Into<Vec<u8>>,
+    ) -> &mut Self {
+        self.links.insert(alias.into().into(), canonical.into().into());
+        self
+    }
+
+    /// Remove an alias, returning its canonical name.
+    pub fn remove(&mut self, alias: &[u8]) -> Option<Vec<u8>> {
+        self.links.remove(alias).map(|x| x.to_vec())
+    }
+
+    /// Canonical name of an alias, or `name` itself if it isn't one.
+    pub fn resolve<'a>(&'a self, name: &'a [u8]) -> &'a [u8] {
+        self.links.get(name).map_or(name, |x| x)
+    }
+
+    /// Whether `name` is an alias, e.g. a legacy name like `Asia/Calcutta`.
+    pub fn is_alias(&self, name: &[u8]) -> bool {
+        self.links.contains_key(name)
+    }
+
+    /// Aliases of a canonical name.
+    pub fn aliases_of<'a>(&'a self, canonical: &'a [u8]) -> impl Iterator<Item = &'a [u8]> {
+        self.links.iter().filter(move |(_, x)| ***x == *canonical).map(|(alias, _)| &**alias)
+    }
+
+    /// Replace the names of aliases and canonical zones with those of the matching entries of
+    /// `indexes`, so both share one allocation per name.
+    pub fn share_names(&mut self, indexes: &TzDataIndexes) {
+        let share = |name: Arc<[u8]>| indexes.find_timezone(&name).map_or(name, |x| x.name.clone());
+        self.links = core::mem::take(&mut self.links)
+            .into_iter()
+            .map(|(alias, canonical)| (share(alias), share(canonical)))
+            .collect();
+    }
+}
+
+/// Zone found by [`TzDataIndexes::lookup_with_aliases`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct AliasLookup<'a> {
+    pub index: &'a TzDataIndex,
+    /// Whether the zone was only found under another name than the one looked up, so a stored
+    /// ID should be migrated to `index.name` before vendors drop the link.
+    pub resolved_via_alias: bool,
+}
+
+impl TzDataIndexes {
+    /// Find a timezone by name, falling back to its canonical name if it's an alias, and then to
+    /// the other aliases of that canonical name, for databases that predate a rename.
+    pub fn find_timezone_with_aliases(
+        &self,
+        timezone: &[u8],
+        aliases: &Aliases,
+    ) -> Option<&TzDataIndex> {
+        self.lookup_with_aliases(timezone, aliases).map(|x| x.index)
+    }
+
+    /// Same as [`TzDataIndexes::find_timezone_with_aliases`], telling whether the zone was only
+    /// found through the alias table. Such lookups emit a `tracing` warning.
+    pub fn lookup_with_aliases(
+        &self,
+        timezone: &[u8],
+        aliases: &Aliases,
+    ) -> Option<AliasLookup<'_>> {
+        if let Some(index) = self.find_timezone(timezone) {
+            return Some(AliasLookup { index, resolved_via_alias: false });
+        }
+        let canonical = aliases.resolve(timezone);
+        let index = self
+            .find_timezone(canonical)
+            .or_else(|| aliases.aliases_of(canonical).find_map(|x| self.find_timezone(x)))?;
+        trace!(warn!(
+            zone = %timezone.escape_ascii(),
+            resolved = %index.name.escape_ascii(),
+            "zone only found through an alias"
+        ));
+        Some(AliasLookup { index, resolved_via_alias: true })
+    }
+}
+
+#[cfg(feature = "std")]
+impl ZoneInfoDb {
+    /// Get the timezone data of a zone, looked up as by
+    /// [`TzDataIndexes::find_timezone_with_aliases`].
+    pub fn get_with_aliases(&self, tz_name: &[u8], aliases: &Aliases) -> Option<&[u8]> {
+        self.tzdata(self.indexes.find_timezone_with_aliases(tz_name, aliases)?)
+    }
+}
+
+#[cfg(all(test, feature = "std"))]
+mod tests {
+    use std::fs::File;
+
+    use super::*;
+
+    #[test]
+    fn test_aliases() {
+        let mut aliases = Aliases::default();
+        assert_eq!(aliases.resolve(b"Asia/Calcutta"), b"Asia/Kolkata");
+        assert_eq!(aliases.resolve(b"Asia/Kolkata"), b"Asia/Kolkata");
+        assert!(BACKWARD.is_sorted());
+
+        // The OpenHarmony database has no backward links.
+        let db = ZoneInfoDb::new_ohos(File::open("./tests/ohos/tzdata").unwrap()).unwrap();
+        assert_eq!(db.get(b"PRC"), None);
+        assert_eq!(db.get_with_aliases(b"PRC", &aliases), db.get(b"Asia/Shanghai"));
+        assert_eq!(db.get_with_aliases(b"Asia/Nowhere", &aliases), None);
+
+        // 2021a predates the rename of Europe/Kiev.
+        let db = ZoneInfoDb::new_android(File::open("./tests/android/tzdata").unwrap()).unwrap();
+        assert_eq!(db.get(b"Europe/Kyiv"), None);
+        assert_eq!(db.get_with_aliases(b"Europe/Kyiv", &aliases), db.get(b"Europe/Kiev"));
+
+        aliases.insert("Asia/Nowhere", "Asia/Tokyo");
+        assert_eq!(db.get_with_aliases(b"Asia/Nowhere", &aliases), db.get(b"Asia/Tokyo"));
+        assert_eq!(aliases.remove(b"Asia/Nowhere").as_deref(), Some(&b"Asia/Tokyo"[..]));
+        assert!(Aliases::new().resolve(b"PRC") == b"PRC");
+        assert!(aliases.is_alias(b"Asia/Calcutta") && !aliases.is_alias(b"Asia/Kolkata"));
+
+        let lookup = db.indexes().lookup_with_aliases(b"Asia/Shanghai", &aliases).unwrap();
+        assert!(!lookup.resolved_via_alias);
+        // Android 2021a still ships the link itself.
+        let lookup = db.indexes().lookup_with_aliases(b"Asia/Calcutta", &aliases).unwrap();
+        assert_eq!(
+            (&*lookup.index.name, lookup.resolved_via_alias),
+            (&b"Asia/Calcutta"[..], false)
+        );
+        let lookup = db.indexes().lookup_with_aliases(b"Europe/Kyiv", &aliases).unwrap();
+        assert_eq!((&*lookup.index.name, lookup.resolved_via_alias), (&b"Europe/Kiev"[..], true));
+        assert_eq!(db.indexes().lookup_with_aliases(b"Asia/Nowhere", &aliases), None);
+
+        aliases.share_names(db.indexes());
+        let index = db.indexes().find_timezone(b"Asia/Kolkata").unwrap();
+        let canonical = &aliases.links[&b"Asia/Calcutta"[..]];
+        assert!(Arc::ptr_eq(canonical, &index.name));
+        assert_eq!(aliases.resolve(b"Europe/Kyiv"), b"Europe/Kyiv");
+    }
+}
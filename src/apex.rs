@@ -0,0 +1,91 @@
+//! Reading the `tzdata` file out of a zip archive laid out like an APEX, behind the `zip`
+//! feature.
+use std::{
+    fs::File,
+    io::{self, Read},
+    path::Path,
+};
+
+use zip::ZipArchive;
+
+use crate::{MAX_UNTRUSTED_LEN, Result, ZoneInfoDb, ZoneInfoError};
+
+impl From<zip::result::ZipError> for ZoneInfoError {
+    fn from(err: zip::result::ZipError) -> Self {
+        match err {
+            zip::result::ZipError::Io(err) => ZoneInfoError::Io(err),
+            err => ZoneInfoError::Zip(err),
+        }
+    }
+}
+
+impl ZoneInfoDb {
+    /// Load the `tzdata` file stored as the `etc/tz/tzdata` entry of a zip archive, without
+    /// extracting it to disk, e.g. a test build of a tzdata APEX.
+    ///
+    /// Real `com.android.tzdata` APEXes and CAPEXes keep their files inside an
+    /// `apex_payload.img` filesystem image, which isn't read: they fail with
+    /// [`io::ErrorKind::NotFound`], as do archives without the entry. The file is checked as by
+    /// [`ZoneInfoDb::open_untrusted_android`].
+    pub fn from_zip(path: impl AsRef<Path>) -> Result<Self> {
+        let mut archive = ZipArchive::new(File::open(path)?)?;
+        let Some(name) = archive
+            .file_names()
+            .find(|x| *x == "etc/tz/tzdata" || x.ends_with("/etc/tz/tzdata"))
+            .map(str::to_string)
+        else {
+            let message = if archive.index_for_name("apex_payload.img").is_some() {
+                "etc/tz/tzdata is inside apex_payload.img, which isn't supported"
+            } else {
+                "no etc/tz/tzdata in zip archive"
+            };
+            return Err(io::Error::new(io::ErrorKind::NotFound, message).into());
+        };
+        let mut data = Vec::new();
+        archive.by_name(&name)?.take(MAX_UNTRUSTED_LEN as u64 + 1).read_to_end(&mut data)?;
+        Self::open_untrusted_android(&data)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::Write;
+
+    use zip::{ZipWriter, write::SimpleFileOptions};
+
+    use super::*;
+
+    #[test]
+    fn test_from_zip() {
+        let tzdata = std::fs::read("./tests/android/tzdata").unwrap();
+        let dir = std::env::temp_dir().join(format!("zoneinfo-db-apex-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let write_apex = |name: &str, entries: &[(&str, &[u8])]| {
+            let path = dir.join(name);
+            let mut zip = ZipWriter::new(File::create(&path).unwrap());
+            for (name, data) in entries {
+                zip.start_file(*name, SimpleFileOptions::default()).unwrap();
+                zip.write_all(data).unwrap();
+            }
+            zip.finish().unwrap();
+            path
+        };
+
+        let apex = write_apex(
+            "com.android.tzdata.apex",
+            &[("apex_manifest.pb", b""), ("etc/tz/tzdata", &tzdata)],
+        );
+        let db = ZoneInfoDb::from_zip(apex).unwrap();
+        assert_eq!(db.header().version, *b"2021a");
+        assert_eq!(db.get(b"Asia/Shanghai").unwrap().len(), 573);
+
+        let image = write_apex("image.apex", &[("apex_payload.img", b"")]);
+        let err = ZoneInfoDb::from_zip(image);
+        let not_apex = ZoneInfoDb::from_zip("./tests/android/tzdata");
+        std::fs::remove_dir_all(&dir).unwrap();
+        assert!(
+            matches!(err, Err(ZoneInfoError::Io(err)) if err.kind() == io::ErrorKind::NotFound)
+        );
+        assert!(matches!(not_apex, Err(ZoneInfoError::Zip(_))));
+    }
+}
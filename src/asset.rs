@@ -0,0 +1,40 @@
+//! Reading `tzdata` bundled in the assets of an Android app.
+use std::{
+    borrow::Cow,
+    cell::RefCell,
+    io::{Read, Seek, SeekFrom},
+};
+
+use ndk::asset::Asset;
+
+use crate::{Result, TzDataSource};
+
+/// A [`TzDataSource`] over an opened [`Asset`], e.g. from
+/// `AssetManager::open(c"tzdata")`, so a `tzdata` file bundled in the APK can be read without
+/// copying it to disk first.
+#[derive(Debug)]
+pub struct AssetSource {
+    asset: RefCell<Asset>,
+}
+
+impl AssetSource {
+    /// Wrap an opened asset.
+    pub fn new(asset: Asset) -> Self {
+        Self { asset: RefCell::new(asset) }
+    }
+
+    /// Get the asset back.
+    pub fn into_inner(self) -> Asset {
+        self.asset.into_inner()
+    }
+}
+
+impl TzDataSource for AssetSource {
+    fn read_at(&self, offset: u64, len: usize) -> Result<Cow<'_, [u8]>> {
+        let mut asset = self.asset.borrow_mut();
+        asset.seek(SeekFrom::Start(offset))?;
+        let mut buffer = vec![0; len];
+        asset.read_exact(&mut buffer)?;
+        Ok(Cow::Owned(buffer))
+    }
+}
@@ -0,0 +1,230 @@
+//! Inspect `tzdata` files of Android and OpenHarmony.
+use std::{
+    fmt::Write as _,
+    io::{self, Write},
+    process::ExitCode,
+};
+
+use zoneinfo_db::{TzDataFlavor, TzDataHeader, TzDataIndexes, ZoneInfoDb, ZoneInfoError, ZoneTab};
+
+const USAGE: &str = "\
+usage: zoneinfo-db [--json] [--verbose] <command> <tzdata>
+
+commands:
+    header            print the header and detected flavor
+    list              list zones with the offset and length of their data, and with
+                      --verbose their country and zone.tab comment
+    extract <zone>    write the TZif data of a zone to stdout
+    show <zone>       print the transitions of a zone with their local times and offsets
+    verify            check the layout, index, zone.tab and TZif data of every zone, and
+                      print the CRC-32 of the file
+    canonicalize      write a copy with sorted entries, deduplicated data and no padding
+                      to stdout, byte-identical for databases with the same contents";
+
+fn main() -> ExitCode {
+    let mut args: Vec<String> = std::env::args().skip(1).collect();
+    let json = args.iter().position(|x| x == "--json").map(|i| args.remove(i)).is_some();
+    let verbose = args.iter().position(|x| x == "--verbose").map(|i| args.remove(i)).is_some();
+    let result = match args.iter().map(String::as_str).collect::<Vec<_>>()[..] {
+        ["header", path] => header(path, json),
+        ["list", path] => list(path, json, verbose),
+        ["extract", zone, path] if !json => extract(path, zone),
+        ["show", zone, path] if !json => show(path, zone),
+        ["verify", path] => verify(path, json),
+        ["canonicalize", path] if !json => canonicalize(path),
+        _ => {
+            eprintln!("{USAGE}");
+            return ExitCode::from(2);
+        }
+    };
+    match result {
+        Ok(true) => ExitCode::SUCCESS,
+        Ok(false) => ExitCode::FAILURE,
+        Err(err) => {
+            eprintln!("zoneinfo-db: {err}");
+            ExitCode::FAILURE
+        }
+    }
+}
+
+/// Read a `tzdata` file, detecting its flavor.
+fn open(path: &str) -> Result<(Vec<u8>, TzDataHeader, TzDataIndexes, TzDataFlavor), ZoneInfoError> {
+    let data = std::fs::read(path)?;
+    let header = TzDataHeader::from_bytes(&data)?;
+    let (indexes, flavor) = TzDataIndexes::from_bytes_auto(&data, &header)?;
+    Ok((data, header, indexes, flavor))
+}
+
+fn flavor_name(flavor: TzDataFlavor) -> &'static str {
+    match flavor {
+        TzDataFlavor::Android => "android",
+        TzDataFlavor::Ohos => "ohos",
+    }
+}
+
+/// Quote a string as a JSON string.
+fn json_string(text: &str) -> String {
+    let mut out = String::from("\"");
+    for c in text.chars() {
+        match c {
+            '"' => out.push_str("\\\""),
+            '\\' => out.push_str("\\\\"),
+            c if c.is_control() => write!(out, "\\u{:04x}", c as u32).unwrap(),
+            c => out.push(c),
+        }
+    }
+    out.push('"');
+    out
+}
+
+fn header(path: &str, json: bool) -> Result<bool, ZoneInfoError> {
+    let (_, header, indexes, flavor) = open(path)?;
+    let version = String::from_utf8_lossy(&header.version);
+    let fields = [
+        ("index_offset", header.index_offset.to_string()),
+        ("data_offset", header.data_offset.to_string()),
+        ("zonetab_offset", header.zonetab_offset.to_string()),
+        ("zones", indexes.timezones().len().to_string()),
+    ];
+    if json {
+        let mut out = format!(
+            "{{\"version\":{},\"flavor\":{}",
+            json_string(&version),
+            json_string(flavor_name(flavor))
+        );
+        for (key, value) in fields {
+            write!(out, ",\"{key}\":{value}").unwrap();
+        }
+        println!("{out}}}");
+    } else {
+        println!("version: {version}");
+        println!("flavor: {}", flavor_name(flavor));
+        for (key, value) in fields {
+            println!("{key}: {value}");
+        }
+    }
+    Ok(true)
+}
+
+fn list(path: &str, json: bool, verbose: bool) -> Result<bool, ZoneInfoError> {
+    let (data, header, indexes, _) = open(path)?;
+    let zone_tab = if verbose {
+        ZoneTab::new(data.get(header.zonetab_offset as usize..).unwrap_or_default())?
+    } else {
+        ZoneTab::default()
+    };
+    let mut stdout = io::stdout().lock();
+    if json {
+        let entries: Vec<_> = indexes
+            .timezones()
+            .iter()
+            .map(|x| {
+                let mut entry = format!(
+                    "{{\"name\":{},\"offset\":{},\"length\":{}",
+                    json_string(&String::from_utf8_lossy(&x.name)),
+                    x.offset,
+                    x.length
+                );
+                if verbose {
+                    let string = |x: Option<&str>| x.map_or("null".to_string(), json_string);
+                    let country = string(zone_tab.country_for_zone(&x.name));
+                    let comment = string(zone_tab.comment_for(&x.name));
+                    write!(entry, ",\"country\":{country},\"comment\":{comment}").unwrap();
+                }
+                entry + "}"
+            })
+            .collect();
+        writeln!(stdout, "[{}]", entries.join(","))?;
+    } else {
+        for index in indexes.timezones() {
+            let name = String::from_utf8_lossy(&index.name);
+            write!(stdout, "{name}\t{}\t{}", index.offset, index.length)?;
+            if verbose {
+                let country = zone_tab.country_for_zone(&index.name).unwrap_or("-");
+                let comment = zone_tab.comment_for(&index.name).unwrap_or("");
+                write!(stdout, "\t{country}\t{comment}")?;
+            }
+            writeln!(stdout)?;
+        }
+    }
+    Ok(true)
+}
+
+fn extract(path: &str, zone: &str) -> Result<bool, ZoneInfoError> {
+    let (data, header, indexes, _) = open(path)?;
+    let Some(index) = indexes.find_timezone(zone.as_bytes()) else {
+        eprintln!("zoneinfo-db: no zone named {zone}");
+        return Ok(false);
+    };
+    let tzif = indexes.find_tzdata(io::Cursor::new(data), &header, index)?;
+    let mut stdout = io::stdout().lock();
+    stdout.write_all(&tzif)?;
+    stdout.flush()?;
+    Ok(true)
+}
+
+fn show(path: &str, zone: &str) -> Result<bool, ZoneInfoError> {
+    let (_, db) = check(&std::fs::read(path)?)?;
+    let Some(zone_info) = db.zone_info(zone.as_bytes())? else {
+        eprintln!("zoneinfo-db: no zone named {zone}");
+        return Ok(false);
+    };
+    let mut stdout = io::stdout().lock();
+    stdout.write_all(zone_info.describe().as_bytes())?;
+    stdout.flush()?;
+    Ok(true)
+}
+
+fn canonicalize(path: &str) -> Result<bool, ZoneInfoError> {
+    let (flavor, db) = check(&std::fs::read(path)?)?;
+    let mut stdout = io::stdout().lock();
+    stdout.write_all(&db.canonicalize(flavor)?)?;
+    stdout.flush()?;
+    Ok(true)
+}
+
+fn verify(path: &str, json: bool) -> Result<bool, ZoneInfoError> {
+    let data = std::fs::read(path)?;
+    // A file failing the checks done when loading can't be verified further.
+    let (flavor, report, errors) = match check(&data) {
+        Ok((flavor, db)) => {
+            let report = db.verify();
+            let errors: Vec<_> = report.issues.iter().map(ToString::to_string).collect();
+            (Some(flavor), Some(report), errors)
+        }
+        Err(err) => (None, None, vec![err.to_string()]),
+    };
+    if json {
+        let errors: Vec<_> = errors.iter().map(|x| json_string(x)).collect();
+        let flavor = flavor.map_or("null".to_string(), |x| json_string(flavor_name(x)));
+        let zones = report.as_ref().map_or(0, |x| x.zones);
+        let checksum = report.as_ref().map_or("null".to_string(), |x| x.checksum.to_string());
+        println!(
+            "{{\"ok\":{},\"flavor\":{flavor},\"zones\":{zones},\"checksum\":{checksum},\"errors\":[{}]}}",
+            errors.is_empty(),
+            errors.join(",")
+        );
+    } else {
+        for err in &errors {
+            println!("error: {err}");
+        }
+        if let (Some(flavor), Some(report)) = (flavor, &report) {
+            if report.is_ok() {
+                println!("ok: {} zones, {} flavor", report.zones, flavor_name(flavor));
+            }
+            println!("crc32: {:08x}", report.checksum);
+        }
+    }
+    Ok(errors.is_empty())
+}
+
+/// Load a `tzdata` file with all checks enabled.
+fn check(data: &[u8]) -> Result<(TzDataFlavor, ZoneInfoDb), ZoneInfoError> {
+    let header = TzDataHeader::from_bytes(data)?;
+    let (_, flavor) = TzDataIndexes::from_bytes_auto(data, &header)?;
+    let db = match flavor {
+        TzDataFlavor::Android => ZoneInfoDb::open_untrusted_android(data)?,
+        TzDataFlavor::Ohos => ZoneInfoDb::open_untrusted_ohos(data)?,
+    };
+    Ok((flavor, db))
+}
@@ -0,0 +1,416 @@
+//! Writer of `tzdata` files.
+use alloc::{collections::BTreeMap, sync::Arc, vec::Vec};
+#[cfg(feature = "std")]
+use std::{fs, io::Write, path::Path};
+
+use crate::{
+    Result, SIZEOF_HEADER, TzDataFlavor, TzDataFormatVersion, TzDataHeader, TzDataIndex,
+    ZoneInfoError,
+};
+
+/// Builder of a `tzdata` file from the TZif data of zones and a zone table.
+///
+/// Zones are written sorted by name, with their data in the same order.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TzDataBuilder {
+    version: [u8; 5],
+    zones: BTreeMap<Vec<u8>, Vec<u8>>,
+    zone_tab: Vec<u8>,
+    dedup: bool,
+    strict_android: bool,
+}
+
+impl TzDataBuilder {
+    /// Builder of a `tzdata` file of the given tzdb version, e.g. `*b"2024a"`.
+    pub fn new(version: [u8; 5]) -> Self {
+        Self {
+            version,
+            zones: BTreeMap::new(),
+            zone_tab: Vec::new(),
+            dedup: false,
+            strict_android: false,
+        }
+    }
+
+    /// Builder with every zone of a `/usr/share/zoneinfo`-style directory tree, named by their
+    /// path relative to `dir`, and its `zone.tab` if there is one.
+    ///
+    /// Files that don't start with the TZif magic are skipped, as are the `posix` and `right`
+    /// trees and the `posixrules` and `localtime` links. Symbolic links to files are read as
+    /// copies of their target; those to directories aren't followed.
+    #[cfg(feature = "std")]
+    pub fn from_zoneinfo_dir(dir: impl AsRef<Path>, version: [u8; 5]) -> Result<Self> {
+        let dir = dir.as_ref();
+        let mut builder = Self::new(version);
+        builder.add_zoneinfo_dir(dir, "")?;
+        match fs::read(dir.join("zone.tab")) {
+            Ok(text) => builder.zone_tab(text),
+            Err(err) if err.kind() == std::io::ErrorKind::NotFound => &mut builder,
+            Err(err) => return Err(err.into()),
+        };
+        Ok(builder)
+    }
+
+    #[cfg(feature = "std")]
+    fn add_zoneinfo_dir(&mut self, dir: &Path, prefix: &str) -> Result<()> {
+        for entry in fs::read_dir(dir)? {
+            let entry = entry?;
+            let Ok(file_name) = entry.file_name().into_string() else {
+                continue;
+            };
+            if prefix.is_empty()
+                && matches!(file_name.as_str(), "posix" | "right" | "posixrules" | "localtime")
+            {
+                continue;
+            }
+            let name = alloc::format!("{prefix}{file_name}");
+            let path = entry.path();
+            if entry.file_type()?.is_dir() {
+                self.add_zoneinfo_dir(&path, &alloc::format!("{name}/"))?;
+            } else if fs::metadata(&path)?.is_file() {
+                let data = fs::read(&path)?;
+                if data.starts_with(b"TZif") {
+                    self.zone(name, data);
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Add a zone, replacing any previous one of the same name.
+    pub fn zone(&mut self, name: impl Into<Vec<u8>>, tzif: impl Into<Vec<u8>>) -> &mut Self {
+        self.zones.insert(name.into(), tzif.into());
+        self
+    }
+
+    /// Set the text of the zonetab section, empty by default.
+    pub fn zone_tab(&mut self, text: impl Into<Vec<u8>>) -> &mut Self {
+        self.zone_tab = text.into();
+        self
+    }
+
+    /// Keep only the zones whose name satisfies `keep`, along with their zonetab rows.
+    ///
+    /// Comments and blank lines of the zonetab are kept as they are.
+    pub fn filter(&mut self, mut keep: impl FnMut(&[u8]) -> bool) -> &mut Self {
+        self.zones.retain(|name, _| keep(name));
+        let mut zone_tab = Vec::with_capacity(self.zone_tab.len());
+        for line in self.zone_tab.split_inclusive(|&x| x == b'\n') {
+            let row = line.strip_suffix(b"\n").unwrap_or(line);
+            let row = row.strip_suffix(b"\r").unwrap_or(row);
+            let zone = row.split(|&x| x == b'\t').nth(2);
+            if row.is_empty() || row[0] == b'#' || zone.is_none_or(|x| self.zones.contains_key(x)) {
+                zone_tab.extend_from_slice(line);
+            }
+        }
+        self.zone_tab = zone_tab;
+        self
+    }
+
+    /// Keep only the zones matching any of the glob `patterns`, e.g. `["Asia/*", "UTC"]`, along
+    /// with their zonetab rows; see [`TzDataBuilder::filter`].
+    ///
+    /// `*` matches any run of bytes, including `/`, and `?` matches a single byte.
+    pub fn subset(&mut self, patterns: &[&str]) -> &mut Self {
+        self.filter(|name| patterns.iter().any(|pattern| glob_match(pattern.as_bytes(), name)))
+    }
+
+    /// Whether to store identical TZif data once and point every zone using it at that copy, as
+    /// the Android tzdata generator does; off by default.
+    pub fn dedup(&mut self, dedup: bool) -> &mut Self {
+        self.dedup = dedup;
+        self
+    }
+
+    /// Whether to reject zones whose TZif data Android's reader, libcore's
+    /// `ZoneInfo.readTimeZone`, fails on; off by default.
+    ///
+    /// It reads the version 1 data block, requiring at most 2000 transitions, 1 to 256 local
+    /// time types, transitions to defined types and DST flags of 0 or 1. Violations fail the
+    /// build with [`ZoneInfoError::AndroidConstraint`] naming the zone.
+    pub fn strict_android(&mut self, strict: bool) -> &mut Self {
+        self.strict_android = strict;
+        self
+    }
+
+    /// Build the `tzdata` file of the given flavor.
+    ///
+    /// Names must be non-empty printable ASCII shorter than the 40-byte name slot, and the file
+    /// must fit 32-bit offsets.
+    pub fn build(&self, flavor: TzDataFlavor) -> Result<Vec<u8>> {
+        if self.strict_android {
+            for (name, tzif) in &self.zones {
+                check_android_tzif(tzif).map_err(|reason| ZoneInfoError::AndroidConstraint {
+                    zone: Arc::from(&name[..]),
+                    reason,
+                })?;
+            }
+        }
+        let format = TzDataFormatVersion::from(flavor);
+        // Position of the data of each zone, and the distinct data in order.
+        let mut positions = Vec::with_capacity(self.zones.len());
+        let mut blobs = Vec::with_capacity(self.zones.len());
+        let mut seen = BTreeMap::new();
+        let mut data_len: usize = 0;
+        for tzif in self.zones.values() {
+            let position = match seen.get(&tzif[..]) {
+                Some(&position) if self.dedup => position,
+                _ => {
+                    let position = data_len;
+                    seen.entry(&tzif[..]).or_insert(position);
+                    blobs.push(&tzif[..]);
+                    data_len = data_len.checked_add(tzif.len()).ok_or(ZoneInfoError::TooLarge)?;
+                    position
+                }
+            };
+            positions.push(position);
+        }
+        let index_len = self.zones.len().checked_mul(format.entry_size());
+        let data_offset = index_len.and_then(|x| x.checked_add(SIZEOF_HEADER));
+        let data_offset = data_offset.ok_or(ZoneInfoError::TooLarge)?;
+        let zonetab_offset = data_offset.checked_add(data_len).ok_or(ZoneInfoError::TooLarge)?;
+        let offset = |x: usize| u32::try_from(x).map_err(|_| ZoneInfoError::TooLarge);
+
+        let mut out = Vec::with_capacity(zonetab_offset + self.zone_tab.len());
+        let header = TzDataHeader {
+            version: self.version,
+            index_offset: offset(SIZEOF_HEADER)?,
+            data_offset: offset(data_offset)?,
+            zonetab_offset: offset(zonetab_offset)?,
+        };
+        out.extend_from_slice(&header.to_bytes());
+        for ((name, tzif), position) in self.zones.iter().zip(positions) {
+            let index = TzDataIndex {
+                name: Arc::from(&name[..]),
+                offset: offset(position)?,
+                length: offset(tzif.len())?,
+                // The legacy raw UTC offset, which current Android releases always write as 0.
+                raw_utc_offset: (flavor == TzDataFlavor::Android).then_some(0),
+            };
+            out.extend_from_slice(&index.to_bytes(format)?);
+        }
+        for tzif in blobs {
+            out.extend_from_slice(tzif);
+        }
+        out.extend_from_slice(&self.zone_tab);
+        Ok(out)
+    }
+
+    /// Build the `tzdata` file of the given flavor and write it, see [`TzDataBuilder::build`].
+    #[cfg(feature = "std")]
+    pub fn write<W: Write>(&self, flavor: TzDataFlavor, mut writer: W) -> Result<()> {
+        writer.write_all(&self.build(flavor)?)?;
+        Ok(writer.flush()?)
+    }
+}
+
+/// Check the version 1 data block of a zone as libcore's `ZoneInfo.readTimeZone` reads it.
+fn check_android_tzif(tzif: &[u8]) -> core::result::Result<(), &'static str> {
+    /// Limits of libcore, against allocating memory for corrupt data.
+    const MAX_TRANSITIONS: usize = 2000;
+    const MAX_TYPES: usize = 256;
+
+    if !tzif.starts_with(b"TZif") {
+        return Err("invalid TZif magic");
+    }
+    let count = |i: usize| crate::be_u32_at(tzif, 20 + 4 * i).map(|x| x as usize);
+    let (Some(timecnt), Some(typecnt)) = (count(3), count(4)) else {
+        return Err("truncated TZif header");
+    };
+    if timecnt > MAX_TRANSITIONS {
+        return Err("more than 2000 transitions");
+    }
+    if !(1..=MAX_TYPES).contains(&typecnt) {
+        return Err("not 1 to 256 local time types");
+    }
+    let types_start = 44 + timecnt * 5;
+    let transition_types =
+        tzif.get(44 + timecnt * 4..types_start).ok_or("truncated transitions")?;
+    if transition_types.iter().any(|&x| usize::from(x) >= typecnt) {
+        return Err("transition to an undefined local time type");
+    }
+    let types =
+        tzif.get(types_start..types_start + typecnt * 6).ok_or("truncated local time types")?;
+    if types.chunks_exact(6).any(|x| x[4] > 1) {
+        return Err("DST flag other than 0 or 1");
+    }
+    Ok(())
+}
+
+/// Match `name` against a glob of `*` and `?` wildcards.
+fn glob_match(pattern: &[u8], name: &[u8]) -> bool {
+    let (mut p, mut n) = (0, 0);
+    // Position of the last `*` and of the name byte it currently extends to.
+    let mut star = None;
+    while n < name.len() {
+        match pattern.get(p) {
+            Some(b'*') => {
+                star = Some((p, n));
+                p += 1;
+            }
+            Some(&x) if x == b'?' || x == name[n] => {
+                p += 1;
+                n += 1;
+            }
+            _ => {
+                let Some((star_p, star_n)) = star else { return false };
+                star = Some((star_p, star_n + 1));
+                p = star_p + 1;
+                n = star_n + 1;
+            }
+        }
+    }
+    pattern[p..].iter().all(|&x| x == b'*')
+}
+
+#[cfg(all(test, feature = "std"))]
+mod tests {
+    use std::fs::File;
+
+    use super::*;
+    use crate::ZoneInfoDb;
+    use crate::testutil::fixture_builder;
+
+    #[test]
+    fn test_builder_roundtrip() {
+        // The OpenHarmony file lays out data in name order, as the builder does.
+        let original = std::fs::read("./tests/ohos/tzdata").unwrap();
+        let db = ZoneInfoDb::new_ohos(&original[..]).unwrap();
+        let builder = fixture_builder(&db, db.header().version);
+        assert!(builder.build(TzDataFlavor::Ohos).unwrap() == original);
+
+        let db = ZoneInfoDb::new_android(File::open("./tests/android/tzdata").unwrap()).unwrap();
+        let builder = fixture_builder(&db, db.header().version);
+        let built = builder.build(TzDataFlavor::Android).unwrap();
+        let built = ZoneInfoDb::open_untrusted_android(&built).unwrap();
+        assert!(built.iter().eq(db.iter()));
+    }
+
+    #[test]
+    fn test_builder_dedup() {
+        // Android already shares identical data, so rebuilding it deduplicated keeps its size.
+        let original = std::fs::read("./tests/android/tzdata").unwrap();
+        let db = ZoneInfoDb::new_android(&original[..]).unwrap();
+        let mut builder = fixture_builder(&db, db.header().version);
+        let built = builder.dedup(true).build(TzDataFlavor::Android).unwrap();
+        assert_eq!(built.len(), original.len());
+        let built = ZoneInfoDb::open_untrusted_android(&built).unwrap();
+        assert!(built.iter().eq(db.iter()));
+        let shanghai = built.indexes().find_timezone(b"Asia/Shanghai").unwrap();
+        let prc = built.indexes().find_timezone(b"PRC").unwrap();
+        assert_eq!((shanghai.offset, shanghai.length), (prc.offset, prc.length));
+        #[cfg(all(feature = "tzif", feature = "zonetab"))]
+        assert!(built.verify().is_ok());
+
+        let original = std::fs::read("./tests/ohos/tzdata").unwrap();
+        let db = ZoneInfoDb::new_ohos(&original[..]).unwrap();
+        let mut builder = fixture_builder(&db, db.header().version);
+        let built = builder.dedup(true).build(TzDataFlavor::Ohos).unwrap();
+        assert!(built.len() < original.len());
+        assert!(ZoneInfoDb::open_untrusted_ohos(&built).unwrap().iter().eq(db.iter()));
+    }
+
+    #[test]
+    fn test_builder_from_zoneinfo_dir() {
+        let dir = std::env::temp_dir().join(format!("zoneinfo-db-builder-{}", std::process::id()));
+        let original = std::fs::read("./tests/ohos/tzdata").unwrap();
+        let db = ZoneInfoDb::new_ohos(&original[..]).unwrap();
+        db.extract_all(&dir).unwrap();
+        std::fs::write(dir.join("tzdata.zi"), "# version 2024a\n").unwrap();
+        std::fs::create_dir_all(dir.join("right/Asia")).unwrap();
+        std::fs::write(dir.join("right/Asia/Leap"), db.get(b"Asia/Tokyo").unwrap()).unwrap();
+
+        let builder = TzDataBuilder::from_zoneinfo_dir(&dir, db.header().version).unwrap();
+        std::fs::remove_dir_all(&dir).unwrap();
+        assert!(builder.build(TzDataFlavor::Ohos).unwrap() == original);
+    }
+
+    #[test]
+    fn test_builder_subset() {
+        assert!(glob_match(b"Asia/*", b"Asia/Shanghai"));
+        assert!(glob_match(b"America/*/*", b"America/Argentina/Salta"));
+        assert!(glob_match(b"Etc/GMT?1", b"Etc/GMT+1"));
+        assert!(!glob_match(b"Asia/*", b"Europe/Paris"));
+        assert!(!glob_match(b"UTC", b"UTC0"));
+
+        let db = ZoneInfoDb::new_android(File::open("./tests/android/tzdata").unwrap()).unwrap();
+        let mut builder = fixture_builder(&db, db.header().version);
+        builder
+            .zone_tab("# comment\nCN\t+3114+12128\tAsia/Shanghai\nFR\t+4852+00220\tEurope/Paris\n")
+            .subset(&["Asia/*", "UTC"]);
+        let built = builder.build(TzDataFlavor::Android).unwrap();
+        let built = ZoneInfoDb::open_untrusted_android(&built).unwrap();
+        let asia = db.iter().filter(|(name, _)| name.starts_with(b"Asia/")).count();
+        assert_eq!(built.zone_count(), asia + 1);
+        assert_eq!(built.get(b"UTC").unwrap(), db.get(b"UTC").unwrap());
+        assert!(built.get(b"Europe/Paris").is_none());
+        #[cfg(feature = "zonetab")]
+        {
+            let zone_tab = built.zone_tab().unwrap();
+            assert_eq!(zone_tab.entries().len(), 1);
+            assert_eq!(zone_tab.country_for_zone(b"Asia/Shanghai"), Some("CN"));
+        }
+    }
+
+    #[test]
+    fn test_builder_strict_android() {
+        let db = ZoneInfoDb::new_android(File::open("./tests/android/tzdata").unwrap()).unwrap();
+        let mut builder = fixture_builder(&db, db.header().version);
+        builder.strict_android(true).build(TzDataFlavor::Android).unwrap();
+
+        // Asia/Shanghai has 29 transitions and 3 local time types in its version 1 block.
+        let shanghai = db.get(b"Asia/Shanghai").unwrap();
+        let mut bad_dst = shanghai.to_vec();
+        bad_dst[44 + 29 * 5 + 4] = 2;
+        let mut too_many = shanghai.to_vec();
+        too_many[32..36].copy_from_slice(&2001u32.to_be_bytes());
+        for (tzif, reason) in [
+            (bad_dst, "DST flag other than 0 or 1"),
+            (too_many, "more than 2000 transitions"),
+            (b"TZif".to_vec(), "truncated TZif header"),
+        ] {
+            let mut builder = TzDataBuilder::new(*b"2099z");
+            builder.zone("Asia/Shanghai", tzif);
+            assert!(builder.build(TzDataFlavor::Android).is_ok());
+            let err = builder.strict_android(true).build(TzDataFlavor::Android).unwrap_err();
+            assert!(
+                matches!(&err, ZoneInfoError::AndroidConstraint { zone, reason: r }
+                    if **zone == *b"Asia/Shanghai" && *r == reason),
+                "{err}"
+            );
+        }
+    }
+
+    #[test]
+    fn test_builder_zone_tab_and_names() {
+        let db = ZoneInfoDb::new_ohos(File::open("./tests/ohos/tzdata").unwrap()).unwrap();
+        let shanghai = db.get(b"Asia/Shanghai").unwrap();
+        let mut builder = TzDataBuilder::new(*b"2099z");
+        builder
+            .zone("Asia/Shanghai", shanghai)
+            .zone("Asia/Chongqing", shanghai)
+            .zone_tab("CN\t+3114+12128\tAsia/Shanghai\n");
+        let mut out = Vec::new();
+        builder.write(TzDataFlavor::Android, &mut out).unwrap();
+
+        let built = ZoneInfoDb::open_untrusted_android(&out).unwrap();
+        assert_eq!(built.header().version, *b"2099z");
+        let names: Vec<_> = built.indexes().timezones().iter().map(|x| &*x.name).collect();
+        assert_eq!(names, [&b"Asia/Chongqing"[..], b"Asia/Shanghai"]);
+        assert_eq!(built.get(b"Asia/Shanghai").unwrap(), shanghai);
+        #[cfg(feature = "zonetab")]
+        {
+            let zone_tab: &crate::ZoneTab = built.zone_tab().unwrap();
+            assert_eq!(zone_tab.country_for_zone(b"Asia/Shanghai"), Some("CN"));
+        }
+
+        for name in ["", "Asia/Shang hai", "Area/An_Exceedingly_Long_Zone_Name_Here_"] {
+            let mut builder = TzDataBuilder::new(*b"2099z");
+            builder.zone(name, shanghai);
+            assert!(matches!(
+                builder.build(TzDataFlavor::Ohos),
+                Err(ZoneInfoError::InvalidZoneName)
+            ));
+        }
+    }
+}
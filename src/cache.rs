@@ -0,0 +1,231 @@
+//! Preprocessed on-disk cache of a [`ZoneInfoDb`].
+//!
+//! Layout, all integers big-endian:
+//!
+//! ```text
+//! magic "ZIDBCACHE\0" | format version u32 | checksum u32 | payload
+//! payload = tzdata header | entry count u32 | entries | tzdata length u32 | tzdata
+//! entry   = name length u8 | name | offset u32 | length u32 | has raw offset u8 | raw offset i32
+//! ```
+//!
+//! The checksum is FNV-1a over the payload. Entries are stored already parsed, so opening a cache
+//! doesn't scan the 40-byte name slots of the original index; they're still checked for sorted
+//! names and bounds, as the checksum doesn't protect against crafted files.
+//!
+//! The whole `tzdata` file is kept, so a cache is slightly larger than it; what's saved is
+//! scanning and validating the index on open. TZif data isn't pre-parsed.
+use std::{
+    fs::File,
+    io::{BufReader, BufWriter, Read, Write},
+    path::Path,
+    sync::Arc,
+};
+
+use crate::{
+    Instrumentation, Result, SIZEOF_HEADER, TzDataHeader, TzDataIndex, TzDataIndexes, ZoneInfoDb,
+    ZoneInfoError, db::Storage,
+};
+
+const CACHE_MAGIC: &[u8; 10] = b"ZIDBCACHE\0";
+/// Bumped whenever the payload layout changes; caches of other versions are rejected.
+const CACHE_FORMAT_VERSION: u32 = 2;
+
+impl ZoneInfoDb {
+    /// Write the database in the preprocessed cache format.
+    ///
+    /// Every index entry is written, so fails with [`ZoneInfoError::EntryOutOfBounds`] if one
+    /// points outside the file, and with [`ZoneInfoError::InvalidCache`] if a name is longer
+    /// than 255 bytes, rather than writing a cache with fewer zones.
+    pub fn write_cache<W: Write>(&self, mut writer: W) -> Result<()> {
+        let mut payload = Vec::with_capacity(self.data.len() + self.data.len() / 8);
+        payload.extend_from_slice(&self.data[..SIZEOF_HEADER]);
+
+        let entries = self.indexes.timezones();
+        payload.extend_from_slice(&(entries.len() as u32).to_be_bytes());
+        for index in entries {
+            if self.tzdata(index).is_none() {
+                return Err(ZoneInfoError::EntryOutOfBounds);
+            }
+            if index.name.len() > u8::MAX as usize {
+                return Err(ZoneInfoError::InvalidCache("zone name longer than 255 bytes"));
+            }
+            payload.push(index.name.len() as u8);
+            payload.extend_from_slice(&index.name);
+            payload.extend_from_slice(&index.offset.to_be_bytes());
+            payload.extend_from_slice(&index.length.to_be_bytes());
+            payload.push(index.raw_utc_offset.is_some().into());
+            payload.extend_from_slice(&index.raw_utc_offset.unwrap_or(0).to_be_bytes());
+        }
+        payload.extend_from_slice(&(self.data.len() as u32).to_be_bytes());
+        payload.extend_from_slice(&self.data);
+
+        writer.write_all(CACHE_MAGIC)?;
+        writer.write_all(&CACHE_FORMAT_VERSION.to_be_bytes())?;
+        writer.write_all(&fnv1a(&payload).to_be_bytes())?;
+        writer.write_all(&payload)?;
+        Ok(writer.flush()?)
+    }
+
+    /// Read a database written by [`ZoneInfoDb::write_cache`].
+    pub fn read_cache<R: Read>(mut reader: R) -> Result<Self> {
+        let mut magic = [0; CACHE_MAGIC.len()];
+        reader.read_exact(&mut magic)?;
+        if &magic != CACHE_MAGIC {
+            return Err(ZoneInfoError::InvalidCache("invalid magic"));
+        }
+        if read_u32(&mut reader)? != CACHE_FORMAT_VERSION {
+            return Err(ZoneInfoError::InvalidCache("unsupported format version"));
+        }
+        let checksum = read_u32(&mut reader)?;
+        let mut payload = Vec::new();
+        reader.read_to_end(&mut payload)?;
+        if fnv1a(&payload) != checksum {
+            return Err(ZoneInfoError::InvalidCache("checksum mismatch"));
+        }
+
+        let payload_len = payload.len();
+        let mut payload = &payload[..];
+        let header = TzDataHeader::new(&mut payload)?;
+        let count = read_u32(&mut payload)?;
+        let mut indexes = Vec::with_capacity(count.min(u16::MAX.into()) as usize);
+        for _ in 0..count {
+            let mut len = [0; 1];
+            payload.read_exact(&mut len)?;
+            let (name, rest) = payload
+                .split_at_checked(len[0] as usize)
+                .ok_or(ZoneInfoError::InvalidCache("truncated entry"))?;
+            payload = rest;
+            let offset = read_u32(&mut payload)?;
+            let length = read_u32(&mut payload)?;
+            let mut has_raw_utc_offset = [0; 1];
+            payload.read_exact(&mut has_raw_utc_offset)?;
+            let raw_utc_offset = read_u32(&mut payload)? as i32;
+            let raw_utc_offset = match has_raw_utc_offset[0] {
+                0 => None,
+                1 => Some(raw_utc_offset),
+                _ => return Err(ZoneInfoError::InvalidCache("bad raw offset flag")),
+            };
+            indexes.push(TzDataIndex { name: Arc::from(name), offset, length, raw_utc_offset });
+        }
+        let len = read_u32(&mut payload)?;
+        if payload.len() != len as usize {
+            return Err(ZoneInfoError::InvalidCache("truncated tzdata"));
+        }
+        header.validate_layout(payload.len() as u64)?;
+        let indexes = TzDataIndexes { indexes, padding: 0, skipped: 0 };
+        indexes.check_names()?;
+        indexes.validate(&header, payload.len() as u64)?;
+
+        let instrumentation = Instrumentation {
+            entries_parsed: indexes.len(),
+            bytes_read: (CACHE_MAGIC.len() + 8 + payload_len) as u64,
+            ..Default::default()
+        };
+        Ok(Self {
+            data: Storage::Owned(payload.into()),
+            header,
+            indexes,
+            instrumentation,
+            #[cfg(feature = "tzif")]
+            abbreviations: Default::default(),
+            #[cfg(feature = "zonetab")]
+            zone_tab: Default::default(),
+        })
+    }
+
+    /// Save the database to a cache file, see [`ZoneInfoDb::write_cache`].
+    pub fn save_cache(&self, path: impl AsRef<Path>) -> Result<()> {
+        self.write_cache(BufWriter::new(File::create(path)?))
+    }
+
+    /// Open a cache file saved by [`ZoneInfoDb::save_cache`].
+    pub fn open_cache(path: impl AsRef<Path>) -> Result<Self> {
+        Self::read_cache(BufReader::new(File::open(path)?))
+    }
+}
+
+fn read_u32<R: Read>(mut reader: R) -> Result<u32> {
+    let mut buf = [0; 4];
+    reader.read_exact(&mut buf)?;
+    Ok(u32::from_be_bytes(buf))
+}
+
+/// 32-bit FNV-1a hash.
+fn fnv1a(data: &[u8]) -> u32 {
+    data.iter().fold(0x811c9dc5, |hash, &byte| (hash ^ byte as u32).wrapping_mul(0x01000193))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_cache_roundtrip() {
+        let db = ZoneInfoDb::new_android(File::open("./tests/android/tzdata").unwrap()).unwrap();
+        let mut cache = Vec::new();
+        db.write_cache(&mut cache).unwrap();
+
+        let cached = ZoneInfoDb::read_cache(&cache[..]).unwrap();
+        assert_eq!(cached.header(), db.header());
+        assert!(cached.indexes().timezones() == db.indexes().timezones());
+        assert_eq!(cached.get(b"Asia/Shanghai"), db.get(b"Asia/Shanghai"));
+        assert_eq!(cached.sections(), db.sections());
+    }
+
+    #[test]
+    fn test_cache_rejects_unwritable_entries() {
+        let open = || ZoneInfoDb::new_android(File::open("./tests/android/tzdata").unwrap());
+        let write = |db: &ZoneInfoDb| db.write_cache(&mut Vec::new());
+        let mut out_of_bounds = open().unwrap();
+        out_of_bounds.indexes.indexes[0].length = u32::MAX;
+        assert!(matches!(write(&out_of_bounds), Err(ZoneInfoError::EntryOutOfBounds)));
+        let mut long_name = open().unwrap();
+        long_name.indexes.indexes[0].name = Arc::from(vec![b'A'; 256]);
+        assert!(matches!(write(&long_name), Err(ZoneInfoError::InvalidCache(_))));
+    }
+
+    #[test]
+    fn test_cache_rejects_corruption() {
+        let db = ZoneInfoDb::new_ohos(File::open("./tests/ohos/tzdata").unwrap()).unwrap();
+        let mut cache = Vec::new();
+        db.write_cache(&mut cache).unwrap();
+
+        let mut corrupted = cache.clone();
+        *corrupted.last_mut().unwrap() ^= 1;
+        assert!(ZoneInfoDb::read_cache(&corrupted[..]).is_err());
+
+        let mut outdated = cache.clone();
+        outdated[CACHE_MAGIC.len() + 3] += 1;
+        assert!(ZoneInfoDb::read_cache(&outdated[..]).is_err());
+
+        assert!(ZoneInfoDb::read_cache(&cache[..cache.len() - 1]).is_err());
+    }
+
+    #[test]
+    fn test_cache_rejects_invalid_entries() {
+        let db = ZoneInfoDb::new_ohos(File::open("./tests/ohos/tzdata").unwrap()).unwrap();
+        let mut cache = Vec::new();
+        db.write_cache(&mut cache).unwrap();
+        let payload = CACHE_MAGIC.len() + 8;
+        // Corrupt the payload, then fix up the checksum.
+        let corrupt = |edit: &dyn Fn(&mut [u8])| {
+            let mut cache = cache.clone();
+            edit(&mut cache[payload..]);
+            let checksum = fnv1a(&cache[payload..]);
+            cache[payload - 4..payload].copy_from_slice(&checksum.to_be_bytes());
+            ZoneInfoDb::read_cache(&cache[..])
+        };
+        assert!(corrupt(&|_| ()).is_ok());
+
+        // The first entry: name length, name, offset, length.
+        let first = SIZEOF_HEADER + 4;
+        let name_len = cache[payload + first] as usize;
+        let length = first + 1 + name_len + 4;
+        let err = corrupt(&|x| x[length..length + 4].copy_from_slice(&u32::MAX.to_be_bytes()));
+        assert!(matches!(err, Err(ZoneInfoError::EntryOutOfBounds)));
+        let err = corrupt(&|x| x[first + 1] = b'Z');
+        assert!(matches!(err, Err(ZoneInfoError::UnsortedIndex)));
+        let err = corrupt(&|x| x[first + 1] = b'\n');
+        assert!(err.is_err());
+    }
+}
@@ -0,0 +1,46 @@
+//! Rewriting a `tzdata` file into a canonical byte layout.
+use alloc::vec::Vec;
+
+use crate::{Result, TzDataBuilder, TzDataFlavor, ZoneInfoDb};
+
+impl ZoneInfoDb {
+    /// Rewrite the database as a `tzdata` file of the given flavor in canonical form: entries
+    /// sorted by name, identical TZif data stored once, sections back to back without padding,
+    /// and the legacy raw offsets of Android zeroed. The zonetab is kept as it is.
+    ///
+    /// Databases with the same version, zones and zonetab canonicalize to the same bytes,
+    /// whatever tool or order they were built in.
+    pub fn canonicalize(&self, flavor: TzDataFlavor) -> Result<Vec<u8>> {
+        let mut builder = TzDataBuilder::new(self.header.version);
+        for (name, tzif) in self.iter() {
+            builder.zone(name, tzif);
+        }
+        builder.zone_tab(self.zone_tab_bytes()).dedup(true).build(flavor)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fs::File;
+
+    use super::*;
+
+    #[test]
+    fn test_canonicalize() {
+        let db = ZoneInfoDb::new_android(File::open("./tests/android/tzdata").unwrap()).unwrap();
+        let canonical = db.canonicalize(TzDataFlavor::Android).unwrap();
+        let reloaded = ZoneInfoDb::open_untrusted_android(&canonical).unwrap();
+        assert!(reloaded.iter().eq(db.iter()));
+        assert!(reloaded.canonicalize(TzDataFlavor::Android).unwrap() == canonical);
+
+        // Padding between the sections is dropped.
+        let mut padded = std::fs::read("./tests/ohos/tzdata").unwrap();
+        let db = ZoneInfoDb::new_ohos(&padded[..]).unwrap();
+        let canonical = db.canonicalize(TzDataFlavor::Ohos).unwrap();
+        let zonetab_offset = db.header().zonetab_offset + 16;
+        padded[20..24].copy_from_slice(&zonetab_offset.to_be_bytes());
+        padded.extend_from_slice(&[0; 16]);
+        let padded = ZoneInfoDb::new_ohos(&padded[..]).unwrap();
+        assert!(padded.canonicalize(TzDataFlavor::Ohos).unwrap() == canonical);
+    }
+}
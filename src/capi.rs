@@ -0,0 +1,100 @@
+//! C API over [`ZoneInfoDb`], behind the `capi` feature; the header is `include/zoneinfo_db.h`.
+//!
+//! Build a C library with `cargo rustc --release --features capi --crate-type cdylib` (or
+//! `staticlib`).
+use std::{
+    ffi::{CStr, c_char},
+    ptr,
+};
+
+use crate::ZoneInfoDb;
+
+/// Load the `tzdata` file at `path`, detecting its flavor.
+///
+/// Returns NULL if the file can't be read or parsed. Release the database with
+/// [`zoneinfo_db_free`].
+///
+/// # Safety
+///
+/// `path` must be NULL or a NUL-terminated string.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn zoneinfo_db_open(path: *const c_char) -> *mut ZoneInfoDb {
+    if path.is_null() {
+        return ptr::null_mut();
+    }
+    // SAFETY: the caller passes a NUL-terminated string.
+    let Ok(path) = unsafe { CStr::from_ptr(path) }.to_str() else {
+        return ptr::null_mut();
+    };
+    match std::fs::read(path).map_err(Into::into).and_then(ZoneInfoDb::from_bytes) {
+        Ok(db) => Box::into_raw(Box::new(db)),
+        Err(_) => ptr::null_mut(),
+    }
+}
+
+/// Find the TZif data of the zone `name`, storing its length in `len`.
+///
+/// Returns NULL if the zone isn't in the database. The data is borrowed from `db` and stays
+/// valid until [`zoneinfo_db_free`].
+///
+/// # Safety
+///
+/// `db` must be NULL or returned by [`zoneinfo_db_open`] and not freed, `name` NULL or a
+/// NUL-terminated string, and `len` NULL or valid for writes.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn zoneinfo_db_find(
+    db: *const ZoneInfoDb,
+    name: *const c_char,
+    len: *mut usize,
+) -> *const u8 {
+    if db.is_null() || name.is_null() {
+        return ptr::null();
+    }
+    // SAFETY: the caller passes a live database and a NUL-terminated string.
+    let (db, name) = unsafe { (&*db, CStr::from_ptr(name)) };
+    let Some(data) = db.get(name.to_bytes()) else {
+        return ptr::null();
+    };
+    if !len.is_null() {
+        // SAFETY: the caller passes a pointer valid for writes.
+        unsafe { len.write(data.len()) };
+    }
+    data.as_ptr()
+}
+
+/// Release a database returned by [`zoneinfo_db_open`]; NULL is ignored.
+///
+/// # Safety
+///
+/// `db` must be NULL or returned by [`zoneinfo_db_open`] and not already freed.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn zoneinfo_db_free(db: *mut ZoneInfoDb) {
+    if !db.is_null() {
+        // SAFETY: the caller passes a database allocated by `zoneinfo_db_open`.
+        drop(unsafe { Box::from_raw(db) });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_capi() {
+        unsafe {
+            let db = zoneinfo_db_open(c"./tests/android/tzdata".as_ptr());
+            assert!(!db.is_null());
+            let mut len = 0;
+            let data = zoneinfo_db_find(db, c"Asia/Shanghai".as_ptr(), &mut len);
+            assert!(!data.is_null());
+            assert_eq!(std::slice::from_raw_parts(data, len), (*db).get(b"Asia/Shanghai").unwrap());
+            assert!(zoneinfo_db_find(db, c"Asia/Nowhere".as_ptr(), &mut len).is_null());
+            assert!(zoneinfo_db_find(db, ptr::null(), &mut len).is_null());
+            zoneinfo_db_free(db);
+
+            assert!(zoneinfo_db_open(c"./tests/nowhere".as_ptr()).is_null());
+            assert!(zoneinfo_db_open(ptr::null()).is_null());
+            zoneinfo_db_free(ptr::null_mut());
+        }
+    }
+}
@@ -0,0 +1,125 @@
+//! [`chrono::TimeZone`](::chrono::TimeZone) implementation for [`ZoneInfo`], behind the `chrono`
+//! feature.
+use core::fmt;
+
+use ::chrono::{
+    FixedOffset, MappedLocalTime, NaiveDate, NaiveDateTime, NaiveTime, Offset, TimeZone,
+};
+
+use crate::{UtcOffset, ZoneInfo};
+
+/// Seconds per day, more than any transition moves local time by.
+const SECS_PER_DAY: i64 = 86400;
+
+/// Offset of a [`ZoneInfo`] at an instant, as stored in a `chrono::DateTime<&ZoneInfo>`.
+///
+/// Displays as the abbreviation, e.g. `CST`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ChronoOffset<'a> {
+    zone: &'a ZoneInfo,
+    offset: UtcOffset<'a>,
+}
+
+impl<'a> ChronoOffset<'a> {
+    /// The offset from UTC, in seconds, whether it's daylight saving time, and the abbreviation.
+    pub fn utc_offset(&self) -> UtcOffset<'a> {
+        self.offset
+    }
+}
+
+impl Offset for ChronoOffset<'_> {
+    fn fix(&self) -> FixedOffset {
+        // Offsets of a day or more, which no real zone has, aren't representable and fall back to
+        // UTC.
+        FixedOffset::east_opt(self.offset.seconds).unwrap_or(FixedOffset::east_opt(0).unwrap())
+    }
+}
+
+impl fmt::Display for ChronoOffset<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.offset.abbreviation)
+    }
+}
+
+impl<'a> TimeZone for &'a ZoneInfo {
+    type Offset = ChronoOffset<'a>;
+
+    fn from_offset(offset: &Self::Offset) -> Self {
+        offset.zone
+    }
+
+    fn offset_from_local_date(&self, local: &NaiveDate) -> MappedLocalTime<Self::Offset> {
+        self.offset_from_local_datetime(&local.and_time(NaiveTime::MIN))
+    }
+
+    fn offset_from_local_datetime(&self, local: &NaiveDateTime) -> MappedLocalTime<Self::Offset> {
+        let local = local.and_utc().timestamp();
+        // The offsets in effect a day before and after are the only ones a local time can map
+        // through, as transitions are further apart than that.
+        let mut instants = [local - SECS_PER_DAY, local + SECS_PER_DAY].map(|probe| {
+            let utc = local.checked_sub(self.offset_at(probe).seconds.into())?;
+            let offset = self.offset_at(utc);
+            (local - utc == i64::from(offset.seconds)).then_some((utc, offset))
+        });
+        instants.sort_by_key(|x| x.map(|(utc, _)| utc));
+        let offset = |offset| ChronoOffset { zone: self, offset };
+        match instants {
+            [None, None] => MappedLocalTime::None,
+            [None, Some((_, x))] => MappedLocalTime::Single(offset(x)),
+            [Some((a, x)), Some((b, _))] if a == b => MappedLocalTime::Single(offset(x)),
+            [Some((_, x)), Some((_, y))] => MappedLocalTime::Ambiguous(offset(x), offset(y)),
+            [Some(_), None] => unreachable!("`None` sorts first"),
+        }
+    }
+
+    fn offset_from_utc_date(&self, utc: &NaiveDate) -> Self::Offset {
+        self.offset_from_utc_datetime(&utc.and_time(NaiveTime::MIN))
+    }
+
+    fn offset_from_utc_datetime(&self, utc: &NaiveDateTime) -> Self::Offset {
+        ChronoOffset { zone: self, offset: self.offset_at(utc.and_utc().timestamp()) }
+    }
+}
+
+#[cfg(all(test, feature = "std"))]
+mod tests {
+    use std::fs::File;
+
+    use ::chrono::{DateTime, Utc};
+
+    use super::*;
+    use crate::ZoneInfoDb;
+
+    #[test]
+    fn test_chrono_time_zone() {
+        let db = ZoneInfoDb::new_ohos(File::open("./tests/ohos/tzdata").unwrap()).unwrap();
+        let new_york = db.zone_info(b"America/New_York").unwrap().unwrap();
+        let tz = &new_york;
+
+        let utc = DateTime::<Utc>::from_timestamp(1719792000, 0).unwrap();
+        let local = utc.with_timezone(&tz);
+        assert_eq!(local.to_string(), "2024-06-30 20:00:00 EDT");
+        assert_eq!(local.offset().fix().local_minus_utc(), -4 * 3600);
+        assert!(local.offset().utc_offset().is_dst);
+
+        let date = NaiveDate::from_ymd_opt(2024, 3, 10).unwrap();
+        // 02:30 is skipped when clocks spring forward.
+        assert_eq!(
+            tz.from_local_datetime(&date.and_hms_opt(2, 30, 0).unwrap()),
+            MappedLocalTime::None
+        );
+        let single = tz.from_local_datetime(&date.and_hms_opt(12, 0, 0).unwrap()).unwrap();
+        assert_eq!(single.timestamp(), 1710086400);
+
+        // 01:30 happens twice when clocks fall back, first in EDT.
+        let date = NaiveDate::from_ymd_opt(2024, 11, 3).unwrap();
+        let MappedLocalTime::Ambiguous(earliest, latest) =
+            tz.from_local_datetime(&date.and_hms_opt(1, 30, 0).unwrap())
+        else {
+            panic!("01:30 should be ambiguous");
+        };
+        assert_eq!(earliest.offset().to_string(), "EDT");
+        assert_eq!(latest.offset().to_string(), "EST");
+        assert_eq!(latest.timestamp() - earliest.timestamp(), 3600);
+    }
+}
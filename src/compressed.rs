@@ -0,0 +1,90 @@
+//! Loading of `tzdata` files that may be compressed.
+use std::{io::Read, path::Path};
+
+use crate::{MAX_UNTRUSTED_LEN, Result, ZoneInfoDb, ZoneInfoError};
+
+const GZIP_MAGIC: &[u8] = &[0x1f, 0x8b];
+const ZSTD_MAGIC: &[u8] = &[0x28, 0xb5, 0x2f, 0xfd];
+
+impl ZoneInfoDb {
+    /// Load the `tzdata` file at `path`, decompressing it in memory if it's gzip (with the
+    /// `flate2` feature) or zstd (with the `zstd` feature), and detecting its flavor.
+    ///
+    /// The format is sniffed from the magic bytes rather than the extension. Decompressed data
+    /// larger than [`MAX_UNTRUSTED_LEN`] fails with [`ZoneInfoError::TooLarge`], and a format
+    /// whose feature is disabled with [`ZoneInfoError::UnsupportedCompression`].
+    pub fn open_auto(path: impl AsRef<Path>) -> Result<Self> {
+        let data = std::fs::read(path)?;
+        if data.starts_with(GZIP_MAGIC) {
+            #[cfg(feature = "flate2")]
+            return Self::from_bytes(decompress(flate2::read::MultiGzDecoder::new(&data[..]))?);
+            #[cfg(not(feature = "flate2"))]
+            return Err(ZoneInfoError::UnsupportedCompression("gzip"));
+        }
+        if data.starts_with(ZSTD_MAGIC) {
+            #[cfg(feature = "zstd")]
+            return Self::from_bytes(decompress(zstd::Decoder::new(&data[..])?)?);
+            #[cfg(not(feature = "zstd"))]
+            return Err(ZoneInfoError::UnsupportedCompression("zstd"));
+        }
+        Self::from_bytes(data)
+    }
+}
+
+/// Read a decompressor to the end, up to [`MAX_UNTRUSTED_LEN`] bytes.
+#[cfg_attr(not(any(feature = "flate2", feature = "zstd")), expect(dead_code))]
+fn decompress(reader: impl Read) -> Result<Vec<u8>> {
+    let mut data = Vec::new();
+    reader.take(MAX_UNTRUSTED_LEN as u64 + 1).read_to_end(&mut data)?;
+    if data.len() > MAX_UNTRUSTED_LEN {
+        return Err(ZoneInfoError::TooLarge);
+    }
+    Ok(data)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::Write;
+
+    use super::*;
+
+    #[test]
+    fn test_open_auto() {
+        let original = std::fs::read("./tests/ohos/tzdata").unwrap();
+        let dir =
+            std::env::temp_dir().join(format!("zoneinfo-db-compressed-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let db = ZoneInfoDb::open_auto("./tests/ohos/tzdata").unwrap();
+        assert_eq!(db.zone_count(), 442);
+
+        #[cfg(feature = "flate2")]
+        let gzip = {
+            let mut encoder = flate2::write::GzEncoder::new(Vec::new(), Default::default());
+            encoder.write_all(&original).unwrap();
+            encoder.finish().unwrap()
+        };
+        #[cfg(not(feature = "flate2"))]
+        let gzip = [GZIP_MAGIC, &[0; 16]].concat();
+        #[cfg(feature = "zstd")]
+        let zstd = zstd::encode_all(&original[..], 0).unwrap();
+        #[cfg(not(feature = "zstd"))]
+        let zstd = [ZSTD_MAGIC, &[0; 16]].concat();
+
+        for (name, data, enabled) in [
+            ("tzdata.gz", gzip, cfg!(feature = "flate2")),
+            ("tzdata.zst", zstd, cfg!(feature = "zstd")),
+        ] {
+            let path = dir.join(name);
+            std::fs::File::create(&path).unwrap().write_all(&data).unwrap();
+            let db = ZoneInfoDb::open_auto(&path);
+            if enabled {
+                let db = db.unwrap();
+                assert_eq!(db.get(b"Asia/Shanghai").unwrap().len(), 393);
+                assert_eq!(db.data_len(), original.len());
+            } else {
+                assert!(matches!(db, Err(ZoneInfoError::UnsupportedCompression(_))));
+            }
+        }
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+}
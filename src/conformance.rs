@@ -0,0 +1,69 @@
+//! Dump of parsed zones for diffing against Android's reference `ZoneInfoDb` implementation.
+use std::io::Write;
+
+use crate::{Result, ZoneInfo, ZoneInfoDb};
+
+/// Write every zone of the database, in index order, as Android's `ZoneInfo.toString` prints it,
+/// followed by one tab-separated line per transition: time, offset from UTC in seconds, DST
+/// flag as 0 or 1, and abbreviation.
+///
+/// `now` is the Unix timestamp `mUseDst` is evaluated at, as the reference takes the current
+/// time, so dumps are reproducible. For example, with tabs shown as `\t`:
+///
+/// ```text
+/// libcore.util.ZoneInfo[id="Asia/Shanghai",mRawOffset=28800000,mEarliestRawOffset=29143000,mUseDst=false,mDstSavings=0,transitions=29]
+/// \t-2177481943\t28800\t0\tCST
+/// ```
+pub fn conformance_dump<W: Write>(db: &ZoneInfoDb, now: i64, mut out: W) -> Result<()> {
+    for (name, tzif) in db.iter() {
+        let zone = ZoneInfo::new(tzif)?;
+        let tzif = zone.tzif();
+        writeln!(
+            out,
+            "libcore.util.ZoneInfo[id=\"{}\",mRawOffset={},mEarliestRawOffset={},mUseDst={},\
+             mDstSavings={},transitions={}]",
+            name.escape_ascii(),
+            i64::from(zone.raw_offset()) * 1000,
+            i64::from(tzif.local_time_types[0].utoff) * 1000,
+            zone.uses_dst(now),
+            i64::from(zone.dst_savings()) * 1000,
+            tzif.transitions.len(),
+        )?;
+        for transition in &tzif.transitions {
+            let ty = &tzif.local_time_types[transition.local_time_type];
+            let is_dst = u8::from(ty.is_dst);
+            writeln!(out, "\t{}\t{}\t{is_dst}\t{}", transition.time, ty.utoff, ty.abbreviation)?;
+        }
+    }
+    Ok(out.flush()?)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fs::File;
+
+    use super::*;
+
+    #[test]
+    fn test_conformance_dump() {
+        let db = ZoneInfoDb::new_android(File::open("./tests/android/tzdata").unwrap()).unwrap();
+        let mut out = Vec::new();
+        // 2024-01-01.
+        conformance_dump(&db, 1704067200, &mut out).unwrap();
+        let out = String::from_utf8(out).unwrap();
+        let zones: Vec<_> = out.lines().filter(|x| !x.starts_with('\t')).collect();
+        assert_eq!(zones.len(), 593);
+
+        let shanghai = out.split_once("[id=\"Asia/Shanghai\"").unwrap().1;
+        let mut lines = shanghai.lines();
+        assert_eq!(
+            lines.next().unwrap(),
+            ",mRawOffset=28800000,mEarliestRawOffset=29143000,mUseDst=false,mDstSavings=0,\
+             transitions=29]"
+        );
+        assert_eq!(lines.next().unwrap(), "\t-2177481943\t28800\t0\tCST");
+        let new_york = zones.iter().find(|x| x.contains("\"America/New_York\"")).unwrap();
+        assert!(new_york.contains(",mRawOffset=-18000000,"), "{new_york}");
+        assert!(new_york.contains(",mUseDst=true,mDstSavings=3600000,"), "{new_york}");
+    }
+}
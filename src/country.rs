@@ -0,0 +1,83 @@
+//! Queries over the zones of a country, combining the zonetab with the zones' rules.
+use alloc::{collections::BTreeSet, vec::Vec};
+
+use crate::{Result, ZoneInfo, ZoneInfoDb};
+
+impl ZoneInfoDb {
+    /// The zones of a country in use at a Unix timestamp, in zonetab order, like Android's
+    /// `TimeZoneFinder` filtering zones by their `notafter` time.
+    ///
+    /// A zone is left out when, from `unix_seconds` on, it has the same local time as a zone
+    /// listed before it, see [`ZoneInfo::same_rules_since`]. Zones missing from the database are
+    /// skipped.
+    pub fn zones_for_country_at(&self, country: &str, unix_seconds: i64) -> Result<Vec<&[u8]>> {
+        let mut zones: Vec<(&[u8], ZoneInfo)> = Vec::new();
+        for name in self.zone_tab()?.zones_for_country(country) {
+            let Some(zone_info) = self.zone_info(name)? else {
+                continue;
+            };
+            if !zones.iter().any(|(_, x)| x.same_rules_since(&zone_info, unix_seconds)) {
+                zones.push((name, zone_info));
+            }
+        }
+        Ok(zones.into_iter().map(|(name, _)| name).collect())
+    }
+
+    /// The distinct offsets from UTC in seconds across the zones of a country at a Unix
+    /// timestamp, in ascending order, e.g. for finding meeting times. Zones missing from the
+    /// database are skipped.
+    pub fn offsets_for_country(&self, country: &str, unix_seconds: i64) -> Result<Vec<i32>> {
+        let mut offsets = BTreeSet::new();
+        for name in self.zone_tab()?.zones_for_country(country) {
+            if let Some(zone_info) = self.zone_info(name)? {
+                offsets.insert(zone_info.offset_at(unix_seconds).seconds);
+            }
+        }
+        Ok(offsets.into_iter().collect())
+    }
+}
+
+#[cfg(all(test, feature = "builder"))]
+mod tests {
+    use super::*;
+    use crate::testutil::android_fixture_with_zone_tab;
+
+    /// The Android fixture with a zonetab of some US zones.
+    fn us_db() -> ZoneInfoDb {
+        android_fixture_with_zone_tab(
+            "US\t+404251-0740023\tAmerica/New_York\tEastern (most areas)\n\
+             US\t+421953-0830245\tAmerica/Detroit\tEastern - MI (most areas)\n\
+             US\t+415100-0873900\tAmerica/Chicago\tCentral (most areas)\n\
+             US\t+332654-1120424\tAmerica/Phoenix\tMST - AZ (most areas)\n\
+             CN\t+3114+12128\tAsia/Shanghai\tBeijing Time\n",
+        )
+    }
+
+    #[test]
+    fn test_zones_for_country_at() {
+        let db = us_db();
+        // Detroit has followed New York since the 1970s.
+        let zones = db.zones_for_country_at("US", 946684800).unwrap();
+        assert_eq!(zones, [&b"America/New_York"[..], b"America/Chicago", b"America/Phoenix"]);
+        let zones = db.zones_for_country_at("US", 0).unwrap();
+        assert_eq!(zones.len(), 4);
+        assert_eq!(db.zones_for_country_at("CN", 0).unwrap(), [&b"Asia/Shanghai"[..]]);
+        assert!(db.zones_for_country_at("XX", 0).unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_offsets_for_country() {
+        let db = us_db();
+        // 2024-01-01 and 2024-07-01: Phoenix doesn't observe daylight saving time.
+        assert_eq!(
+            db.offsets_for_country("US", 1704067200).unwrap(),
+            [-7 * 3600, -6 * 3600, -5 * 3600]
+        );
+        assert_eq!(
+            db.offsets_for_country("us", 1719792000).unwrap(),
+            [-7 * 3600, -5 * 3600, -4 * 3600]
+        );
+        assert_eq!(db.offsets_for_country("CN", 0).unwrap(), [8 * 3600]);
+        assert!(db.offsets_for_country("XX", 0).unwrap().is_empty());
+    }
+}
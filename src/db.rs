@@ -0,0 +1,472 @@
+//! In-memory handle over a whole `tzdata` file.
+use std::{
+    io::Read,
+    ops::{Deref, Range},
+    time::{Duration, Instant},
+};
+
+use crate::{Result, TzDataHeader, TzDataIndex, TzDataIndexes, ZoneInfoError};
+
+/// A `tzdata` file loaded into memory, with its header and indexes parsed once.
+pub struct ZoneInfoDb {
+    pub(crate) data: Storage,
+    pub(crate) header: TzDataHeader,
+    pub(crate) indexes: TzDataIndexes,
+    pub(crate) instrumentation: Instrumentation,
+    /// Zones using each abbreviation, built on first use.
+    #[cfg(feature = "tzif")]
+    pub(crate) abbreviations:
+        std::sync::OnceLock<std::collections::BTreeMap<String, Vec<std::sync::Arc<[u8]>>>>,
+    /// The parsed zonetab section, built on first use.
+    #[cfg(feature = "zonetab")]
+    pub(crate) zone_tab: std::sync::OnceLock<crate::ZoneTab>,
+}
+
+/// Bytes of a loaded `tzdata` file.
+pub(crate) enum Storage {
+    Owned(Box<[u8]>),
+    #[cfg(feature = "mmap")]
+    Mmap(memmap2::Mmap),
+}
+
+impl Deref for Storage {
+    type Target = [u8];
+
+    fn deref(&self) -> &[u8] {
+        match self {
+            Storage::Owned(data) => data,
+            #[cfg(feature = "mmap")]
+            Storage::Mmap(data) => data,
+        }
+    }
+}
+
+/// Timings and counters collected while loading a [`ZoneInfoDb`], for tracking parse performance
+/// across crate versions. Timings are zero on `wasm32-unknown-unknown`, which has no clock.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct Instrumentation {
+    /// Time spent parsing the header.
+    pub header_time: Duration,
+    /// Time spent parsing the index.
+    pub index_time: Duration,
+    /// Number of index entries parsed.
+    pub entries_parsed: usize,
+    /// Number of index entries skipped as malformed.
+    pub entries_skipped: usize,
+    /// Number of bytes read from the source.
+    pub bytes_read: u64,
+}
+
+/// Largest file accepted by [`ZoneInfoDb::open_untrusted_android`] and
+/// [`ZoneInfoDb::open_untrusted_ohos`], several times the size of real databases.
+pub const MAX_UNTRUSTED_LEN: usize = 4 << 20;
+
+type ParseIndexes = fn(&[u8], &TzDataHeader) -> Result<TzDataIndexes>;
+
+/// A byte range of the `tzdata` file, tagged with what it contains.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Section {
+    /// The fixed-size file header.
+    Header(Range<u64>),
+    /// The index entries, from `index_offset` to `data_offset`.
+    Index(Range<u64>),
+    /// The concatenated TZif blobs, from `data_offset` to `zonetab_offset`.
+    Data(Range<u64>),
+    /// The zone.tab text, from `zonetab_offset` to the end of the file.
+    ZoneTab(Range<u64>),
+    /// Bytes not covered by any section the header describes.
+    Unknown { range: Range<u64> },
+}
+
+impl Section {
+    /// Byte range of the section in the file.
+    pub fn range(&self) -> Range<u64> {
+        match self {
+            Section::Header(range)
+            | Section::Index(range)
+            | Section::Data(range)
+            | Section::ZoneTab(range)
+            | Section::Unknown { range } => range.clone(),
+        }
+    }
+}
+
+impl ZoneInfoDb {
+    /// Load the `tzdata` file of Android.
+    pub fn new_android<R: Read>(reader: R) -> Result<Self> {
+        Self::new(reader, |index, header| TzDataIndexes::new_android(index, header))
+    }
+
+    /// Load the `tzdata` file of HarmonyOS NEXT.
+    pub fn new_ohos<R: Read>(reader: R) -> Result<Self> {
+        Self::new(reader, |index, header| TzDataIndexes::new_ohos(index, header))
+    }
+
+    fn new<R: Read>(mut reader: R, parse_indexes: ParseIndexes) -> Result<Self> {
+        let mut data = Vec::new();
+        reader.read_to_end(&mut data)?;
+        Self::from_storage(Storage::Owned(data.into()), parse_indexes)
+    }
+
+    /// Load a `tzdata` file of either flavor already in memory, e.g. downloaded over HTTP,
+    /// detecting its flavor.
+    ///
+    /// A `Vec<u8>` is taken over without copying. This touches neither the file system nor the
+    /// environment, so it also works on `wasm32-unknown-unknown`.
+    pub fn from_bytes(bytes: impl Into<Box<[u8]>>) -> Result<Self> {
+        Self::from_storage(Storage::Owned(bytes.into()), |index, header| {
+            TzDataIndexes::new_auto(index, header).map(|(indexes, _)| indexes)
+        })
+    }
+
+    /// Load a possibly truncated `tzdata` file of either flavor, e.g. left by an interrupted
+    /// update, serving the zones that are intact.
+    ///
+    /// Index entries cut off by the end of the file, and entries whose data is, are dropped; see
+    /// [`ZoneInfoDb::degraded`]. Only a truncated header, or a file ending before the index,
+    /// fails.
+    pub fn recover(bytes: impl Into<Box<[u8]>>) -> Result<Self> {
+        Self::from_storage(Storage::Owned(bytes.into()), |index, header| {
+            TzDataIndexes::recover(index, header).map(|(indexes, _)| indexes)
+        })
+    }
+
+    /// Memory-map the `tzdata` file at `path`, detecting its flavor.
+    ///
+    /// The header and index are parsed once; zone data is read from the mapping on access.
+    #[cfg(feature = "mmap")]
+    pub fn open(path: impl AsRef<std::path::Path>) -> Result<Self> {
+        let file = std::fs::File::open(path)?;
+        // SAFETY: tzdata files are replaced atomically by updates rather than modified in place,
+        // so the mapping isn't expected to change under us.
+        let mmap = unsafe { memmap2::Mmap::map(&file)? };
+        Self::from_storage(Storage::Mmap(mmap), |index, header| {
+            TzDataIndexes::new_auto(index, header).map(|(indexes, _)| indexes)
+        })
+    }
+
+    /// Load an untrusted Android `tzdata` file, e.g. one uploaded by a device, with all checks
+    /// enabled, see [`ZoneInfoDb::open_untrusted_ohos`].
+    pub fn open_untrusted_android(bytes: &[u8]) -> Result<Self> {
+        Self::open_untrusted(bytes, |index, header| TzDataIndexes::new_android(index, header))
+    }
+
+    /// Load an untrusted HarmonyOS NEXT `tzdata` file with all checks enabled.
+    ///
+    /// Files larger than [`MAX_UNTRUSTED_LEN`] are rejected up front, so memory use is bounded by
+    /// a small multiple of that. The header sections must be in order and within the file, and
+    /// every index entry must have a printable ASCII name, be sorted, and point inside the data
+    /// section.
+    pub fn open_untrusted_ohos(bytes: &[u8]) -> Result<Self> {
+        Self::open_untrusted(bytes, |index, header| TzDataIndexes::new_ohos(index, header))
+    }
+
+    fn open_untrusted(bytes: &[u8], parse_indexes: ParseIndexes) -> Result<Self> {
+        if bytes.len() > MAX_UNTRUSTED_LEN {
+            return Err(ZoneInfoError::TooLarge);
+        }
+        let header = TzDataHeader::new(bytes)?;
+        let len = bytes.len() as u64;
+        header.validate_layout(len)?;
+
+        let db = Self::from_storage(Storage::Owned(bytes.into()), parse_indexes)?;
+        db.indexes.check_names()?;
+        db.indexes.validate(&header, len)?;
+        Ok(db)
+    }
+
+    fn from_storage(data: Storage, parse_indexes: ParseIndexes) -> Result<Self> {
+        #[cfg(feature = "tracing")]
+        let _span = tracing::debug_span!("load_tzdata", len = data.len()).entered();
+        let (header, header_time) = timed(|| TzDataHeader::new(&data[..]));
+        let header = header?;
+
+        let (indexes, index_time) = timed(|| {
+            let index =
+                data.get(header.index_offset as usize..).ok_or(ZoneInfoError::TruncatedIndex)?;
+            parse_indexes(index, &header)
+        });
+        let indexes = indexes?;
+
+        let instrumentation = Instrumentation {
+            header_time,
+            index_time,
+            entries_parsed: indexes.timezones().len(),
+            entries_skipped: indexes.skipped(),
+            bytes_read: data.len() as u64,
+        };
+        Ok(Self {
+            data,
+            header,
+            indexes,
+            instrumentation,
+            #[cfg(feature = "tzif")]
+            abbreviations: Default::default(),
+            #[cfg(feature = "zonetab")]
+            zone_tab: Default::default(),
+        })
+    }
+
+    /// Timings and counters collected while loading the database.
+    pub fn instrumentation(&self) -> &Instrumentation {
+        &self.instrumentation
+    }
+
+    /// Header of the database.
+    pub fn header(&self) -> &TzDataHeader {
+        &self.header
+    }
+
+    /// Indexes of the database.
+    pub fn indexes(&self) -> &TzDataIndexes {
+        &self.indexes
+    }
+
+    /// Number of zones in the index.
+    pub fn zone_count(&self) -> usize {
+        self.indexes.timezones().len()
+    }
+
+    /// Size of the loaded file in bytes.
+    pub fn data_len(&self) -> usize {
+        self.data.len()
+    }
+
+    /// Whether the file ends before the zonetab section the header describes, i.e. it's
+    /// truncated and zones past its end are missing, see [`ZoneInfoDb::recover`].
+    pub fn degraded(&self) -> bool {
+        (self.data.len() as u64) < u64::from(self.header.zonetab_offset)
+    }
+
+    /// The raw text of the zonetab section, from `zonetab_offset` to the end of the file, or
+    /// empty if it starts past the end.
+    pub fn zone_tab_bytes(&self) -> &[u8] {
+        crate::slice_at(&self.data, self.header.zonetab_range(self.data.len() as u64))
+            .unwrap_or_default()
+    }
+
+    /// Get the timezone data of a zone, borrowed from the loaded file.
+    pub fn get(&self, tz_name: &[u8]) -> Option<&[u8]> {
+        let index = self.indexes.find_timezone(tz_name)?;
+        self.tzdata(index)
+    }
+
+    /// Iterate over the names and timezone data of all zones, in index order.
+    ///
+    /// Entries pointing outside the file are skipped.
+    pub fn iter(&self) -> impl Iterator<Item = (&[u8], &[u8])> {
+        self.indexes
+            .timezones()
+            .iter()
+            .filter_map(|index| Some((&*index.name, self.tzdata(index)?)))
+    }
+
+    /// Get the timezone data referenced by an index entry, if it lies within the file.
+    pub fn tzdata(&self, index: &TzDataIndex) -> Option<&[u8]> {
+        crate::slice_at(&self.data, crate::entry_range(&self.header, index))
+    }
+
+    /// Split the file into its sections, in file order.
+    ///
+    /// Gaps between the sections described by the header, and any offsets pointing past the end
+    /// of the file, are reported as [`Section::Unknown`] or clamped to the file length.
+    pub fn sections(&self) -> Vec<Section> {
+        let len = self.data.len() as u64;
+        let ranges = self.header.sections(len);
+        let sections = [
+            (ranges.header, Section::Header as fn(_) -> _),
+            (ranges.index, Section::Index),
+            (ranges.data, Section::Data),
+            (ranges.zonetab, Section::ZoneTab),
+        ];
+
+        let mut cursor = 0;
+        let mut result = Vec::with_capacity(sections.len());
+        for (range, section) in sections {
+            let start = range.start.clamp(cursor, len);
+            let end = range.end.clamp(start, len);
+            if start > cursor {
+                result.push(Section::Unknown { range: cursor..start });
+            }
+            result.push(section(start..end));
+            cursor = end;
+        }
+        result
+    }
+}
+
+/// Run `f`, measuring how long it takes on platforms with a clock.
+///
+/// `wasm32-unknown-unknown` has none and [`Instant::now`] panics there, so the duration is zero.
+fn timed<T>(f: impl FnOnce() -> T) -> (T, Duration) {
+    if cfg!(all(target_family = "wasm", target_os = "unknown")) {
+        return (f(), Duration::ZERO);
+    }
+    let start = Instant::now();
+    (f(), start.elapsed())
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fs::File;
+
+    use super::*;
+    use crate::entry_range;
+
+    #[test]
+    fn test_android_db_get() {
+        let db = ZoneInfoDb::new_android(File::open("./tests/android/tzdata").unwrap()).unwrap();
+        assert_eq!(db.header().version, *b"2021a");
+        assert_eq!(db.indexes().timezones().len(), 593);
+        assert_eq!(db.zone_count(), 593);
+        assert_eq!(db.data_len(), 491837);
+        assert_eq!(db.get(b"Asia/Shanghai").unwrap().len(), 573);
+        assert!(db.get(b"Asia/Nowhere").is_none());
+    }
+
+    #[test]
+    fn test_db_iter() {
+        let db = ZoneInfoDb::new_ohos(File::open("./tests/ohos/tzdata").unwrap()).unwrap();
+        assert_eq!(db.iter().count(), 442);
+        let (name, data) = db.iter().find(|(name, _)| *name == b"Asia/Shanghai").unwrap();
+        assert_eq!(data, db.get(name).unwrap());
+    }
+
+    #[test]
+    fn test_db_from_bytes() {
+        let db = ZoneInfoDb::from_bytes(std::fs::read("./tests/ohos/tzdata").unwrap()).unwrap();
+        assert_eq!(db.zone_count(), 442);
+        assert_eq!(db.get(b"Asia/Shanghai").unwrap().len(), 393);
+        let db = ZoneInfoDb::from_bytes(std::fs::read("./tests/android/tzdata").unwrap()).unwrap();
+        assert_eq!(db.get(b"Asia/Shanghai").unwrap().len(), 573);
+        assert!(ZoneInfoDb::from_bytes(&b"tzdata"[..]).is_err());
+    }
+
+    #[cfg(feature = "mmap")]
+    #[test]
+    fn test_db_open_mmap() {
+        let db = ZoneInfoDb::open("./tests/android/tzdata").unwrap();
+        assert!(matches!(db.data, Storage::Mmap(_)));
+        assert_eq!(db.header().version, *b"2021a");
+        assert_eq!(db.get(b"Asia/Shanghai").unwrap().len(), 573);
+
+        let db = ZoneInfoDb::open("./tests/ohos/tzdata").unwrap();
+        assert_eq!(db.indexes().timezones().len(), 442);
+        assert_eq!(db.get(b"Asia/Shanghai").unwrap().len(), 393);
+    }
+
+    #[test]
+    fn test_instrumentation() {
+        let mut data = std::fs::read("./tests/ohos/tzdata").unwrap();
+        // Drop the NUL terminator of the first entry's name.
+        data[24..24 + 40].fill(b'A');
+        let db = ZoneInfoDb::new_ohos(&data[..]).unwrap();
+        let instrumentation = db.instrumentation();
+        assert_eq!(instrumentation.entries_parsed, 441);
+        assert_eq!(instrumentation.entries_skipped, 1);
+        assert_eq!(instrumentation.bytes_read, 272428);
+    }
+
+    #[test]
+    fn test_open_untrusted() {
+        let mut data = std::fs::read("./tests/android/tzdata").unwrap();
+        let db = ZoneInfoDb::open_untrusted_android(&data).unwrap();
+        assert_eq!(db.get(b"Asia/Shanghai").unwrap().len(), 573);
+        // The OpenHarmony layout doesn't fit Android files: names land mid-entry.
+        assert!(matches!(
+            ZoneInfoDb::open_untrusted_ohos(&data),
+            Err(ZoneInfoError::TruncatedIndex)
+        ));
+
+        let mut oversized = data.clone();
+        oversized.resize(MAX_UNTRUSTED_LEN + 1, 0);
+        assert!(matches!(
+            ZoneInfoDb::open_untrusted_android(&oversized),
+            Err(ZoneInfoError::TooLarge)
+        ));
+
+        // zonetab_offset past the end of the file.
+        let mut inverted = data.clone();
+        inverted[20..24].copy_from_slice(&u32::MAX.to_be_bytes());
+        assert!(matches!(
+            ZoneInfoDb::open_untrusted_android(&inverted),
+            Err(ZoneInfoError::SectionPastEnd { section: "zonetab", .. })
+        ));
+        assert!(ZoneInfoDb::new_android(&inverted[..]).is_ok());
+
+        // Length of the first entry reaching past the data section.
+        data[24 + 44..24 + 48].copy_from_slice(&u32::MAX.to_be_bytes());
+        assert!(matches!(
+            ZoneInfoDb::open_untrusted_android(&data),
+            Err(ZoneInfoError::EntryOutOfBounds)
+        ));
+    }
+
+    #[test]
+    fn test_recover_truncated() {
+        let data = std::fs::read("./tests/android/tzdata").unwrap();
+        let intact = ZoneInfoDb::recover(data.clone()).unwrap();
+        assert!(!intact.degraded());
+        assert_eq!(intact.zone_count(), 593);
+
+        // Cut in the middle of the data of Asia/Shanghai.
+        let db = ZoneInfoDb::new_android(&data[..]).unwrap();
+        let shanghai = db.indexes().find_timezone(b"Asia/Shanghai").unwrap();
+        let cut = entry_range(db.header(), shanghai).start as usize + 10;
+        let db = ZoneInfoDb::recover(&data[..cut]).unwrap();
+        assert!(db.degraded());
+        assert!(db.get(b"Asia/Shanghai").is_none());
+        assert_eq!(db.get(b"Africa/Abidjan"), intact.get(b"Africa/Abidjan"));
+        assert_eq!(db.iter().count(), db.zone_count());
+        assert!(db.zone_count() < 593);
+        assert!(ZoneInfoDb::new_android(&data[..cut]).is_ok_and(|x| x.zone_count() == 593));
+
+        // Cut in the middle of an index entry, with the data gone.
+        let cut = db.header().index_offset as usize + 52 * 10 + 20;
+        let db = ZoneInfoDb::recover(&data[..cut]).unwrap();
+        assert!(db.degraded());
+        assert_eq!(db.zone_count(), 0);
+        assert!(ZoneInfoDb::new_android(&data[..cut]).is_err());
+
+        let ohos = std::fs::read("./tests/ohos/tzdata").unwrap();
+        let cut = ohos.len() - 1000;
+        let db = ZoneInfoDb::recover(&ohos[..cut]).unwrap();
+        assert!(db.degraded());
+        assert!(db.zone_count() > 400 && db.zone_count() < 442);
+        assert!(ZoneInfoDb::recover(&ohos[..20]).is_err());
+    }
+
+    #[test]
+    fn test_ohos_db_sections() {
+        let db = ZoneInfoDb::new_ohos(File::open("./tests/ohos/tzdata").unwrap()).unwrap();
+        assert_eq!(
+            db.sections(),
+            [
+                Section::Header(0..24),
+                Section::Index(24..21240),
+                Section::Data(21240..272428),
+                Section::ZoneTab(272428..272428),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_sections_unknown_gaps() {
+        let mut data = std::fs::read("./tests/ohos/tzdata").unwrap();
+        // Skip the first index entry, leaving a gap after the header.
+        data[12..16].copy_from_slice(&72u32.to_be_bytes());
+        // Point the zonetab past the end of the file.
+        data[20..24].copy_from_slice(&300000u32.to_be_bytes());
+        let db = ZoneInfoDb::new_ohos(&data[..]).unwrap();
+        assert_eq!(
+            db.sections(),
+            [
+                Section::Header(0..24),
+                Section::Unknown { range: 24..72 },
+                Section::Index(72..21240),
+                Section::Data(21240..272428),
+                Section::ZoneTab(272428..272428),
+            ]
+        );
+    }
+}
@@ -0,0 +1,141 @@
+//! The zone the system is set to.
+#[cfg(feature = "tzif")]
+use crate::{Result, TzDataIndex, ZoneInfo, ZoneInfoDb};
+
+/// Name of the zone the system is set to, e.g. `Asia/Shanghai`.
+///
+/// On Android this is the `persist.sys.timezone` system property, and on HarmonyOS NEXT the
+/// `persist.time.timezone` system parameter. Elsewhere, or if those are unset, `$TZ` is used,
+/// then the target of the `/etc/localtime` link; a path into a `zoneinfo` directory is reduced
+/// to the zone name.
+pub fn system_timezone_name() -> Option<String> {
+    system_property()
+        .or_else(|| std::env::var("TZ").ok())
+        .or_else(|| Some(std::fs::read_link("/etc/localtime").ok()?.to_str()?.to_string()))
+        .and_then(|name| zone_name(&name))
+}
+
+/// Strip the `:` prefix of `$TZ` and anything up to a `zoneinfo/` directory.
+fn zone_name(value: &str) -> Option<String> {
+    let name = value.strip_prefix(':').unwrap_or(value);
+    let name = name.rfind("zoneinfo/").map_or(name, |start| &name[start + "zoneinfo/".len()..]);
+    (!name.is_empty()).then(|| name.to_string())
+}
+
+#[cfg(target_os = "android")]
+fn system_property() -> Option<String> {
+    /// Size of a property value buffer, including the NUL.
+    const PROP_VALUE_MAX: usize = 92;
+    unsafe extern "C" {
+        fn __system_property_get(
+            name: *const core::ffi::c_char,
+            value: *mut core::ffi::c_char,
+        ) -> core::ffi::c_int;
+    }
+
+    let mut value = [0u8; PROP_VALUE_MAX];
+    // SAFETY: the name is NUL-terminated and the buffer is PROP_VALUE_MAX bytes, as bionic
+    // requires.
+    let len = unsafe {
+        __system_property_get(c"persist.sys.timezone".as_ptr(), value.as_mut_ptr().cast())
+    };
+    if len <= 0 {
+        return None;
+    }
+    core::ffi::CStr::from_bytes_until_nul(&value).ok()?.to_str().ok().map(str::to_string)
+}
+
+#[cfg(target_env = "ohos")]
+fn system_property() -> Option<String> {
+    use core::ffi::{c_char, c_int, c_void};
+
+    type GetParameter =
+        unsafe extern "C" fn(*const c_char, *const c_char, *mut c_char, u32) -> c_int;
+    unsafe extern "C" {
+        fn dlopen(filename: *const c_char, flags: c_int) -> *mut c_void;
+        fn dlsym(handle: *mut c_void, symbol: *const c_char) -> *mut c_void;
+    }
+    const RTLD_NOW: c_int = 2;
+    const PARAM_VALUE_LEN_MAX: usize = 96;
+
+    // The parameter service isn't part of the NDK, so look it up at run time and fall back to
+    // `$TZ` where it's missing.
+    // SAFETY: both strings are NUL-terminated; a null handle or symbol is checked below.
+    let handle = unsafe { dlopen(c"libbegetutil.z.so".as_ptr(), RTLD_NOW) };
+    if handle.is_null() {
+        return None;
+    }
+    // SAFETY: as above.
+    let symbol = unsafe { dlsym(handle, c"GetParameter".as_ptr()) };
+    if symbol.is_null() {
+        return None;
+    }
+    // SAFETY: `GetParameter` has this signature in `parameter.h` of the init subsystem.
+    let get_parameter: GetParameter = unsafe { core::mem::transmute(symbol) };
+    let mut value = [0u8; PARAM_VALUE_LEN_MAX];
+    // SAFETY: the key and default are NUL-terminated and the length is that of the buffer.
+    let len = unsafe {
+        get_parameter(
+            c"persist.time.timezone".as_ptr(),
+            c"".as_ptr(),
+            value.as_mut_ptr().cast(),
+            value.len() as u32,
+        )
+    };
+    if len <= 0 {
+        return None;
+    }
+    core::ffi::CStr::from_bytes_until_nul(&value).ok()?.to_str().ok().map(str::to_string)
+}
+
+#[cfg(not(any(target_os = "android", target_env = "ohos")))]
+fn system_property() -> Option<String> {
+    None
+}
+
+#[cfg(feature = "tzif")]
+impl ZoneInfoDb {
+    /// The zone the system is set to, see [`system_timezone_name`], with its index entry and
+    /// parsed data, giving local time end to end.
+    ///
+    /// Returns `None` if no zone is set or it isn't in the database.
+    pub fn system_default_zone(&self) -> Result<Option<(&TzDataIndex, ZoneInfo)>> {
+        let Some(name) = system_timezone_name() else { return Ok(None) };
+        self.default_zone_for(&name)
+    }
+
+    /// The zone named `name` with its index entry and parsed data, or `None` if it isn't in the
+    /// database.
+    pub(crate) fn default_zone_for(&self, name: &str) -> Result<Option<(&TzDataIndex, ZoneInfo)>> {
+        let Some(index) = self.indexes.find_timezone(name.as_bytes()) else { return Ok(None) };
+        let Some(tzdata) = self.tzdata(index) else { return Ok(None) };
+        Ok(Some((index, ZoneInfo::new(tzdata)?)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_zone_name() {
+        assert_eq!(zone_name(":Asia/Shanghai").as_deref(), Some("Asia/Shanghai"));
+        assert_eq!(
+            zone_name("/usr/share/zoneinfo/America/Argentina/Salta").as_deref(),
+            Some("America/Argentina/Salta")
+        );
+        assert_eq!(zone_name("../usr/share/zoneinfo/UTC").as_deref(), Some("UTC"));
+        assert_eq!(zone_name(":"), None);
+    }
+
+    #[cfg(feature = "tzif")]
+    #[test]
+    fn test_default_zone_for() {
+        let db = ZoneInfoDb::new_ohos(std::fs::File::open("./tests/ohos/tzdata").unwrap()).unwrap();
+        let name = zone_name(":/usr/share/zoneinfo/Asia/Shanghai").unwrap();
+        let (index, zone_info) = db.default_zone_for(&name).unwrap().unwrap();
+        assert_eq!(&*index.name, b"Asia/Shanghai");
+        assert_eq!(zone_info.raw_offset(), 8 * 3600);
+        assert!(db.default_zone_for("Asia/Nowhere").unwrap().is_none());
+    }
+}
@@ -0,0 +1,156 @@
+//! Differences between two `tzdata` databases.
+use std::{cmp::Ordering, fmt::Write, sync::Arc};
+
+use crate::{Result, TzdbVersion, ZoneInfoDb, ZoneInfoError};
+
+/// Zones added, removed or changed from one database to another, each sorted by name.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct TzDataDiff {
+    /// The old and new versions, if they differ.
+    pub version: Option<([u8; 5], [u8; 5])>,
+    /// Zones only in the new database.
+    pub added: Vec<Arc<[u8]>>,
+    /// Zones only in the old database.
+    pub removed: Vec<Arc<[u8]>>,
+    /// Zones in both whose TZif data differs byte-wise.
+    pub changed: Vec<Arc<[u8]>>,
+}
+
+impl TzDataDiff {
+    /// Whether the databases have the same version and zones.
+    pub fn is_empty(&self) -> bool {
+        self.version.is_none()
+            && self.added.is_empty()
+            && self.removed.is_empty()
+            && self.changed.is_empty()
+    }
+
+    /// Human-readable summary for release notes, a line for the versions and one for each
+    /// non-empty list of zones, e.g.:
+    ///
+    /// ```text
+    /// tzdata 2024a -> 2024b
+    /// added: Asia/Nowhere
+    /// changed: Asia/Shanghai, Asia/Tokyo
+    /// ```
+    pub fn summary(&self) -> String {
+        let mut out = match self.version {
+            Some((old, new)) => {
+                format!("tzdata {} -> {}\n", old.escape_ascii(), new.escape_ascii())
+            }
+            None => "tzdata version unchanged\n".to_string(),
+        };
+        for (label, zones) in
+            [("added", &self.added), ("removed", &self.removed), ("changed", &self.changed)]
+        {
+            if zones.is_empty() {
+                continue;
+            }
+            let names: Vec<_> = zones.iter().map(|x| String::from_utf8_lossy(x)).collect();
+            writeln!(out, "{label}: {}", names.join(", ")).unwrap();
+        }
+        if self.added.is_empty() && self.removed.is_empty() && self.changed.is_empty() {
+            out.push_str("no zone changes\n");
+        }
+        out
+    }
+}
+
+/// Zones whose rules changed from an older database to a newer one, i.e. the added and changed
+/// zones, sorted by name.
+///
+/// Fails with [`ZoneInfoError::InvalidTzVersion`] if either header version isn't a tzdb release
+/// like `2024a`, and with [`ZoneInfoError::VersionDowngrade`] if `new` is of an older release
+/// than `old`.
+pub fn changed_zones_between(old: &ZoneInfoDb, new: &ZoneInfoDb) -> Result<Vec<Arc<[u8]>>> {
+    let version = |db: &ZoneInfoDb| {
+        let version = TzdbVersion::from_bytes(db.header().version);
+        version.year().map(|_| version).ok_or(ZoneInfoError::InvalidTzVersion)
+    };
+    let (old_version, new_version) = (version(old)?, version(new)?);
+    if new_version < old_version {
+        return Err(ZoneInfoError::VersionDowngrade {
+            old: *old_version.as_bytes(),
+            new: *new_version.as_bytes(),
+        });
+    }
+    let diff = diff(old, new);
+    let mut zones = diff.added;
+    zones.extend(diff.changed);
+    zones.sort_unstable();
+    Ok(zones)
+}
+
+/// Compare an old database with a new one.
+pub fn diff(old: &ZoneInfoDb, new: &ZoneInfoDb) -> TzDataDiff {
+    let (old_version, new_version) = (old.header().version, new.header().version);
+    let mut diff = TzDataDiff {
+        version: (old_version != new_version).then_some((old_version, new_version)),
+        ..Default::default()
+    };
+
+    // Both indexes are sorted by name, so walk them in step.
+    let mut old_zones = old.indexes().timezones().iter().peekable();
+    let mut new_zones = new.indexes().timezones().iter().peekable();
+    loop {
+        let order = match (old_zones.peek(), new_zones.peek()) {
+            (None, None) => break,
+            (Some(_), None) => Ordering::Less,
+            (None, Some(_)) => Ordering::Greater,
+            (Some(a), Some(b)) => a.name.cmp(&b.name),
+        };
+        match order {
+            Ordering::Less => diff.removed.push(old_zones.next().unwrap().name.clone()),
+            Ordering::Greater => diff.added.push(new_zones.next().unwrap().name.clone()),
+            Ordering::Equal => {
+                let (a, b) = (old_zones.next().unwrap(), new_zones.next().unwrap());
+                if old.tzdata(a) != new.tzdata(b) {
+                    diff.changed.push(a.name.clone());
+                }
+            }
+        }
+    }
+    diff
+}
+
+#[cfg(all(test, feature = "builder"))]
+mod tests {
+    use std::fs::File;
+
+    use super::*;
+    use crate::TzDataFlavor;
+    use crate::testutil::fixture_builder;
+
+    #[test]
+    fn test_diff() {
+        let old = ZoneInfoDb::new_ohos(File::open("./tests/ohos/tzdata").unwrap()).unwrap();
+        assert!(diff(&old, &old).is_empty());
+
+        let mut builder = fixture_builder(&old, *b"2024b");
+        builder.filter(|name| name != b"Africa/Abidjan");
+        let tokyo = old.get(b"Asia/Tokyo").unwrap();
+        builder.zone("Asia/Shanghai", tokyo).zone("Asia/Nowhere", tokyo);
+        let new = builder.build(TzDataFlavor::Ohos).unwrap();
+        let new = ZoneInfoDb::new_ohos(&new[..]).unwrap();
+
+        let diff = diff(&old, &new);
+        assert_eq!(diff.version, Some((*b"2024a", *b"2024b")));
+        assert_eq!(diff.added, [Arc::from(&b"Asia/Nowhere"[..])]);
+        assert_eq!(diff.removed, [Arc::from(&b"Africa/Abidjan"[..])]);
+        assert_eq!(diff.changed, [Arc::from(&b"Asia/Shanghai"[..])]);
+        assert_eq!(
+            diff.summary(),
+            "tzdata 2024a -> 2024b\nadded: Asia/Nowhere\nremoved: Africa/Abidjan\n\
+             changed: Asia/Shanghai\n"
+        );
+        assert_eq!(TzDataDiff::default().summary(), "tzdata version unchanged\nno zone changes\n");
+
+        let changed = changed_zones_between(&old, &new).unwrap();
+        assert_eq!(changed, [Arc::from(&b"Asia/Nowhere"[..]), Arc::from(&b"Asia/Shanghai"[..])]);
+        assert!(matches!(
+            changed_zones_between(&new, &old),
+            Err(ZoneInfoError::VersionDowngrade { old, new }) if old == *b"2024b" && new == *b"2024a"
+        ));
+    }
+}
@@ -0,0 +1,66 @@
+//! A minimal `tzdata` file bundled into the binary, behind the `embedded-fallback` feature.
+use std::sync::OnceLock;
+
+use crate::ZoneInfoDb;
+
+/// Android flavor `tzdata` built from tzdata 2021a with a few widely used zones and their zone.tab
+/// rows; see the test for the exact list.
+static EMBEDDED_TZDATA: &[u8] = include_bytes!("../data/tzdata");
+
+impl ZoneInfoDb {
+    /// A database bundled into the binary, for a deterministic last resort when the files of
+    /// the device are missing or unreadable.
+    ///
+    /// It holds `UTC`, `Etc/UTC`, `GMT` and one zone for each of a dozen large cities, of tzdata
+    /// 2021a, so it's neither complete nor current. Parsed on first use.
+    pub fn embedded_fallback() -> &'static ZoneInfoDb {
+        static DB: OnceLock<ZoneInfoDb> = OnceLock::new();
+        DB.get_or_init(|| {
+            ZoneInfoDb::from_bytes(EMBEDDED_TZDATA).expect("the embedded tzdata is valid")
+        })
+    }
+}
+
+#[cfg(all(test, feature = "builder", feature = "zonetab"))]
+mod tests {
+    use std::fs::File;
+
+    use super::*;
+    use crate::{TzDataBuilder, TzDataFlavor};
+
+    const ZONE_TAB: &str = "\
+US\t+404251-0740023\tAmerica/New_York\tEastern (most areas)
+US\t+415100-0873900\tAmerica/Chicago\tCentral (most areas)
+US\t+394421-1045903\tAmerica/Denver\tMountain (most areas)
+US\t+340308-1181434\tAmerica/Los_Angeles\tPacific
+BR\t-2332-04637\tAmerica/Sao_Paulo\tBrazil (southeast: GO, DF, MG, ES, RJ, SP, PR, SC, RS)
+GB\t+513030-0000731\tEurope/London
+FR\t+4852+00220\tEurope/Paris
+DE\t+5230+01322\tEurope/Berlin\tmost of Germany
+RU\t+554521+0373704\tEurope/Moscow\tMSK+00 - Moscow area
+IN\t+2232+08822\tAsia/Kolkata
+CN\t+3114+12128\tAsia/Shanghai\tBeijing Time
+JP\t+353916+1394441\tAsia/Tokyo
+AU\t-3352+15113\tAustralia/Sydney\tNew South Wales (most areas)
+";
+
+    /// The bundled file must stay reproducible from the Android fixture.
+    #[test]
+    fn test_embedded_fallback() {
+        let fixture =
+            ZoneInfoDb::new_android(File::open("./tests/android/tzdata").unwrap()).unwrap();
+        let mut builder = TzDataBuilder::new(fixture.header().version);
+        let zones = ZONE_TAB.lines().map(|x| x.split('\t').nth(2).unwrap());
+        for name in ["UTC", "Etc/UTC", "GMT"].into_iter().chain(zones) {
+            builder.zone(name, fixture.get(name.as_bytes()).unwrap());
+        }
+        builder.zone_tab(ZONE_TAB);
+        assert_eq!(builder.build(TzDataFlavor::Android).unwrap(), EMBEDDED_TZDATA);
+
+        let db = ZoneInfoDb::embedded_fallback();
+        assert_eq!(db.zone_count(), 16);
+        assert_eq!(db.get(b"Asia/Shanghai"), fixture.get(b"Asia/Shanghai"));
+        assert_eq!(db.zone_tab().unwrap().country_for_zone(b"Europe/Paris"), Some("FR"));
+        assert!(std::ptr::eq(db, ZoneInfoDb::embedded_fallback()));
+    }
+}
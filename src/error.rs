@@ -0,0 +1,118 @@
+//! Errors of parsing `tzdata` files and their contents.
+use alloc::sync::Arc;
+#[cfg(feature = "std")]
+use std::io;
+
+use thiserror::Error;
+
+/// Result of the crate's fallible operations.
+pub type Result<T, E = ZoneInfoError> = core::result::Result<T, E>;
+
+/// Error of parsing a `tzdata` file, the zone data it contains, or reading it.
+#[derive(Debug, Error)]
+#[non_exhaustive]
+pub enum ZoneInfoError {
+    /// The file is shorter than its header.
+    #[error("truncated tzdata header")]
+    TruncatedHeader,
+    /// The file doesn't start with `tzdata` followed by a version.
+    #[error("invalid tzdata header magic")]
+    InvalidMagic,
+    /// The header's section offsets are out of order or past the end of the file.
+    #[error("invalid tzdata section layout")]
+    InvalidLayout,
+    /// A header offset puts a section before the end of the previous one, see
+    /// [`TzDataHeader::validate_layout`](crate::TzDataHeader::validate_layout).
+    #[error("tzdata {second} section starts inside the {first} section")]
+    OverlappingSections { first: &'static str, second: &'static str },
+    /// A section starts past the end of the file.
+    #[error(
+        "tzdata {section} section starts at byte {offset}, past the end of the {file_len}-byte file"
+    )]
+    SectionPastEnd { section: &'static str, offset: u32, file_len: u64 },
+    /// The index region ends in a partial entry, or lies outside the file.
+    #[error("truncated tzdata index")]
+    TruncatedIndex,
+    /// An index entry's name isn't NUL-terminated, reported by the strict index parsers.
+    #[error("malformed tzdata index entry {entry} at byte {offset}")]
+    MalformedEntry {
+        /// Number of the entry, from 0.
+        entry: usize,
+        /// Offset of the entry in the file.
+        offset: u64,
+    },
+    /// An index entry points outside the data section.
+    #[error("tzdata index entry out of bounds")]
+    EntryOutOfBounds,
+    /// A zone name is empty or not printable ASCII.
+    #[error("invalid tzdata zone name")]
+    InvalidZoneName,
+    /// The index entries are not sorted by name.
+    #[error("tzdata index entries are not sorted")]
+    UnsortedIndex,
+    /// The index matches neither the Android nor the OpenHarmony layout.
+    #[error("unknown tzdata flavor")]
+    UnknownFlavor,
+    /// The file, or its index or an entry, exceeds a size limit, see [`ParseOptions`](crate::ParseOptions).
+    #[error("tzdata file too large")]
+    TooLarge,
+    /// The TZif data of a zone is malformed.
+    #[error("invalid TZif data: {0}")]
+    InvalidTzif(&'static str),
+    /// The zonetab section is malformed.
+    #[error("invalid zone.tab: {0}")]
+    InvalidZoneTab(&'static str),
+    /// A `tz_version` file is malformed.
+    #[error("invalid tz_version")]
+    InvalidTzVersion,
+    /// The header version of a tzdata file differs from its `tz_version` file.
+    #[error(
+        "tzdata version {} does not match tz_version {}",
+        tzdata.escape_ascii(),
+        tz_version.escape_ascii()
+    )]
+    VersionMismatch { tzdata: [u8; 5], tz_version: [u8; 5] },
+    /// The database expected to be newer is of an older tzdb release.
+    #[error("tzdata version {} is older than {}", new.escape_ascii(), old.escape_ascii())]
+    VersionDowngrade { old: [u8; 5], new: [u8; 5] },
+    /// A `tzlookup.xml` file is malformed.
+    #[error("invalid tzlookup.xml: {0}")]
+    InvalidTzLookup(&'static str),
+    /// A cache written by [`ZoneInfoDb::write_cache`](crate::ZoneInfoDb::write_cache) is corrupt
+    /// or of another format version.
+    #[error("invalid zoneinfo cache: {0}")]
+    InvalidCache(&'static str),
+    /// A zone's TZif data would be rejected by Android's reader, see
+    /// [`TzDataBuilder::strict_android`](crate::TzDataBuilder::strict_android).
+    #[error("zone {} breaks Android's tzdata constraints: {reason}", zone.escape_ascii())]
+    AndroidConstraint { zone: Arc<[u8]>, reason: &'static str },
+    /// A patch created by [`TzDataPatch::create`](crate::TzDataPatch::create) is corrupt, of
+    /// another format version, or doesn't apply to the file.
+    #[error("invalid tzdata patch: {0}")]
+    InvalidPatch(&'static str),
+    /// The file is compressed with a format whose feature isn't enabled, e.g. `gzip` without
+    /// `flate2`.
+    #[error("tzdata is {0}-compressed, but the feature decompressing it isn't enabled")]
+    UnsupportedCompression(&'static str),
+    /// A file couldn't be read as a zip archive.
+    #[cfg(feature = "zip")]
+    #[error("invalid zip archive: {0}")]
+    Zip(zip::result::ZipError),
+    /// The TZif data of a zone was rejected by jiff.
+    #[cfg(feature = "jiff")]
+    #[error("jiff rejected TZif data: {0}")]
+    Jiff(jiff::Error),
+    #[cfg(feature = "std")]
+    #[error(transparent)]
+    Io(#[from] io::Error),
+}
+
+#[cfg(feature = "std")]
+impl From<ZoneInfoError> for io::Error {
+    fn from(err: ZoneInfoError) -> Self {
+        match err {
+            ZoneInfoError::Io(err) => err,
+            err => io::Error::other(err),
+        }
+    }
+}
@@ -0,0 +1,69 @@
+//! Display city names derived from zone IDs.
+use alloc::{borrow::Cow, format, string::String};
+
+/// Derive the exemplar city shown for a zone, e.g. `Buenos Aires` for
+/// `America/Argentina/Buenos_Aires`.
+///
+/// Returns `None` for zones that don't name a place, such as `UTC`, `EST5EDT` or `Etc/GMT+8`.
+pub fn exemplar_city(zone_id: &str) -> Option<Cow<'_, str>> {
+    let (area, city) = zone_id.split_once('/')?;
+    if area == "Etc" || area == "SystemV" {
+        return None;
+    }
+    // The city is the last segment: `America/Argentina/Buenos_Aires`, `America/North_Dakota/Center`.
+    let city = city.rsplit('/').next().unwrap_or(city);
+    if city.is_empty() {
+        return None;
+    }
+    Some(match city {
+        "St_Johns" => Cow::Borrowed("St. John's"),
+        _ if city.contains('_') => {
+            let city = match city.strip_prefix("St_") {
+                Some(rest) => Cow::Owned(format!("St. {rest}")),
+                None => Cow::Borrowed(city),
+            };
+            Cow::Owned(city.replace('_', " "))
+        }
+        _ => Cow::Borrowed(city),
+    })
+}
+
+/// Like [`exemplar_city`], but asks `localize` first so callers can supply per-locale names,
+/// falling back to the derived name when it returns `None`.
+pub fn exemplar_city_with(
+    zone_id: &str,
+    localize: impl FnOnce(&str) -> Option<String>,
+) -> Option<Cow<'_, str>> {
+    match localize(zone_id) {
+        Some(city) => Some(Cow::Owned(city)),
+        None => exemplar_city(zone_id),
+    }
+}
+
+#[cfg(all(test, feature = "std"))]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_exemplar_city() {
+        assert_eq!(exemplar_city("Asia/Shanghai").as_deref(), Some("Shanghai"));
+        assert_eq!(
+            exemplar_city("America/Argentina/Buenos_Aires").as_deref(),
+            Some("Buenos Aires")
+        );
+        assert_eq!(exemplar_city("America/North_Dakota/New_Salem").as_deref(), Some("New Salem"));
+        assert_eq!(exemplar_city("America/Port-au-Prince").as_deref(), Some("Port-au-Prince"));
+        assert_eq!(exemplar_city("America/St_Johns").as_deref(), Some("St. John's"));
+        assert_eq!(exemplar_city("America/St_Vincent").as_deref(), Some("St. Vincent"));
+        assert_eq!(exemplar_city("Etc/GMT+8"), None);
+        assert_eq!(exemplar_city("UTC"), None);
+        assert_eq!(exemplar_city("EST5EDT"), None);
+    }
+
+    #[test]
+    fn test_exemplar_city_with() {
+        let zh = |zone_id: &str| (zone_id == "Asia/Shanghai").then(|| "上海".to_string());
+        assert_eq!(exemplar_city_with("Asia/Shanghai", zh).as_deref(), Some("上海"));
+        assert_eq!(exemplar_city_with("Asia/Ho_Chi_Minh", zh).as_deref(), Some("Ho Chi Minh"));
+    }
+}
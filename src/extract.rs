@@ -0,0 +1,70 @@
+//! Conversion of a `tzdata` file into a `/usr/share/zoneinfo`-style directory tree.
+use std::{
+    fs,
+    path::{Path, PathBuf},
+};
+
+#[cfg(feature = "rayon")]
+use rayon::prelude::*;
+
+use crate::{Result, ZoneInfoDb, ZoneInfoError};
+
+impl ZoneInfoDb {
+    /// Write the TZif data of every zone to `dir/Area/Location`, creating directories as
+    /// needed, and return the number of files written.
+    ///
+    /// Existing files are overwritten. Names that are empty, absolute or have `.`, `..` or empty
+    /// components fail with [`ZoneInfoError::InvalidZoneName`] before anything is written, so a
+    /// forged index can't write outside of `dir`. With the `rayon` feature, files are written
+    /// across threads.
+    pub fn extract_all(&self, dir: impl AsRef<Path>) -> Result<usize> {
+        let dir = dir.as_ref();
+        let zones: Vec<_> = self
+            .iter()
+            .map(|(name, data)| {
+                let name =
+                    core::str::from_utf8(name).map_err(|_| ZoneInfoError::InvalidZoneName)?;
+                if name.split('/').any(|x| matches!(x, "" | "." | "..") || x.contains('\\')) {
+                    return Err(ZoneInfoError::InvalidZoneName);
+                }
+                Ok((dir.join(name), data))
+            })
+            .collect::<Result<_>>()?;
+        let write = |(path, data): &(PathBuf, &[u8])| -> Result<()> {
+            if let Some(parent) = path.parent() {
+                fs::create_dir_all(parent)?;
+            }
+            Ok(fs::write(path, data)?)
+        };
+        #[cfg(feature = "rayon")]
+        zones.par_iter().try_for_each(write)?;
+        #[cfg(not(feature = "rayon"))]
+        zones.iter().try_for_each(write)?;
+        Ok(zones.len())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fs::File;
+
+    use super::*;
+
+    #[test]
+    fn test_extract_all() {
+        let dir = std::env::temp_dir().join(format!("zoneinfo-db-extract-{}", std::process::id()));
+        let db = ZoneInfoDb::new_ohos(File::open("./tests/ohos/tzdata").unwrap()).unwrap();
+        assert_eq!(db.extract_all(&dir).unwrap(), 442);
+        let shanghai = fs::read(dir.join("Asia/Shanghai")).unwrap();
+        assert_eq!(shanghai, db.get(b"Asia/Shanghai").unwrap());
+        assert!(dir.join("America/Argentina/Buenos_Aires").is_file());
+        fs::remove_dir_all(&dir).unwrap();
+
+        // A forged first entry, formerly `Africa/Abidjan`.
+        let mut data = fs::read("./tests/ohos/tzdata").unwrap();
+        data[24..24 + 16].copy_from_slice(b"../../etc/hosts\0");
+        let db = ZoneInfoDb::new_ohos(&data[..]).unwrap();
+        assert!(matches!(db.extract_all(&dir), Err(ZoneInfoError::InvalidZoneName)));
+        assert!(!dir.exists());
+    }
+}
@@ -0,0 +1,113 @@
+//! Content fingerprints of a `tzdata` file and of its zones.
+use crate::ZoneInfoDb;
+
+impl ZoneInfoDb {
+    /// SHA-256 of the whole file, header, index, data and zonetab, the same as `sha256sum`
+    /// prints. Devices with equal fingerprints have byte-identical databases.
+    pub fn fingerprint(&self) -> [u8; 32] {
+        sha256(&self.data)
+    }
+
+    /// SHA-256 of the TZif data of a zone, or `None` if there's no such zone. Unlike
+    /// [`ZoneInfoDb::fingerprint`], this doesn't change when other zones or the layout do.
+    pub fn zone_fingerprint(&self, tz_name: &[u8]) -> Option<[u8; 32]> {
+        self.get(tz_name).map(sha256)
+    }
+}
+
+/// SHA-256 as specified in FIPS 180-4.
+pub(crate) fn sha256(data: &[u8]) -> [u8; 32] {
+    const K: [u32; 64] = [
+        0x428a2f98, 0x71374491, 0xb5c0fbcf, 0xe9b5dba5, 0x3956c25b, 0x59f111f1, 0x923f82a4,
+        0xab1c5ed5, 0xd807aa98, 0x12835b01, 0x243185be, 0x550c7dc3, 0x72be5d74, 0x80deb1fe,
+        0x9bdc06a7, 0xc19bf174, 0xe49b69c1, 0xefbe4786, 0x0fc19dc6, 0x240ca1cc, 0x2de92c6f,
+        0x4a7484aa, 0x5cb0a9dc, 0x76f988da, 0x983e5152, 0xa831c66d, 0xb00327c8, 0xbf597fc7,
+        0xc6e00bf3, 0xd5a79147, 0x06ca6351, 0x14292967, 0x27b70a85, 0x2e1b2138, 0x4d2c6dfc,
+        0x53380d13, 0x650a7354, 0x766a0abb, 0x81c2c92e, 0x92722c85, 0xa2bfe8a1, 0xa81a664b,
+        0xc24b8b70, 0xc76c51a3, 0xd192e819, 0xd6990624, 0xf40e3585, 0x106aa070, 0x19a4c116,
+        0x1e376c08, 0x2748774c, 0x34b0bcb5, 0x391c0cb3, 0x4ed8aa4a, 0x5b9cca4f, 0x682e6ff3,
+        0x748f82ee, 0x78a5636f, 0x84c87814, 0x8cc70208, 0x90befffa, 0xa4506ceb, 0xbef9a3f7,
+        0xc67178f2,
+    ];
+    let mut state: [u32; 8] = [
+        0x6a09e667, 0xbb67ae85, 0x3c6ef372, 0xa54ff53a, 0x510e527f, 0x9b05688c, 0x1f83d9ab,
+        0x5be0cd19,
+    ];
+
+    // The message, a 1 bit, zeros up to 8 bytes short of a block, and the length in bits.
+    let mut tail = [0u8; 128];
+    let rest = data.chunks_exact(64).remainder();
+    tail[..rest.len()].copy_from_slice(rest);
+    tail[rest.len()] = 0x80;
+    let tail_len = if rest.len() < 56 { 64 } else { 128 };
+    tail[tail_len - 8..tail_len].copy_from_slice(&(data.len() as u64 * 8).to_be_bytes());
+
+    for block in data.chunks_exact(64).chain(tail[..tail_len].chunks_exact(64)) {
+        let mut w = [0u32; 64];
+        for (w, word) in w.iter_mut().zip(block.chunks_exact(4)) {
+            *w = u32::from_be_bytes(word.try_into().unwrap());
+        }
+        for i in 16..64 {
+            let s0 = w[i - 15].rotate_right(7) ^ w[i - 15].rotate_right(18) ^ (w[i - 15] >> 3);
+            let s1 = w[i - 2].rotate_right(17) ^ w[i - 2].rotate_right(19) ^ (w[i - 2] >> 10);
+            w[i] = w[i - 16].wrapping_add(s0).wrapping_add(w[i - 7]).wrapping_add(s1);
+        }
+        let [mut a, mut b, mut c, mut d, mut e, mut f, mut g, mut h] = state;
+        for i in 0..64 {
+            let s1 = e.rotate_right(6) ^ e.rotate_right(11) ^ e.rotate_right(25);
+            let ch = (e & f) ^ (!e & g);
+            let t1 = h.wrapping_add(s1).wrapping_add(ch).wrapping_add(K[i]).wrapping_add(w[i]);
+            let s0 = a.rotate_right(2) ^ a.rotate_right(13) ^ a.rotate_right(22);
+            let maj = (a & b) ^ (a & c) ^ (b & c);
+            let t2 = s0.wrapping_add(maj);
+            (h, g, f, e, d, c, b, a) = (g, f, e, d.wrapping_add(t1), c, b, a, t1.wrapping_add(t2));
+        }
+        for (state, x) in state.iter_mut().zip([a, b, c, d, e, f, g, h]) {
+            *state = state.wrapping_add(x);
+        }
+    }
+
+    let mut out = [0; 32];
+    for (out, word) in out.chunks_exact_mut(4).zip(state) {
+        out.copy_from_slice(&word.to_be_bytes());
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fs::File;
+
+    use super::*;
+
+    fn hex(bytes: &[u8]) -> String {
+        bytes.iter().map(|x| format!("{x:02x}")).collect()
+    }
+
+    #[test]
+    fn test_fingerprint() {
+        assert_eq!(
+            hex(&sha256(b"")),
+            "e3b0c44298fc1c149afbf4c8996fb92427ae41e4649b934ca495991b7852b855"
+        );
+        assert_eq!(
+            hex(&sha256(b"abcdbcdecdefdefgefghfghighijhijkijkljklmklmnlmnomnopnopq")),
+            "248d6a61d20638b8e5c026930c3e6039a33ce45964ff2167f6ecedd419db06c1"
+        );
+
+        let db = ZoneInfoDb::new_ohos(File::open("./tests/ohos/tzdata").unwrap()).unwrap();
+        assert_eq!(
+            hex(&db.fingerprint()),
+            "562eeec1a61e6d46a9b32bf5c49bddbab6d23a55c84048047675805a90bbd634"
+        );
+        let android =
+            ZoneInfoDb::new_android(File::open("./tests/android/tzdata").unwrap()).unwrap();
+        assert_ne!(android.fingerprint(), db.fingerprint());
+        let shanghai = db.zone_fingerprint(b"Asia/Shanghai").unwrap();
+        assert_eq!(shanghai, sha256(db.get(b"Asia/Shanghai").unwrap()));
+        let chongqing = android.zone_fingerprint(b"Asia/Chongqing");
+        assert_eq!(chongqing, android.zone_fingerprint(b"Asia/Shanghai"));
+        assert_ne!(db.zone_fingerprint(b"Europe/Paris"), Some(shanghai));
+        assert_eq!(db.zone_fingerprint(b"Asia/Nowhere"), None);
+    }
+}
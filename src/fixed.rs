@@ -0,0 +1,305 @@
+//! Synthetic fixed-offset zones, for databases trimmed of `UTC`, `GMT` and `Etc/GMT±N`.
+#[cfg(feature = "std")]
+use alloc::borrow::Cow;
+use alloc::{
+    format,
+    string::{String, ToString},
+    vec::Vec,
+};
+
+#[cfg(feature = "std")]
+use crate::ZoneInfoDb;
+#[cfg(feature = "tzif")]
+use crate::{Result, ZoneInfo, ZoneInfoError};
+
+/// Synthesize the TZif data of a fixed-offset zone: `UTC` and its `UCT`, `Universal` and `Zulu`
+/// links, `GMT` and its `GMT0`, `GMT±0` and `Greenwich` links, their `Etc/` forms, and
+/// `Etc/GMT±N`. `UT`, which tzdb doesn't define but RFC 2822 dates use, is taken as `UTC`.
+///
+/// The output matches what zic emits for these zones: a version 2 file with a single local time
+/// type, no transitions and a POSIX TZ footer. Returns `None` for any other name.
+pub fn fixed_offset_tzdata(tz_name: &[u8]) -> Option<Vec<u8>> {
+    let name = tz_name.strip_prefix(b"Etc/").unwrap_or(tz_name);
+    let (utoff, abbr) = match name {
+        b"UTC" | b"UCT" | b"Universal" | b"Zulu" => (0, "UTC"),
+        b"UT" if name.len() == tz_name.len() => (0, "UTC"),
+        b"GMT" | b"GMT0" | b"GMT+0" | b"GMT-0" | b"Greenwich" => (0, "GMT"),
+        // Only the `Etc/` area carries the non-zero offsets.
+        _ if name.len() == tz_name.len() => return None,
+        _ => {
+            let hours = name.strip_prefix(b"GMT")?;
+            let (sign, digits) = hours.split_first()?;
+            if digits.is_empty() || digits.len() > 2 || digits.starts_with(b"0") {
+                return None;
+            }
+            let hours: i32 = core::str::from_utf8(digits).ok()?.parse().ok()?;
+            // POSIX style: `Etc/GMT+8` is 8 hours *behind* UTC.
+            let hours = match sign {
+                b'+' if hours <= 12 => -hours,
+                b'-' if hours <= 14 => hours,
+                _ => return None,
+            };
+            return fixed_tzdata(hours * 3600);
+        }
+    };
+    Some(fixed_tzif(utoff, abbr, &format!("{abbr}0")))
+}
+
+/// Synthesize the TZif data of a zone that is always `utoff` seconds ahead of UTC, e.g. for a
+/// "UTC+09:30" user selection.
+///
+/// The abbreviation is numeric like zic's `%z` (`+0930`, `-08`). Returns `None` if the offset
+/// isn't within a day of UTC.
+pub fn fixed_tzdata(utoff: i32) -> Option<Vec<u8>> {
+    const DAY: i32 = 24 * 3600;
+    if utoff <= -DAY || utoff >= DAY {
+        return None;
+    }
+    let abbr = format!("{}{}", if utoff < 0 { '-' } else { '+' }, hhmmss(utoff, ""));
+    Some(fixed_tzif(utoff, &abbr, &fixed_posix(utoff, &abbr)))
+}
+
+/// POSIX TZ string of a zone always `utoff` seconds ahead of UTC. POSIX offsets count
+/// westwards: `<+0930>-9:30`.
+fn fixed_posix(utoff: i32, abbr: &str) -> String {
+    format!("<{abbr}>{}{}", if utoff > 0 { "-" } else { "" }, hhmmss(utoff, ":"))
+}
+
+/// Format the magnitude of an offset as hours with minutes and seconds appended only when they
+/// are non-zero. Hours take two digits unless `sep` is given, as POSIX TZ strings do.
+fn hhmmss(offset: i32, sep: &str) -> String {
+    let offset = offset.unsigned_abs();
+    let (hours, minutes, seconds) = (offset / 3600, offset / 60 % 60, offset % 60);
+    let mut out = if sep.is_empty() { format!("{hours:02}") } else { hours.to_string() };
+    if minutes != 0 || seconds != 0 {
+        out += &format!("{sep}{minutes:02}");
+    }
+    if seconds != 0 {
+        out += &format!("{sep}{seconds:02}");
+    }
+    out
+}
+
+/// Synthesize the TZif data of a custom offset ID, as Android apps pass to `TimeZone`: `GMT` or
+/// `UTC` followed by a signed `h`, `hh`, `hh:mm` or `hhmm` offset, e.g. `GMT+05:30` or
+/// `UTC-7`. Unlike `Etc/GMT+8`, these count eastwards, so `GMT+8` is 8 hours ahead of UTC.
+///
+/// Returns `None` for any other name, and for offsets beyond 18 hours.
+pub fn gmt_offset_tzdata(tz_name: &[u8]) -> Option<Vec<u8>> {
+    fixed_tzdata(parse_gmt_offset(tz_name)?)
+}
+
+/// Parse `GMT` or `UTC` followed by a signed `h`, `hh`, `hh:mm` or `hhmm` offset, counting
+/// eastwards, e.g. `GMT+08:00`. ASCII case is ignored.
+pub(crate) fn parse_gmt_offset(name: &[u8]) -> Option<i32> {
+    let (prefix, offset) = name.split_at_checked(3)?;
+    if !prefix.eq_ignore_ascii_case(b"GMT") && !prefix.eq_ignore_ascii_case(b"UTC") {
+        return None;
+    }
+    let (sign, offset) = match offset.split_first()? {
+        (b'+', offset) => (1, offset),
+        (b'-', offset) => (-1, offset),
+        _ => return None,
+    };
+    let (hours, minutes) = match offset {
+        [h] => ([b'0', *h], [b'0'; 2]),
+        [h1, h2] => ([*h1, *h2], [b'0'; 2]),
+        [h1, h2, b':', m1, m2] | [h1, h2, m1, m2] => ([*h1, *h2], [*m1, *m2]),
+        _ => return None,
+    };
+    let number = |digits: [u8; 2]| {
+        digits
+            .iter()
+            .all(u8::is_ascii_digit)
+            .then(|| i32::from((digits[0] - b'0') * 10 + digits[1] - b'0'))
+    };
+    let (hours, minutes) = (number(hours)?, number(minutes)?);
+    (hours <= 18 && minutes < 60).then_some(sign * (hours * 3600 + minutes * 60))
+}
+
+/// Build a TZif version 2 file with one local time type and no transitions.
+fn fixed_tzif(utoff: i32, abbr: &str, posix: &str) -> Vec<u8> {
+    // isutcnt, isstdcnt, leapcnt, timecnt, typecnt, charcnt
+    let counts = [1, 1, 0, 0, 1, abbr.len() as u32 + 1];
+    let mut block = Vec::new();
+    block.extend_from_slice(b"TZif2");
+    block.extend_from_slice(&[0; 15]);
+    counts.iter().for_each(|count| block.extend_from_slice(&count.to_be_bytes()));
+    // ttinfo: utoff, isdst, desigidx
+    block.extend_from_slice(&utoff.to_be_bytes());
+    block.extend_from_slice(&[0, 0]);
+    block.extend_from_slice(abbr.as_bytes());
+    // abbreviation terminator, isstd, isut
+    block.extend_from_slice(&[0, 0, 0]);
+
+    // Without transitions the version 1 and version 2 blocks are identical.
+    let mut tzif = Vec::with_capacity(2 * block.len() + posix.len() + 2);
+    tzif.extend_from_slice(&block);
+    tzif.extend_from_slice(&block);
+    tzif.push(b'\n');
+    tzif.extend_from_slice(posix.as_bytes());
+    tzif.push(b'\n');
+    tzif
+}
+
+#[cfg(feature = "tzif")]
+impl ZoneInfo {
+    /// Decode a custom offset ID like `GMT+05:30` into a zone with that constant offset, see
+    /// [`gmt_offset_tzdata`].
+    pub fn from_gmt_offset(tz_name: &[u8]) -> Option<Self> {
+        Self::new(&gmt_offset_tzdata(tz_name)?).ok()
+    }
+
+    /// A zone always `offset_secs` ahead of UTC, abbreviated as `name`, e.g. for a user-defined
+    /// zone shown next to those of the database.
+    ///
+    /// Fails with [`ZoneInfoError::InvalidTzif`](crate::ZoneInfoError::InvalidTzif) unless the
+    /// offset is within a day of UTC and `name` is at least 3 ASCII letters, digits, `+` or `-`,
+    /// as POSIX TZ strings require.
+    pub fn fixed(offset_secs: i32, name: &str) -> Result<Self> {
+        const DAY: i32 = 24 * 3600;
+        if offset_secs <= -DAY || offset_secs >= DAY {
+            return Err(ZoneInfoError::InvalidTzif("offset out of range"));
+        }
+        if name.len() < 3 || !name.bytes().all(|x| x.is_ascii_alphanumeric() || b"+-".contains(&x))
+        {
+            return Err(ZoneInfoError::InvalidTzif("invalid abbreviation"));
+        }
+        Self::new(&fixed_tzif(offset_secs, name, &fixed_posix(offset_secs, name)))
+    }
+}
+
+#[cfg(feature = "std")]
+impl ZoneInfoDb {
+    /// Get the timezone data of a zone, synthesizing fixed-offset zones missing from the
+    /// database, see [`fixed_offset_tzdata`], and custom offset IDs, see [`gmt_offset_tzdata`].
+    pub fn get_or_fixed(&self, tz_name: &[u8]) -> Option<Cow<'_, [u8]>> {
+        match self.get(tz_name) {
+            Some(data) => Some(Cow::Borrowed(data)),
+            None => {
+                fixed_offset_tzdata(tz_name).or_else(|| gmt_offset_tzdata(tz_name)).map(Cow::Owned)
+            }
+        }
+    }
+
+    /// Parse the timezone data of a zone, synthesizing fixed-offset zones missing from the
+    /// database; the fallback counterpart of [`ZoneInfoDb::zone_info`].
+    #[cfg(feature = "tzif")]
+    pub fn zone_info_or_fixed(&self, tz_name: &[u8]) -> Result<Option<ZoneInfo>> {
+        self.get_or_fixed(tz_name).as_deref().map(ZoneInfo::new).transpose()
+    }
+}
+
+#[cfg(all(test, feature = "std"))]
+mod tests {
+    use std::fs::File;
+
+    use super::*;
+
+    #[test]
+    fn test_fixed_offset_matches_zic() {
+        let db = ZoneInfoDb::new_android(File::open("./tests/android/tzdata").unwrap()).unwrap();
+        for name in [
+            &b"UTC"[..],
+            b"Etc/UTC",
+            b"GMT",
+            b"Etc/GMT0",
+            b"Etc/GMT+8",
+            b"Etc/GMT-14",
+            b"Etc/GMT+12",
+            b"Etc/GMT-1",
+            b"Etc/GMT+0",
+            b"UCT",
+            b"Etc/Universal",
+            b"Zulu",
+            b"Etc/Zulu",
+            b"Greenwich",
+            b"Etc/Greenwich",
+        ] {
+            assert_eq!(
+                fixed_offset_tzdata(name).as_deref(),
+                db.get(name),
+                "{}",
+                String::from_utf8_lossy(name)
+            );
+        }
+    }
+
+    #[test]
+    fn test_fixed_offset_rejects_other_names() {
+        for name in [
+            &b"GMT+8"[..],
+            b"Etc/GMT+13",
+            b"Etc/GMT-15",
+            b"Etc/GMT+08",
+            b"Etc/GMT+",
+            b"Z",
+            b"Etc/UT",
+        ] {
+            assert_eq!(fixed_offset_tzdata(name), None, "{}", String::from_utf8_lossy(name));
+        }
+    }
+
+    #[test]
+    fn test_fixed_tzdata() {
+        let tzdata = fixed_tzdata(9 * 3600 + 1800).unwrap();
+        assert!(tzdata.ends_with(b"+0930\0\0\0\n<+0930>-9:30\n"));
+        let tzdata = fixed_tzdata(-(3 * 3600 + 1800 + 15)).unwrap();
+        assert!(tzdata.ends_with(b"-033015\0\0\0\n<-033015>3:30:15\n"));
+        assert_eq!(fixed_tzdata(0).unwrap()[0x2c..0x30], [0; 4]);
+        assert!(fixed_tzdata(24 * 3600).is_none());
+        assert!(fixed_tzdata(i32::MIN).is_none());
+    }
+
+    #[test]
+    fn test_ohos_get_or_fixed() {
+        // The OpenHarmony database ships without any of the UTC/GMT zones.
+        let db = ZoneInfoDb::new_ohos(File::open("./tests/ohos/tzdata").unwrap()).unwrap();
+        assert!(db.get(b"UTC").is_none());
+        assert!(matches!(db.get_or_fixed(b"UTC"), Some(Cow::Owned(_))));
+        assert!(matches!(db.get_or_fixed(b"Asia/Shanghai"), Some(Cow::Borrowed(_))));
+        assert!(db.get_or_fixed(b"Asia/Nowhere").is_none());
+        assert_eq!(db.get_or_fixed(b"UT"), db.get_or_fixed(b"Zulu"));
+
+        // Custom offset IDs count eastwards, unlike the `Etc/GMT±N` zones.
+        assert_eq!(db.get_or_fixed(b"GMT+8").as_deref(), fixed_tzdata(8 * 3600).as_deref());
+        assert_eq!(db.get_or_fixed(b"Etc/GMT+8").as_deref(), fixed_tzdata(-8 * 3600).as_deref());
+        assert!(gmt_offset_tzdata(b"GMT+19").is_none());
+    }
+
+    #[cfg(feature = "tzif")]
+    #[test]
+    fn test_zone_info_fixed() {
+        let office = ZoneInfo::fixed(5 * 3600 + 1800, "OFFICE").unwrap();
+        assert_eq!(office.raw_offset(), 5 * 3600 + 1800);
+        assert_eq!(office.dst_savings(), 0);
+        let offset = office.offset_at(1719792000);
+        assert_eq!((offset.seconds, offset.is_dst, offset.abbreviation), (19800, false, "OFFICE"));
+        assert_eq!(office.tzif().footer.as_deref(), Some("<OFFICE>-5:30"));
+        assert_eq!(ZoneInfo::fixed(-8 * 3600, "PST8").unwrap().offset_at(0).seconds, -8 * 3600);
+        // The same data as the numeric fixed zones.
+        let plus_nine = ZoneInfo::fixed(9 * 3600, "+09").unwrap();
+        assert_eq!(
+            plus_nine.tzif(),
+            ZoneInfo::new(&fixed_tzdata(9 * 3600).unwrap()).unwrap().tzif()
+        );
+
+        for (offset, name) in [(0, "UT"), (0, "My Zone"), (0, "Zürich"), (24 * 3600, "UTC")] {
+            assert!(matches!(ZoneInfo::fixed(offset, name), Err(ZoneInfoError::InvalidTzif(_))));
+        }
+    }
+
+    #[cfg(feature = "tzif")]
+    #[test]
+    fn test_zone_info_or_fixed() {
+        let db = ZoneInfoDb::new_ohos(File::open("./tests/ohos/tzdata").unwrap()).unwrap();
+        let zulu = db.zone_info_or_fixed(b"Zulu").unwrap().unwrap();
+        assert_eq!(zulu.raw_offset(), 0);
+        assert!(db.zone_info(b"Zulu").unwrap().is_none());
+        let india = ZoneInfo::from_gmt_offset(b"GMT+05:30").unwrap();
+        assert_eq!(india.raw_offset(), 5 * 3600 + 1800);
+        assert_eq!(india.offset_at(0).abbreviation, "+0530");
+        assert_eq!(ZoneInfo::from_gmt_offset(b"UTC-7").unwrap().raw_offset(), -7 * 3600);
+        assert!(ZoneInfo::from_gmt_offset(b"Asia/Shanghai").is_none());
+    }
+}
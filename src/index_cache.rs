@@ -0,0 +1,91 @@
+//! Process-wide cache of the parsed indexes of `tzdata` files, for the `_from_fs` helpers.
+use std::{
+    collections::BTreeMap,
+    fs::File,
+    path::{Path, PathBuf},
+    sync::{Arc, Mutex, PoisonError},
+    time::SystemTime,
+};
+
+use crate::{ParseOptions, Result, TzDataFormatVersion, TzDataHeader, TzDataIndexes};
+
+/// Parsed header and indexes of a file, with the modification time and length they were read at.
+struct Entry {
+    modified: SystemTime,
+    len: u64,
+    parsed: Arc<(TzDataHeader, TzDataIndexes)>,
+}
+
+static INDEXES: Mutex<BTreeMap<PathBuf, Entry>> = Mutex::new(BTreeMap::new());
+
+/// Get the timezone data of a zone from the opened `tzdata` file at `path`.
+///
+/// The header and indexes are parsed once and reused while the file keeps its modification time
+/// and length, so an update replacing the file is picked up on the next call. Files without a
+/// modification time aren't cached.
+pub(crate) fn find_tz_data_cached(
+    mut file: File,
+    path: &Path,
+    format: TzDataFormatVersion,
+    tz_name: &[u8],
+) -> Result<Option<Vec<u8>>> {
+    let metadata = file.metadata()?;
+    let key = metadata.modified().ok().map(|modified| (modified, metadata.len()));
+    let cached = key.and_then(|(modified, len)| {
+        let indexes = INDEXES.lock().unwrap_or_else(PoisonError::into_inner);
+        let entry = indexes.get(path).filter(|x| x.modified == modified && x.len == len)?;
+        Some(entry.parsed.clone())
+    });
+    let parsed = match cached {
+        Some(parsed) => parsed,
+        None => {
+            let header = TzDataHeader::new(&mut file)?;
+            let options = ParseOptions::default();
+            let indexes = TzDataIndexes::new_with_format(&mut file, &header, format, &options)?;
+            let parsed = Arc::new((header, indexes));
+            if let Some((modified, len)) = key {
+                let entry = Entry { modified, len, parsed: parsed.clone() };
+                let mut indexes = INDEXES.lock().unwrap_or_else(PoisonError::into_inner);
+                indexes.insert(path.to_owned(), entry);
+            }
+            trace!(debug!(path = %path.display(), cached = key.is_some(), "parsed tzdata index"));
+            parsed
+        }
+    };
+    let (header, indexes) = &*parsed;
+    let Some(index) = indexes.find_timezone(tz_name) else { return Ok(None) };
+    indexes.find_tzdata(file, header, index).map(Some)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_find_tz_data_cached() {
+        let dir = std::env::temp_dir().join(format!("zoneinfo-db-cached-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("tzdata");
+        let format = TzDataFormatVersion::NoRawOffset;
+        let find =
+            |name: &[u8]| find_tz_data_cached(File::open(&path).unwrap(), &path, format, name);
+
+        let mut data = std::fs::read("./tests/ohos/tzdata").unwrap();
+        std::fs::write(&path, &data).unwrap();
+        let modified = std::fs::metadata(&path).unwrap().modified().unwrap();
+        assert_eq!(find(b"Asia/Shanghai").unwrap().unwrap().len(), 393);
+
+        // Rewriting the index with the same time and length still hits the cache.
+        data[24..24 + 40].fill(b'X');
+        std::fs::write(&path, &data).unwrap();
+        File::options().write(true).open(&path).unwrap().set_modified(modified).unwrap();
+        assert!(find(b"Africa/Abidjan").unwrap().is_some());
+
+        // A different modification time reparses the file.
+        let later = modified + std::time::Duration::from_secs(1);
+        File::options().write(true).open(&path).unwrap().set_modified(later).unwrap();
+        let result = find(b"Africa/Abidjan");
+        std::fs::remove_dir_all(&dir).unwrap();
+        assert!(result.unwrap().is_none());
+    }
+}
@@ -0,0 +1,44 @@
+//! Conversion of zone data to [`jiff::tz::TimeZone`](::jiff::tz::TimeZone), behind the `jiff`
+//! feature.
+use ::jiff::tz::TimeZone;
+
+#[cfg(feature = "std")]
+use crate::ZoneInfoDb;
+use crate::{Result, ZoneInfoError};
+
+/// Build a jiff time zone named `name` from the TZif data of a zone.
+pub fn jiff_time_zone(name: &str, tzif: &[u8]) -> Result<TimeZone> {
+    TimeZone::tzif(name, tzif).map_err(ZoneInfoError::Jiff)
+}
+
+#[cfg(feature = "std")]
+impl ZoneInfoDb {
+    /// Build a jiff time zone from the data of a zone, or `None` if it isn't in the database.
+    ///
+    /// The time zone is named after the zone, so `iana_name()` returns e.g. `Asia/Shanghai`.
+    pub fn jiff_time_zone(&self, tz_name: &str) -> Result<Option<TimeZone>> {
+        self.get(tz_name.as_bytes()).map(|tzif| jiff_time_zone(tz_name, tzif)).transpose()
+    }
+}
+
+#[cfg(all(test, feature = "std"))]
+mod tests {
+    use std::fs::File;
+
+    use ::jiff::Timestamp;
+
+    use super::*;
+
+    #[test]
+    fn test_jiff_time_zone() {
+        let db = ZoneInfoDb::new_ohos(File::open("./tests/ohos/tzdata").unwrap()).unwrap();
+        let tz = db.jiff_time_zone("America/New_York").unwrap().unwrap();
+        assert_eq!(tz.iana_name(), Some("America/New_York"));
+        let zoned = Timestamp::from_second(1719792000).unwrap().to_zoned(tz);
+        assert_eq!(zoned.offset().seconds(), -4 * 3600);
+        assert_eq!(zoned.hour(), 20);
+
+        assert!(db.jiff_time_zone("Asia/Nowhere").unwrap().is_none());
+        assert!(matches!(jiff_time_zone("Broken", b"TZif"), Err(ZoneInfoError::Jiff(_))));
+    }
+}
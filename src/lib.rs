@@ -1,12 +1,220 @@
 //! Rust parser of ZoneInfoDb(`tzdata`) on Android and OpenHarmony
 //!
 //! Ported from: https://android.googlesource.com/platform/prebuilts/fullsdk/sources/+/refs/heads/androidx-appcompat-release/android-34/com/android/i18n/timezone/ZoneInfoDb.java
-use std::{
-    ffi::CStr,
-    fmt::Debug,
-    fs::File,
-    io::{Error, Read, Result, Seek, SeekFrom},
-};
+//!
+//! Without the default `std` feature, the crate is `no_std` and needs `alloc` only: headers,
+//! indexes, TZif data and zone tables are parsed from byte slices, and everything reading files,
+//! such as [`ZoneInfoDb`], is unavailable.
+//!
+//! The rest is split into default features that size-conscious apps needing only name to bytes
+//! lookups can turn off, leaving the `parse-core` of headers, indexes and lookups, which pulls
+//! in no optional dependency:
+//!
+//! - `fs`: locating and reading the system's `tzdata` files, e.g. [`find_tz_data_android_from_fs`]
+//!   and [`ZoneInfoDb::system`]; implies `std`.
+//! - `tzif`: decoding TZif data, e.g. [`TzIf`] and [`ZoneInfo`].
+//! - `zonetab`: parsing the zonetab section, [`ZoneTab`].
+//! - `builder`: writing `tzdata` files, [`TzDataBuilder`].
+//!
+//! On `wasm32-unknown-unknown`, which has no file system or environment, load the bytes
+//! yourself, e.g. with `fetch()`, and use [`ZoneInfoDb::from_bytes`] or [`TzDataRef`]; the
+//! `fetch` example shows the same flow natively.
+//!
+//! With the `tracing` feature, opening files, parsing headers and indexes, and lookup misses
+//! emit [`tracing`](https://docs.rs/tracing) events, showing which `tzdata` path and version a
+//! device actually uses.
+//!
+//! With the `rayon` feature, [`ZoneInfoDb::verify`], [`ZoneInfoDb::extract_all`],
+//! [`ZoneInfoDb::current_offsets_snapshot`] and [`TzDataIndexes::duplicate_groups`] spread their
+//! work across threads.
+//!
+//! With the `zip` feature, `ZoneInfoDb::from_zip` reads the `etc/tz/tzdata` entry of a zip
+//! archive. This only suits test builds storing it as a plain entry: the APEXes of real tzdata
+//! module updates keep it inside a filesystem image, which isn't supported.
+//!
+//! With the `ndk` feature on Android and the `ohos-sys` feature on OpenHarmony, `AssetSource`
+//! and `RawFileSource` read a `tzdata` file bundled in the app's assets or rawfiles as a
+//! [`TzDataSource`].
+#![cfg_attr(not(feature = "std"), no_std)]
+
+extern crate alloc;
+
+/// Emit a `tracing` event, e.g. `trace!(debug!(zones = 3, "parsed"))`, if the `tracing` feature
+/// is enabled.
+macro_rules! trace {
+    ($level:ident!($($arg:tt)*)) => {
+        #[cfg(feature = "tracing")]
+        tracing::$level!($($arg)*);
+    };
+}
+
+use alloc::{string::String, sync::Arc, vec::Vec};
+use core::{ffi::CStr, fmt::Debug, ops::Range};
+#[cfg(feature = "std")]
+use std::io::{Read, Seek, SeekFrom, Write};
+
+#[cfg(all(feature = "std", feature = "tzif"))]
+mod abbreviations;
+#[cfg(feature = "aliases")]
+mod aliases;
+#[cfg(feature = "zip")]
+mod apex;
+#[cfg(all(feature = "ndk", target_os = "android"))]
+mod asset;
+#[cfg(feature = "builder")]
+mod builder;
+#[cfg(feature = "std")]
+mod cache;
+#[cfg(all(feature = "std", feature = "builder"))]
+mod canonicalize;
+#[cfg(feature = "capi")]
+pub mod capi;
+#[cfg(feature = "chrono")]
+mod chrono;
+#[cfg(feature = "std")]
+mod compressed;
+#[cfg(feature = "conformance")]
+mod conformance;
+#[cfg(all(feature = "std", feature = "tzif", feature = "zonetab"))]
+mod country;
+#[cfg(feature = "std")]
+mod db;
+#[cfg(feature = "fs")]
+mod default_zone;
+#[cfg(feature = "std")]
+mod diff;
+#[cfg(feature = "embedded-fallback")]
+mod embedded;
+mod error;
+mod exemplar;
+#[cfg(feature = "std")]
+mod extract;
+#[cfg(feature = "std")]
+mod fingerprint;
+mod fixed;
+#[cfg(feature = "fs")]
+mod index_cache;
+#[cfg(feature = "jiff")]
+mod jiff;
+#[cfg(feature = "fs")]
+mod locator;
+mod normalize;
+mod options;
+#[cfg(feature = "std")]
+mod patch;
+#[cfg(all(feature = "std", feature = "tzif", feature = "zonetab"))]
+mod picker;
+#[cfg(feature = "fs")]
+mod platform;
+#[cfg(feature = "tzif")]
+mod posix;
+#[cfg(feature = "std")]
+mod probe;
+#[cfg(all(feature = "ohos-sys", target_env = "ohos"))]
+mod rawfile;
+#[cfg(all(feature = "std", feature = "tzif"))]
+mod shared;
+#[cfg(all(feature = "std", feature = "tzif"))]
+mod snapshot;
+#[cfg(feature = "std")]
+mod source;
+#[cfg(feature = "std")]
+mod split;
+#[cfg(feature = "fs")]
+mod system;
+#[cfg(any(all(test, feature = "builder"), feature = "testutil"))]
+pub mod testutil;
+#[cfg(feature = "time")]
+mod time;
+#[cfg(feature = "tokio")]
+mod tokio;
+mod tz_version;
+mod tzdata_ref;
+#[cfg(feature = "tzif")]
+mod tzif;
+#[cfg(feature = "tzlookup")]
+mod tzlookup;
+#[cfg(all(feature = "std", feature = "tzif", feature = "zonetab"))]
+mod verify;
+#[cfg(feature = "std")]
+mod version_set;
+#[cfg(feature = "watch")]
+mod watch;
+mod zone_id;
+#[cfg(feature = "tzif")]
+mod zone_info;
+#[cfg(all(feature = "std", feature = "zonetab"))]
+mod zone_meta;
+#[cfg(feature = "zonetab")]
+mod zonetab;
+
+#[cfg(feature = "aliases")]
+pub use aliases::{AliasLookup, Aliases};
+#[cfg(all(feature = "ndk", target_os = "android"))]
+pub use asset::AssetSource;
+#[cfg(feature = "builder")]
+pub use builder::TzDataBuilder;
+#[cfg(feature = "chrono")]
+pub use chrono::ChronoOffset;
+#[cfg(feature = "conformance")]
+pub use conformance::conformance_dump;
+#[cfg(feature = "std")]
+pub use db::{Instrumentation, MAX_UNTRUSTED_LEN, Section, ZoneInfoDb};
+#[cfg(feature = "fs")]
+pub use default_zone::system_timezone_name;
+#[cfg(feature = "std")]
+pub use diff::{TzDataDiff, changed_zones_between, diff};
+pub use error::{Result, ZoneInfoError};
+pub use exemplar::{exemplar_city, exemplar_city_with};
+pub use fixed::{fixed_offset_tzdata, fixed_tzdata, gmt_offset_tzdata};
+#[cfg(feature = "jiff")]
+pub use jiff::jiff_time_zone;
+#[cfg(feature = "fs")]
+pub use locator::{OhosTzDataSource, TzdataLocator};
+pub use normalize::NormalizedZone;
+pub use options::ParseOptions;
+#[cfg(feature = "std")]
+pub use patch::TzDataPatch;
+#[cfg(all(feature = "std", feature = "tzif", feature = "zonetab"))]
+pub use picker::{PickerCountry, PickerZone};
+#[cfg(feature = "fs")]
+pub use platform::Platform;
+#[cfg(feature = "std")]
+pub use probe::{TzDataProbe, probe_android, probe_ohos};
+#[cfg(all(feature = "ohos-sys", target_env = "ohos"))]
+pub use rawfile::RawFileSource;
+#[cfg(all(feature = "std", feature = "tzif"))]
+pub use shared::SharedZoneInfoDb;
+#[cfg(all(feature = "std", feature = "tzif"))]
+pub use snapshot::ZoneOffset;
+#[cfg(feature = "std")]
+pub use source::TzDataSource;
+#[cfg(feature = "tokio")]
+pub use tokio::{find_tz_data_android_async, find_tz_data_ohos_async};
+#[cfg(all(feature = "tokio", feature = "fs"))]
+pub use tokio::{find_tz_data_android_from_fs_async, find_tz_data_ohos_from_fs_async};
+pub use tz_version::{TzVersion, TzdbVersion};
+pub use tzdata_ref::TzDataRef;
+#[cfg(feature = "tzif")]
+pub use tzif::{LeapSecond, LocalTimeType, Transition, TzIf, posix_tz_string};
+#[cfg(feature = "tzlookup")]
+pub use tzlookup::{CountryZones, TzLookup};
+#[cfg(all(feature = "std", feature = "tzif", feature = "zonetab"))]
+pub use verify::{VerifyIssue, VerifyReport};
+#[cfg(feature = "std")]
+pub use version_set::VersionSet;
+#[cfg(feature = "watch")]
+pub use watch::UpdateSubscription;
+pub use zone_id::is_valid_zone_id;
+#[cfg(feature = "tzif")]
+pub use zone_info::{OffsetChange, OffsetTransition, UtcOffset, ZoneInfo};
+#[cfg(all(feature = "std", feature = "zonetab"))]
+pub use zone_meta::ZoneMeta;
+#[cfg(feature = "zonetab")]
+pub use zonetab::{Coordinates, ZoneTab, ZoneTabEntry};
+
+/// Size of the fixed `tzdata` header: `version + index_offset + data_offset + zonetab_offset`.
+const SIZEOF_HEADER: usize = 12 + 3 * size_of::<u32>();
 
 // The database reserves 40 bytes for each id.
 const SIZEOF_TZNAME: usize = 40;
@@ -18,6 +226,7 @@ const SIZEOF_INDEX_ENTRY_ANDROID: usize = SIZEOF_TZNAME + 3 * size_of::<u32>();
 
 /// Header of the `tzdata` file.
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct TzDataHeader {
     pub version: [u8; 5],
     pub index_offset: u32,
@@ -27,94 +236,512 @@ pub struct TzDataHeader {
 
 impl TzDataHeader {
     /// Parse the header of the `tzdata` file.
+    #[cfg(feature = "std")]
     pub fn new<R: Read>(mut data: R) -> Result<Self> {
+        let mut header = [0; SIZEOF_HEADER];
+        data.read_exact(&mut header)?;
+        Self::from_bytes(&header)
+    }
+
+    /// Parse the header at the start of the `tzdata` file's bytes.
+    pub fn from_bytes(data: &[u8]) -> Result<Self> {
         /// e.g. `tzdata2024b\0`
         const TZDATA_VERSION_SIZE: usize = 12;
-        /// Magic header of `tzdata` file
-        const TZDATA_MAGIC_HEADER: &[u8] = b"tzdata";
-
-        let version = {
-            let mut magic = [0; TZDATA_VERSION_SIZE];
-            data.read_exact(&mut magic)?;
-            if !magic.starts_with(TZDATA_MAGIC_HEADER) || magic[TZDATA_VERSION_SIZE - 1] != 0 {
-                return Err(Error::other("invalid tzdata header magic"));
-            }
-            let mut version = [0; 5];
-            version.copy_from_slice(&magic[6..11]);
-            version
+
+        let header: &[u8; SIZEOF_HEADER] =
+            data.first_chunk().ok_or(ZoneInfoError::TruncatedHeader)?;
+        let (magic, offsets) = header
+            .split_first_chunk::<TZDATA_VERSION_SIZE>()
+            .ok_or(ZoneInfoError::TruncatedHeader)?;
+        let [b't', b'z', b'd', b'a', b't', b'a', version @ .., 0] = *magic else {
+            return Err(ZoneInfoError::InvalidMagic);
+        };
+        let offset = |i: usize| be_u32_at(offsets, 4 * i).ok_or(ZoneInfoError::TruncatedHeader);
+
+        let header = Self {
+            version,
+            index_offset: offset(0)?,
+            data_offset: offset(1)?,
+            zonetab_offset: offset(2)?,
         };
+        trace!(debug!(
+            version = %header.version.escape_ascii(),
+            index_offset = header.index_offset,
+            data_offset = header.data_offset,
+            zonetab_offset = header.zonetab_offset,
+            "parsed tzdata header"
+        ));
+        Ok(header)
+    }
+
+    /// Check that the header, index, data and zonetab sections follow each other in that order
+    /// without overlapping, and that they start within a file of `file_len` bytes.
+    ///
+    /// Empty sections are fine. Fails with [`ZoneInfoError::OverlappingSections`] or
+    /// [`ZoneInfoError::SectionPastEnd`].
+    pub fn validate_layout(&self, file_len: u64) -> Result<()> {
+        let sections = [
+            ("header", SIZEOF_HEADER as u32),
+            ("index", self.index_offset),
+            ("data", self.data_offset),
+            ("zonetab", self.zonetab_offset),
+        ];
+        for pair in sections.windows(2) {
+            let [(first, start), (second, next)] = pair else { unreachable!() };
+            if next < start {
+                return Err(ZoneInfoError::OverlappingSections { first, second });
+            }
+        }
+        if u64::from(self.zonetab_offset) > file_len {
+            return Err(ZoneInfoError::SectionPastEnd {
+                section: "zonetab",
+                offset: self.zonetab_offset,
+                file_len,
+            });
+        }
+        Ok(())
+    }
 
-        let mut offset = [0; 4];
-        data.read_exact(&mut offset)?;
-        let index_offset = u32::from_be_bytes(offset);
-        data.read_exact(&mut offset)?;
-        let data_offset = u32::from_be_bytes(offset);
-        data.read_exact(&mut offset)?;
-        let zonetab_offset = u32::from_be_bytes(offset);
+    /// Byte range of the index, from `index_offset` to `data_offset`, empty if they're
+    /// inverted.
+    pub fn index_range(&self) -> Range<u64> {
+        let start = u64::from(self.index_offset);
+        start..u64::from(self.data_offset).max(start)
+    }
+
+    /// Byte range of the TZif data, from `data_offset` to `zonetab_offset`, clamped to a file
+    /// of `file_len` bytes and empty if they're inverted.
+    pub fn data_range(&self, file_len: u64) -> Range<u64> {
+        let start = u64::from(self.data_offset).min(file_len);
+        start..u64::from(self.zonetab_offset).clamp(start, file_len)
+    }
+
+    /// Byte range of the zonetab, from `zonetab_offset` to the end of a file of `file_len`
+    /// bytes, empty if it starts past the end.
+    pub fn zonetab_range(&self, file_len: u64) -> Range<u64> {
+        u64::from(self.zonetab_offset).min(file_len)..file_len
+    }
+
+    /// Byte ranges of all sections of a file of `file_len` bytes.
+    ///
+    /// The ranges only follow from the offsets, so they may overlap or leave gaps in malformed
+    /// files, see [`TzDataHeader::validate_layout`].
+    pub fn sections(&self, file_len: u64) -> Sections {
+        Sections {
+            header: 0..(SIZEOF_HEADER as u64).min(file_len),
+            index: self.index_range(),
+            data: self.data_range(file_len),
+            zonetab: self.zonetab_range(file_len),
+        }
+    }
+
+    /// Serialize the header as at the start of a `tzdata` file, the inverse of
+    /// [`TzDataHeader::from_bytes`].
+    pub fn to_bytes(&self) -> [u8; SIZEOF_HEADER] {
+        let mut out = [0; SIZEOF_HEADER];
+        out[..6].copy_from_slice(b"tzdata");
+        out[6..11].copy_from_slice(&self.version);
+        for (i, offset) in
+            [self.index_offset, self.data_offset, self.zonetab_offset].iter().enumerate()
+        {
+            out[12 + 4 * i..16 + 4 * i].copy_from_slice(&offset.to_be_bytes());
+        }
+        out
+    }
 
-        Ok(Self { version, index_offset, data_offset, zonetab_offset })
+    /// Write the header, see [`TzDataHeader::to_bytes`].
+    #[cfg(feature = "std")]
+    pub fn write_to<W: Write>(&self, mut writer: W) -> Result<()> {
+        Ok(writer.write_all(&self.to_bytes())?)
     }
 }
 
+/// Byte ranges of the sections of a `tzdata` file, see [`TzDataHeader::sections`].
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Sections {
+    pub header: Range<u64>,
+    pub index: Range<u64>,
+    pub data: Range<u64>,
+    pub zonetab: Range<u64>,
+}
+
 /// Index entry of the `tzdata` file.
+///
+/// The name is reference counted, so cloning an entry is cheap. Entries are ordered by name
+/// first, as in the index.
+#[derive(Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct TzDataIndex {
-    pub name: Box<[u8]>,
+    pub name: Arc<[u8]>,
     pub offset: u32,
     pub length: u32,
+    /// Legacy raw UTC offset of the zone, only present in entries of the Android flavor. Current
+    /// Android releases always write 0; the TZif data is authoritative.
+    pub raw_utc_offset: Option<i32>,
+}
+
+impl TzDataIndex {
+    /// The name as a string, failing with [`ZoneInfoError::InvalidZoneName`] unless it's
+    /// non-empty printable ASCII.
+    pub fn name_str(&self) -> Result<&str> {
+        if self.name.is_empty() || !self.name.iter().all(u8::is_ascii_graphic) {
+            return Err(ZoneInfoError::InvalidZoneName);
+        }
+        // Printable ASCII is valid UTF-8.
+        core::str::from_utf8(&self.name).map_err(|_| ZoneInfoError::InvalidZoneName)
+    }
+
+    /// The name as an `Arc<str>` sharing the allocation of [`TzDataIndex::name`], for caches
+    /// keyed by zone name. Fails like [`TzDataIndex::name_str`].
+    pub fn name_arc_str(&self) -> Result<Arc<str>> {
+        self.name_str()?;
+        let name = Arc::into_raw(self.name.clone()) as *const str;
+        // SAFETY: the bytes were checked to be UTF-8 above, and `str` has the layout of `[u8]`.
+        Ok(unsafe { Arc::from_raw(name) })
+    }
+
+    /// Serialize the entry as in an index laid out in `format`, with a raw UTC offset of 0 if
+    /// it has none.
+    ///
+    /// Fails with [`ZoneInfoError::InvalidZoneName`] unless the name is non-empty printable
+    /// ASCII shorter than the 40-byte name slot.
+    pub fn to_bytes(&self, format: TzDataFormatVersion) -> Result<Vec<u8>> {
+        if self.name.len() >= SIZEOF_TZNAME {
+            return Err(ZoneInfoError::InvalidZoneName);
+        }
+        self.name_str()?;
+        let mut out = Vec::with_capacity(format.entry_size());
+        out.extend_from_slice(&self.name);
+        out.resize(SIZEOF_TZNAME, 0);
+        out.extend_from_slice(&self.offset.to_be_bytes());
+        out.extend_from_slice(&self.length.to_be_bytes());
+        if format == TzDataFormatVersion::AndroidRawOffset {
+            out.extend_from_slice(&self.raw_utc_offset.unwrap_or(0).to_be_bytes());
+        }
+        Ok(out)
+    }
+
+    /// Write the entry, see [`TzDataIndex::to_bytes`].
+    #[cfg(feature = "std")]
+    pub fn write_to<W: Write>(&self, format: TzDataFormatVersion, mut writer: W) -> Result<()> {
+        Ok(writer.write_all(&self.to_bytes(format)?)?)
+    }
 }
 
 impl Debug for TzDataIndex {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
         f.debug_struct("TzDataIndex")
             .field("name", &String::from_utf8_lossy(&self.name))
             .field("offset", &self.offset)
             .field("length", &self.length)
+            .field("raw_utc_offset", &self.raw_utc_offset)
             .finish()
     }
 }
 
+/// Layout variant of the `tzdata` file, which differ in the size of index entries.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum TzDataFlavor {
+    /// Entries carry a legacy raw UTC offset after the name, offset and length.
+    Android,
+    /// Entries are the name, offset and length only.
+    Ohos,
+}
+
+impl TzDataFlavor {
+    /// Flavor of the system the crate is built for: [`TzDataFlavor::Android`] on Android,
+    /// [`TzDataFlavor::Ohos`] on OpenHarmony (`target_env = "ohos"`), `None` elsewhere.
+    pub const fn native() -> Option<Self> {
+        if cfg!(target_os = "android") {
+            Some(Self::Android)
+        } else if cfg!(target_env = "ohos") {
+            Some(Self::Ohos)
+        } else {
+            None
+        }
+    }
+}
+
+/// Revision of the layout of index entries, which [`TzDataIndexes`] parsing dispatches on.
+///
+/// The header's version string only records the tzdb release, which doesn't change the layout,
+/// so the format follows from the flavor or is detected from the index itself. New layouts are
+/// added as variants rather than as new constructors.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[non_exhaustive]
+pub enum TzDataFormatVersion {
+    /// 52-byte entries of name, offset, length and a legacy raw UTC offset, written by every
+    /// Android release since the unified `tzdata` file.
+    AndroidRawOffset,
+    /// 48-byte entries of name, offset and length, written by HarmonyOS NEXT.
+    NoRawOffset,
+}
+
+impl TzDataFormatVersion {
+    /// Size of an index entry in bytes.
+    pub const fn entry_size(self) -> usize {
+        match self {
+            Self::AndroidRawOffset => SIZEOF_INDEX_ENTRY_ANDROID,
+            Self::NoRawOffset => SIZEOF_INDEX_ENTRY_OHOS,
+        }
+    }
+
+    /// Flavor of the files using this format.
+    pub const fn flavor(self) -> TzDataFlavor {
+        match self {
+            Self::AndroidRawOffset => TzDataFlavor::Android,
+            Self::NoRawOffset => TzDataFlavor::Ohos,
+        }
+    }
+
+    /// Detect the format from the bytes of the whole `tzdata` file, see
+    /// [`TzDataIndexes::from_bytes_auto`].
+    pub fn detect(data: &[u8], header: &TzDataHeader) -> Result<Self> {
+        TzDataIndexes::from_bytes_auto(data, header).map(|(_, flavor)| flavor.into())
+    }
+}
+
+impl From<TzDataFlavor> for TzDataFormatVersion {
+    fn from(flavor: TzDataFlavor) -> Self {
+        match flavor {
+            TzDataFlavor::Android => Self::AndroidRawOffset,
+            TzDataFlavor::Ohos => Self::NoRawOffset,
+        }
+    }
+}
+
 /// Indexes of the `tzdata` file.
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct TzDataIndexes {
     indexes: Vec<TzDataIndex>,
+    /// Zero bytes at the end of the index region that don't form an entry.
+    padding: usize,
+    /// Entries dropped because their name isn't NUL-terminated.
+    skipped: usize,
 }
 
 impl TzDataIndexes {
     /// Parse the indexes of the `tzdata` file of Android.
+    #[cfg(feature = "std")]
     pub fn new_android<R: Read>(reader: R, header: &TzDataHeader) -> Result<Self> {
-        Self::new::<SIZEOF_INDEX_ENTRY_ANDROID, R>(reader, header)
+        Self::new_android_with_options(reader, header, &ParseOptions::default())
+    }
+
+    /// Parse the indexes of the `tzdata` file of Android within the limits of `options`.
+    #[cfg(feature = "std")]
+    pub fn new_android_with_options<R: Read>(
+        reader: R,
+        header: &TzDataHeader,
+        options: &ParseOptions,
+    ) -> Result<Self> {
+        Self::new_with_format(reader, header, TzDataFormatVersion::AndroidRawOffset, options)
     }
 
     /// Parse the indexes of the `tzdata` file of HarmonyOS NEXT.
+    #[cfg(feature = "std")]
     pub fn new_ohos<R: Read>(reader: R, header: &TzDataHeader) -> Result<Self> {
-        Self::new::<SIZEOF_INDEX_ENTRY_OHOS, R>(reader, header)
+        Self::new_ohos_with_options(reader, header, &ParseOptions::default())
     }
 
-    fn new<const SIZEOF_INDEX_ENTRY: usize, R: Read>(
-        mut reader: R,
+    /// Parse the indexes of the `tzdata` file of HarmonyOS NEXT within the limits of `options`.
+    #[cfg(feature = "std")]
+    pub fn new_ohos_with_options<R: Read>(
+        reader: R,
         header: &TzDataHeader,
+        options: &ParseOptions,
     ) -> Result<Self> {
-        let mut buf = vec![0; header.data_offset.saturating_sub(header.index_offset) as usize];
-        reader.read_exact(&mut buf)?;
-        // replace chunks with array_chunks when it's stable
-        Ok(TzDataIndexes {
-            indexes: buf
-                .chunks(SIZEOF_INDEX_ENTRY)
-                .filter_map(|chunk| {
-                    if let Ok(name) = CStr::from_bytes_until_nul(&chunk[..SIZEOF_TZNAME]) {
-                        let name = name.to_bytes().to_vec().into_boxed_slice();
-                        let offset = u32::from_be_bytes(
-                            chunk[SIZEOF_TZNAME..SIZEOF_TZNAME + 4].try_into().unwrap(),
-                        );
-                        let length = u32::from_be_bytes(
-                            chunk[SIZEOF_TZNAME + 4..SIZEOF_TZNAME + 8].try_into().unwrap(),
-                        );
-                        Some(TzDataIndex { name, offset, length })
-                    } else {
-                        None
-                    }
-                })
-                .collect(),
-        })
+        Self::new_with_format(reader, header, TzDataFormatVersion::NoRawOffset, options)
+    }
+
+    /// Parse the indexes of a `tzdata` file laid out in `format`, within the limits of
+    /// `options`.
+    #[cfg(feature = "std")]
+    pub fn new_with_format<R: Read>(
+        reader: R,
+        header: &TzDataHeader,
+        format: TzDataFormatVersion,
+        options: &ParseOptions,
+    ) -> Result<Self> {
+        let indexes = Self::parse_format(&read_index(reader, header, options)?, format)?;
+        options.check_zones(indexes.indexes.len())?;
+        options.check_names(&indexes.indexes)?;
+        Ok(indexes)
+    }
+
+    /// Parse the indexes of a `tzdata` file of either flavor, detecting which one it is, see
+    /// [`TzDataIndexes::from_bytes_auto`].
+    #[cfg(feature = "std")]
+    pub fn new_auto<R: Read>(reader: R, header: &TzDataHeader) -> Result<(Self, TzDataFlavor)> {
+        Self::parse_auto(&read_index(reader, header, &ParseOptions::default())?)
+    }
+
+    /// Parse the indexes of the `tzdata` file of Android, failing on the first entry whose name
+    /// isn't NUL-terminated instead of skipping it.
+    #[cfg(feature = "std")]
+    pub fn new_strict_android<R: Read>(reader: R, header: &TzDataHeader) -> Result<Self> {
+        let index = read_index(reader, header, &ParseOptions::default())?;
+        Self::parse_strict::<SIZEOF_INDEX_ENTRY_ANDROID>(&index, header)
+    }
+
+    /// Parse the indexes of the `tzdata` file of HarmonyOS NEXT, failing on the first entry
+    /// whose name isn't NUL-terminated instead of skipping it.
+    #[cfg(feature = "std")]
+    pub fn new_strict_ohos<R: Read>(reader: R, header: &TzDataHeader) -> Result<Self> {
+        let index = read_index(reader, header, &ParseOptions::default())?;
+        Self::parse_strict::<SIZEOF_INDEX_ENTRY_OHOS>(&index, header)
+    }
+
+    /// Parse the indexes from the bytes of the whole `tzdata` file of Android.
+    pub fn from_bytes_android(data: &[u8], header: &TzDataHeader) -> Result<Self> {
+        Self::from_bytes_with_format(data, header, TzDataFormatVersion::AndroidRawOffset)
+    }
+
+    /// Parse the indexes from the bytes of the whole `tzdata` file of HarmonyOS NEXT.
+    pub fn from_bytes_ohos(data: &[u8], header: &TzDataHeader) -> Result<Self> {
+        Self::from_bytes_with_format(data, header, TzDataFormatVersion::NoRawOffset)
+    }
+
+    /// Parse the indexes from the bytes of the whole `tzdata` file laid out in `format`.
+    pub fn from_bytes_with_format(
+        data: &[u8],
+        header: &TzDataHeader,
+        format: TzDataFormatVersion,
+    ) -> Result<Self> {
+        Self::parse_format(index_region(data, header)?, format)
+    }
+
+    /// Parse the indexes from the bytes of the whole `tzdata` file of either flavor, detecting
+    /// which one it is.
+    ///
+    /// Flavors whose entry size divides the index region are tried first. A flavor is accepted
+    /// when every entry has a printable ASCII name and the names are sorted, which misaligned
+    /// entries practically never satisfy.
+    pub fn from_bytes_auto(data: &[u8], header: &TzDataHeader) -> Result<(Self, TzDataFlavor)> {
+        Self::parse_auto(index_region(data, header)?)
+    }
+
+    fn parse_auto(buf: &[u8]) -> Result<(Self, TzDataFlavor)> {
+        let mut flavors = [
+            (TzDataFlavor::Android, SIZEOF_INDEX_ENTRY_ANDROID),
+            (TzDataFlavor::Ohos, SIZEOF_INDEX_ENTRY_OHOS),
+        ];
+        flavors.sort_by_key(|(_, size)| !buf.len().is_multiple_of(*size));
+        for (flavor, _) in flavors {
+            if let Ok(indexes) = Self::parse_format(buf, flavor.into())
+                && indexes.skipped == 0
+                && indexes.check_names().is_ok()
+            {
+                return Ok((indexes, flavor));
+            }
+        }
+        Err(ZoneInfoError::UnknownFlavor)
+    }
+
+    /// Salvage the indexes of a possibly truncated `tzdata` file of either flavor from the bytes
+    /// from `index_offset` to the end of the file, see [`ZoneInfoDb::recover`].
+    ///
+    /// A partial last entry is dropped, as are entries whose data extends past the end of the
+    /// file.
+    #[cfg(feature = "std")]
+    pub(crate) fn recover(index: &[u8], header: &TzDataHeader) -> Result<(Self, TzDataFlavor)> {
+        let file_len = u64::from(header.index_offset) + index.len() as u64;
+        let expected = header.data_offset.saturating_sub(header.index_offset) as usize;
+        let region = index.get(..expected).unwrap_or(index);
+        let mut flavors = [
+            (TzDataFlavor::Android, SIZEOF_INDEX_ENTRY_ANDROID),
+            (TzDataFlavor::Ohos, SIZEOF_INDEX_ENTRY_OHOS),
+        ];
+        flavors.sort_by_key(|(_, size)| !expected.is_multiple_of(*size));
+        for (flavor, size) in flavors {
+            let whole = &region[..region.len() - region.len() % size];
+            if let Ok(mut indexes) = Self::parse_format(whole, flavor.into())
+                && indexes.skipped == 0
+                && indexes.check_names().is_ok()
+            {
+                indexes.indexes.retain(|index| entry_range(header, index).end <= file_len);
+                return Ok((indexes, flavor));
+            }
+        }
+        Err(ZoneInfoError::UnknownFlavor)
+    }
+
+    /// Parse the index region with the entry size of `format`.
+    fn parse_format(buf: &[u8], format: TzDataFormatVersion) -> Result<Self> {
+        match format {
+            TzDataFormatVersion::AndroidRawOffset => Self::parse::<SIZEOF_INDEX_ENTRY_ANDROID>(buf),
+            TzDataFormatVersion::NoRawOffset => Self::parse::<SIZEOF_INDEX_ENTRY_OHOS>(buf),
+        }
+    }
+
+    /// Parse the index region, from `index_offset` to `data_offset`.
+    fn parse<const SIZEOF_INDEX_ENTRY: usize>(buf: &[u8]) -> Result<Self> {
+        let (entries, padding) = strip_index_padding::<SIZEOF_INDEX_ENTRY>(buf)?;
+        let indexes: Vec<_> =
+            entries.chunks_exact(SIZEOF_INDEX_ENTRY).filter_map(index_from_entry).collect();
+        let skipped = entries.len() / SIZEOF_INDEX_ENTRY - indexes.len();
+        trace!(debug!(
+            entries = indexes.len(),
+            entry_size = SIZEOF_INDEX_ENTRY,
+            padding,
+            skipped,
+            "parsed tzdata index"
+        ));
+        Ok(TzDataIndexes { indexes, padding, skipped })
+    }
+
+    /// Parse the index region like [`TzDataIndexes::parse`], but fail with
+    /// [`ZoneInfoError::MalformedEntry`] on the first entry it would skip.
+    #[cfg(feature = "std")]
+    fn parse_strict<const SIZEOF_INDEX_ENTRY: usize>(
+        buf: &[u8],
+        header: &TzDataHeader,
+    ) -> Result<Self> {
+        let indexes = Self::parse::<SIZEOF_INDEX_ENTRY>(buf)?;
+        if let Some(entry) =
+            buf.chunks_exact(SIZEOF_INDEX_ENTRY).position(|x| parse_index_entry(x).is_none())
+        {
+            let offset = u64::from(header.index_offset) + (entry * SIZEOF_INDEX_ENTRY) as u64;
+            return Err(ZoneInfoError::MalformedEntry { entry, offset });
+        }
+        Ok(indexes)
+    }
+
+    /// Check that names are non-empty printable ASCII and strictly sorted.
+    pub(crate) fn check_names(&self) -> Result<()> {
+        let mut previous: Option<&[u8]> = None;
+        for index in &self.indexes {
+            index.name_str()?;
+            if previous.is_some_and(|previous| previous >= &index.name[..]) {
+                return Err(ZoneInfoError::UnsortedIndex);
+            }
+            previous = Some(&index.name);
+        }
+        Ok(())
+    }
+
+    /// Check that every entry lies within the data section, which ends at `zonetab_offset` or at
+    /// `file_len`, whichever comes first.
+    pub fn validate(&self, header: &TzDataHeader, file_len: u64) -> Result<()> {
+        let data_end = u64::from(header.zonetab_offset).min(file_len);
+        for index in &self.indexes {
+            if entry_range(header, index).end > data_end {
+                return Err(ZoneInfoError::EntryOutOfBounds);
+            }
+        }
+        Ok(())
+    }
+
+    /// Number of zero padding bytes found after the last index entry.
+    pub fn padding(&self) -> usize {
+        self.padding
+    }
+
+    /// Number of index entries skipped because their name isn't NUL-terminated.
+    pub fn skipped(&self) -> usize {
+        self.skipped
     }
 
     /// Get all timezones.
@@ -122,88 +749,654 @@ impl TzDataIndexes {
         &self.indexes
     }
 
+    /// Number of entries.
+    pub fn len(&self) -> usize {
+        self.indexes.len()
+    }
+
+    /// Whether there are no entries.
+    pub fn is_empty(&self) -> bool {
+        self.indexes.is_empty()
+    }
+
+    /// Names of all timezones, in index order.
+    ///
+    /// Names that aren't printable ASCII, see [`TzDataIndex::name_str`], are skipped.
+    pub fn zone_names(&self) -> impl Iterator<Item = &str> {
+        self.indexes.iter().filter_map(|x| x.name_str().ok())
+    }
+
+    /// Distinct top-level areas of the zones, e.g. `Africa` and `Asia`, in order.
+    ///
+    /// Zones without an area, such as `UTC`, and names that aren't UTF-8 are left out.
+    pub fn areas(&self) -> Vec<&str> {
+        let mut areas: Vec<&str> =
+            self.zone_names().filter_map(|name| name.split_once('/')).map(|(x, _)| x).collect();
+        // Sorted names keep the zones of an area together.
+        areas.dedup();
+        areas
+    }
+
+    /// Zones of an area, e.g. `Asia` or `America/Argentina`, found by binary search.
+    pub fn zones_in_area(&self, area: &str) -> &[TzDataIndex] {
+        let prefix = [area.as_bytes(), b"/"].concat();
+        let start = self.indexes.partition_point(|x| *x.name < *prefix);
+        let len = self.indexes[start..].partition_point(|x| x.name.starts_with(&prefix));
+        &self.indexes[start..start + len]
+    }
+
+    /// Find a timezone in the `tzdata` file of Android by binary-searching the index on disk,
+    /// reading only O(log n) entries instead of the whole index.
+    #[cfg(feature = "std")]
+    pub fn find_timezone_streaming_android<R: Read + Seek>(
+        reader: R,
+        header: &TzDataHeader,
+        timezone: &[u8],
+    ) -> Result<Option<TzDataIndex>> {
+        find_streaming::<SIZEOF_INDEX_ENTRY_ANDROID>(reader, header, timezone)
+    }
+
+    /// Find a timezone in the `tzdata` file of HarmonyOS NEXT by binary-searching the index on
+    /// disk, reading only O(log n) entries instead of the whole index.
+    #[cfg(feature = "std")]
+    pub fn find_timezone_streaming_ohos<R: Read + Seek>(
+        reader: R,
+        header: &TzDataHeader,
+        timezone: &[u8],
+    ) -> Result<Option<TzDataIndex>> {
+        find_streaming::<SIZEOF_INDEX_ENTRY_OHOS>(reader, header, timezone)
+    }
+
+    /// Map every zone name to its position in [`TzDataIndexes::timezones`], for callers doing
+    /// many lookups, which then hash the name once instead of comparing it against several
+    /// entries.
+    #[cfg(feature = "std")]
+    pub fn build_lookup_map(&self) -> std::collections::HashMap<&[u8], usize> {
+        self.indexes.iter().enumerate().map(|(i, x)| (&*x.name, i)).collect()
+    }
+
     /// Find a timezone by name.
     pub fn find_timezone(&self, timezone: &[u8]) -> Option<&TzDataIndex> {
         // timezones in tzdata are sorted by name.
-        self.indexes.binary_search_by_key(&timezone, |x| &x.name).map(|x| &self.indexes[x]).ok()
+        let found = self.indexes.binary_search_by_key(&timezone, |x| &x.name).ok();
+        if found.is_none() {
+            trace!(debug!(zone = %timezone.escape_ascii(), "zone not found in tzdata index"));
+        }
+        found.map(|x| &self.indexes[x])
+    }
+
+    /// Absolute offset in the file and length of a zone's timezone data, for callers reading
+    /// it themselves, e.g. with `pread` or `sendfile`.
+    ///
+    /// The entry isn't checked against the file, see [`TzDataIndexes::validate`].
+    pub fn locate(&self, header: &TzDataHeader, timezone: &[u8]) -> Option<(u64, u32)> {
+        let index = self.find_timezone(timezone)?;
+        Some((entry_range(header, index).start, index.length))
     }
 
     /// Retrieve a chunk of timezone data by the index.
+    ///
+    /// Fails with [`ZoneInfoError::EntryOutOfBounds`] if the entry reaches past `zonetab_offset`.
+    #[cfg(feature = "std")]
     pub fn find_tzdata<R: Read + Seek>(
         &self,
-        mut reader: R,
+        reader: R,
         header: &TzDataHeader,
         index: &TzDataIndex,
     ) -> Result<Vec<u8>> {
-        reader.seek(SeekFrom::Start(index.offset as u64 + header.data_offset as u64))?;
-        let mut buffer = vec![0; index.length as usize];
-        reader.read_exact(&mut buffer)?;
-        Ok(buffer)
+        self.find_tzdata_with_options(reader, header, index, &ParseOptions::default())
+    }
+
+    /// Retrieve a chunk of timezone data by the index, within the limits of `options`.
+    #[cfg(feature = "std")]
+    pub fn find_tzdata_with_options<R: Read + Seek>(
+        &self,
+        reader: R,
+        header: &TzDataHeader,
+        index: &TzDataIndex,
+        options: &ParseOptions,
+    ) -> Result<Vec<u8>> {
+        read_tzdata(reader, header, index, options)
+    }
+
+    /// Iterate over the names and timezone data of all entries in a single forward pass.
+    ///
+    /// Entries are yielded in the order of their data in the file rather than by name, so
+    /// consecutive entries are read without seeking. Iteration stops after the first error.
+    #[cfg(feature = "std")]
+    pub fn iter_tzdata<'a, R: Read + Seek + 'a>(
+        &'a self,
+        mut reader: R,
+        header: &'a TzDataHeader,
+    ) -> impl Iterator<Item = Result<(&'a [u8], Vec<u8>)>> + 'a {
+        let mut entries: Vec<_> = self.indexes.iter().collect();
+        entries.sort_by_key(|index| index.offset);
+        let mut position = None;
+        let mut failed = false;
+        entries.into_iter().map_while(move |index| {
+            if failed {
+                return None;
+            }
+            let result = (|| {
+                let start = checked_entry_range(header, index, &ParseOptions::default())?.start;
+                if position != Some(start) {
+                    reader.seek(SeekFrom::Start(start))?;
+                }
+                let mut buffer = vec![0; index.length as usize];
+                reader.read_exact(&mut buffer)?;
+                position = Some(start + u64::from(index.length));
+                Ok((&*index.name, buffer))
+            })();
+            failed = result.is_err();
+            Some(result)
+        })
+    }
+
+    /// Retrieve the timezone data of several zones in a single forward pass, e.g. to warm a
+    /// cache at startup; the result has one entry per name, `None` for zones not in the index.
+    ///
+    /// Requests are read in the order of their data in the file, seeking only over gaps, and
+    /// zones sharing data are read once.
+    #[cfg(feature = "std")]
+    pub fn find_tzdata_many<R: Read + Seek>(
+        &self,
+        mut reader: R,
+        header: &TzDataHeader,
+        names: &[&[u8]],
+    ) -> Result<Vec<Option<Vec<u8>>>> {
+        let mut requests: Vec<_> = names
+            .iter()
+            .enumerate()
+            .filter_map(|(i, name)| Some((self.find_timezone(name)?, i)))
+            .collect();
+        requests.sort_by_key(|(index, _)| (index.offset, index.length));
+        let mut found = vec![None; names.len()];
+        let mut position = None;
+        let mut previous: Option<(&TzDataIndex, usize)> = None;
+        for (index, i) in requests {
+            if let Some((last, j)) = previous
+                && (last.offset, last.length) == (index.offset, index.length)
+            {
+                found[i] = found[j].clone();
+                continue;
+            }
+            let start = checked_entry_range(header, index, &ParseOptions::default())?.start;
+            if position != Some(start) {
+                reader.seek(SeekFrom::Start(start))?;
+            }
+            let mut buffer = vec![0; index.length as usize];
+            reader.read_exact(&mut buffer)?;
+            position = Some(start + u64::from(index.length));
+            found[i] = Some(buffer);
+            previous = Some((index, i));
+        }
+        Ok(found)
+    }
+
+    /// Group the names of zones whose timezone data is byte-for-byte identical, e.g. a zone
+    /// and its backward links.
+    ///
+    /// Only groups of two or more zones are returned, each sorted by name and ordered by their
+    /// first name. The data is read in a single pass as by [`TzDataIndexes::iter_tzdata`], and
+    /// with the `rayon` feature compared across threads.
+    #[cfg(feature = "std")]
+    pub fn duplicate_groups<'a, R: Read + Seek + 'a>(
+        &'a self,
+        reader: R,
+        header: &'a TzDataHeader,
+    ) -> Result<Vec<Vec<&'a [u8]>>> {
+        let mut entries = self.iter_tzdata(reader, header).collect::<Result<Vec<_>>>()?;
+        // Sorting by data, then name, puts each group together already sorted by name.
+        #[cfg(feature = "rayon")]
+        rayon::slice::ParallelSliceMut::par_sort_unstable_by(&mut entries[..], |a, b| {
+            (&a.1, a.0).cmp(&(&b.1, b.0))
+        });
+        #[cfg(not(feature = "rayon"))]
+        entries.sort_unstable_by(|a, b| (&a.1, a.0).cmp(&(&b.1, b.0)));
+        let mut groups: Vec<Vec<_>> = entries
+            .chunk_by(|a, b| a.1 == b.1)
+            .filter(|group| group.len() > 1)
+            .map(|group| group.iter().map(|(name, _)| *name).collect())
+            .collect();
+        groups.sort_unstable();
+        Ok(groups)
     }
 }
 
-/// Get timezone data from the `tzdata` file reader of Android.
-pub fn find_tz_data_android(
+impl core::ops::Index<usize> for TzDataIndexes {
+    type Output = TzDataIndex;
+
+    fn index(&self, index: usize) -> &TzDataIndex {
+        &self.indexes[index]
+    }
+}
+
+impl<'a> IntoIterator for &'a TzDataIndexes {
+    type Item = &'a TzDataIndex;
+    type IntoIter = core::slice::Iter<'a, TzDataIndex>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.indexes.iter()
+    }
+}
+
+impl IntoIterator for TzDataIndexes {
+    type Item = TzDataIndex;
+    type IntoIter = alloc::vec::IntoIter<TzDataIndex>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.indexes.into_iter()
+    }
+}
+
+/// Read the index region, from `index_offset` to `data_offset`, of a reader positioned at
+/// `index_offset`.
+#[cfg(feature = "std")]
+fn read_index<R: Read>(
+    mut reader: R,
+    header: &TzDataHeader,
+    options: &ParseOptions,
+) -> Result<Vec<u8>> {
+    let mut buf = alloc::vec![0; options.index_len(header)?];
+    reader.read_exact(&mut buf)?;
+    Ok(buf)
+}
+
+/// Binary-search the index region of a reader for a timezone, one entry read per step.
+#[cfg(feature = "std")]
+fn find_streaming<const SIZEOF_INDEX_ENTRY: usize>(
+    mut reader: impl Read + Seek,
+    header: &TzDataHeader,
+    timezone: &[u8],
+) -> Result<Option<TzDataIndex>> {
+    use core::cmp::Ordering;
+
+    let len = header.data_offset.saturating_sub(header.index_offset) as usize;
+    let (mut low, mut high) = (0, len / SIZEOF_INDEX_ENTRY);
+    let mut chunk = [0; SIZEOF_INDEX_ENTRY];
+    while low < high {
+        let mid = low + (high - low) / 2;
+        let position = u64::from(header.index_offset) + (mid * SIZEOF_INDEX_ENTRY) as u64;
+        reader.seek(SeekFrom::Start(position))?;
+        reader.read_exact(&mut chunk)?;
+        // Entries without a NUL-terminated name sort by their full name slot but never match.
+        let name = entry_name(&chunk);
+        // Zero padding after the entries has an empty name.
+        let ordering = if name.is_empty() { Ordering::Greater } else { name.cmp(timezone) };
+        match ordering {
+            Ordering::Less => low = mid + 1,
+            Ordering::Greater => high = mid,
+            Ordering::Equal => return Ok(index_from_entry(&chunk)),
+        }
+    }
+    trace!(debug!(zone = %timezone.escape_ascii(), "zone not found in tzdata index"));
+    Ok(None)
+}
+
+/// Read the timezone data of an index entry, checking it lies before `zonetab_offset`.
+#[cfg(feature = "std")]
+fn read_tzdata(
+    mut reader: impl Read + Seek,
+    header: &TzDataHeader,
+    index: &TzDataIndex,
+    options: &ParseOptions,
+) -> Result<Vec<u8>> {
+    let start = checked_entry_range(header, index, options)?.start;
+    reader.seek(SeekFrom::Start(start))?;
+    let mut buffer = vec![0; index.length as usize];
+    reader.read_exact(&mut buffer)?;
+    Ok(buffer)
+}
+
+/// Range of an entry's data in the file.
+///
+/// Positions in the file are computed as `u64`, which sums of the `u32` offsets and lengths of
+/// the header and entries can't overflow, and only converted to `usize` by [`slice_at`].
+fn entry_range(header: &TzDataHeader, index: &TzDataIndex) -> Range<u64> {
+    let start = u64::from(header.data_offset) + u64::from(index.offset);
+    start..start + u64::from(index.length)
+}
+
+#[cfg(feature = "std")]
+/// Range of an entry's data in the file, failing with [`ZoneInfoError::EntryOutOfBounds`]
+/// unless it ends before `zonetab_offset`, or if the entry exceeds the limits of `options`.
+fn checked_entry_range(
+    header: &TzDataHeader,
+    index: &TzDataIndex,
+    options: &ParseOptions,
+) -> Result<Range<u64>> {
+    let range = entry_range(header, index);
+    if range.end > u64::from(header.zonetab_offset) {
+        return Err(ZoneInfoError::EntryOutOfBounds);
+    }
+    options.check_zone(index)?;
+    Ok(range)
+}
+
+/// Slice a range of positions out of the whole file, or `None` if it lies outside the file,
+/// including when it doesn't fit in `usize` on 32-bit targets.
+fn slice_at(data: &[u8], range: Range<u64>) -> Option<&[u8]> {
+    let start = usize::try_from(range.start).ok()?;
+    let end = usize::try_from(range.end).ok()?;
+    data.get(start..end)
+}
+
+/// Slice the index region, from `index_offset` to `data_offset`, out of the whole file.
+fn index_region<'a>(data: &'a [u8], header: &TzDataHeader) -> Result<&'a [u8]> {
+    let start = u64::from(header.index_offset);
+    let end = u64::from(header.data_offset).max(start);
+    slice_at(data, start..end).ok_or(ZoneInfoError::TruncatedIndex)
+}
+
+/// Split the index region into whole entries and the zero padding some builds append, either to
+/// a size that isn't a multiple of the entry size or as whole empty entries.
+fn strip_index_padding<const SIZEOF_INDEX_ENTRY: usize>(buf: &[u8]) -> Result<(&[u8], usize)> {
+    let (mut entries, remainder) = buf.split_at(buf.len() - buf.len() % SIZEOF_INDEX_ENTRY);
+    if remainder.iter().any(|&x| x != 0) {
+        return Err(ZoneInfoError::TruncatedIndex);
+    }
+    let mut padding = remainder.len();
+    while let Some(rest) = entries.strip_suffix(&[0; SIZEOF_INDEX_ENTRY][..]) {
+        entries = rest;
+        padding += SIZEOF_INDEX_ENTRY;
+    }
+    Ok((entries, padding))
+}
+
+/// Big-endian `u32` at `at`, or `None` if `bytes` ends before it.
+fn be_u32_at(bytes: &[u8], at: usize) -> Option<u32> {
+    Some(u32::from_be_bytes(*bytes.get(at..)?.first_chunk()?))
+}
+
+#[cfg(feature = "std")]
+/// Name of an entry up to its NUL terminator, or its whole name slot if it has none.
+fn entry_name(chunk: &[u8]) -> &[u8] {
+    let name = chunk.get(..SIZEOF_TZNAME).unwrap_or(chunk);
+    name.split(|&x| x == 0).next().unwrap_or(name)
+}
+
+/// Build the index of an entry, reading the raw UTC offset of Android entries.
+fn index_from_entry(chunk: &[u8]) -> Option<TzDataIndex> {
+    let (name, offset, length) = parse_index_entry(chunk)?;
+    let raw_utc_offset = match chunk.len() {
+        SIZEOF_INDEX_ENTRY_ANDROID => Some(be_u32_at(chunk, SIZEOF_TZNAME + 8)? as i32),
+        _ => None,
+    };
+    Some(TzDataIndex { name: Arc::from(name), offset, length, raw_utc_offset })
+}
+
+/// Parse the `name, offset, length` of an index entry, or `None` if the name isn't
+/// NUL-terminated or the entry is too short.
+fn parse_index_entry(chunk: &[u8]) -> Option<(&[u8], u32, u32)> {
+    let name = CStr::from_bytes_until_nul(chunk.get(..SIZEOF_TZNAME)?).ok()?;
+    let offset = be_u32_at(chunk, SIZEOF_TZNAME)?;
+    let length = be_u32_at(chunk, SIZEOF_TZNAME + 4)?;
+    Some((name.to_bytes(), offset, length))
+}
+
+/// Fuzz entry point: parse a header from arbitrary bytes and check that it round-trips.
+#[cfg(fuzzing)]
+#[doc(hidden)]
+pub fn fuzz_parse_header(data: &[u8]) {
+    if let Ok(header) = TzDataHeader::from_bytes(data) {
+        let _ = header.validate_layout(data.len() as u64);
+        assert_eq!(TzDataHeader::from_bytes(&header.to_bytes()).ok(), Some(header));
+    }
+}
+
+/// Fuzz entry point: parse the indexes of arbitrary bytes as a whole `tzdata` file in every
+/// format, and look up the first zone found.
+#[cfg(fuzzing)]
+#[doc(hidden)]
+pub fn fuzz_parse_index(data: &[u8]) {
+    let Ok(header) = TzDataHeader::from_bytes(data) else {
+        return;
+    };
+    for format in [TzDataFormatVersion::AndroidRawOffset, TzDataFormatVersion::NoRawOffset] {
+        if let Ok(indexes) = TzDataIndexes::from_bytes_with_format(data, &header, format)
+            && let Some(index) = indexes.timezones().first()
+        {
+            let _ = indexes.validate(&header, data.len() as u64);
+            let _ = indexes.find_timezone(&index.name);
+            #[cfg(feature = "std")]
+            let _ = indexes.find_tzdata(std::io::Cursor::new(data), &header, index);
+        }
+    }
+    let _ = TzDataIndexes::from_bytes_auto(data, &header);
+    #[cfg(feature = "std")]
+    {
+        let index = data.get(header.index_offset as usize..).unwrap_or_default();
+        let _ = TzDataIndexes::new_strict_android(index, &header);
+        let _ = TzDataIndexes::new_strict_ohos(index, &header);
+        for flavor in [TzDataFlavor::Android, TzDataFlavor::Ohos] {
+            let _ = find_tz_data(std::io::Cursor::new(data), flavor, b"Asia/Shanghai");
+        }
+        for flavor in [TzDataFlavor::Android, TzDataFlavor::Ohos] {
+            let _ = find_tz_data_streaming(std::io::Cursor::new(data), flavor, b"Asia/Shanghai");
+        }
+    }
+}
+
+/// Get timezone data from a `tzdata` file reader of the given flavor, e.g. one picked at
+/// runtime or by [`TzDataFlavor::native`].
+#[cfg(feature = "std")]
+pub fn find_tz_data(
+    reader: impl Read + Seek,
+    flavor: TzDataFlavor,
+    tz_name: &[u8],
+) -> Result<Option<Vec<u8>>> {
+    find_tz_data_with_options(reader, flavor, tz_name, &ParseOptions::default())
+}
+
+/// Get timezone data from a `tzdata` file reader of the given flavor within the limits of
+/// `options`.
+#[cfg(feature = "std")]
+pub fn find_tz_data_with_options(
     mut reader: impl Read + Seek,
+    flavor: TzDataFlavor,
     tz_name: &[u8],
+    options: &ParseOptions,
 ) -> Result<Option<Vec<u8>>> {
     let header = TzDataHeader::new(&mut reader)?;
-    let index = TzDataIndexes::new_android(&mut reader, &header)?;
+    let index = TzDataIndexes::new_with_format(&mut reader, &header, flavor.into(), options)?;
     Ok(if let Some(entry) = index.find_timezone(tz_name) {
-        Some(index.find_tzdata(reader, &header, entry)?)
+        Some(index.find_tzdata_with_options(reader, &header, entry, options)?)
     } else {
         None
     })
 }
 
-/// Get timezone data from the `tzdata` file reader of HarmonyOS NEXT.
-pub fn find_tz_data_ohos(mut reader: impl Read + Seek, tz_name: &[u8]) -> Result<Option<Vec<u8>>> {
+/// Get timezone data from the `tzdata` file reader of Android.
+#[cfg(feature = "std")]
+pub fn find_tz_data_android(reader: impl Read + Seek, tz_name: &[u8]) -> Result<Option<Vec<u8>>> {
+    find_tz_data(reader, TzDataFlavor::Android, tz_name)
+}
+
+/// Get timezone data from the `tzdata` file reader of Android within the limits of `options`.
+#[cfg(feature = "std")]
+pub fn find_tz_data_android_with_options(
+    reader: impl Read + Seek,
+    tz_name: &[u8],
+    options: &ParseOptions,
+) -> Result<Option<Vec<u8>>> {
+    find_tz_data_with_options(reader, TzDataFlavor::Android, tz_name, options)
+}
+
+/// Get timezone data from a `tzdata` file reader of the given flavor without loading the
+/// index.
+///
+/// The index is binary-searched on disk one entry at a time, see
+/// [`TzDataIndexes::find_timezone_streaming_android`], so peak memory stays at one index entry
+/// plus the zone's data however large the file, at the cost of a seek per probed entry. This
+/// suits single lookups on devices short of RAM; load a [`TzDataIndexes`] for many.
+#[cfg(feature = "std")]
+pub fn find_tz_data_streaming(
+    mut reader: impl Read + Seek,
+    flavor: TzDataFlavor,
+    tz_name: &[u8],
+) -> Result<Option<Vec<u8>>> {
     let header = TzDataHeader::new(&mut reader)?;
-    let index = TzDataIndexes::new_ohos(&mut reader, &header)?;
-    Ok(if let Some(entry) = index.find_timezone(tz_name) {
-        Some(index.find_tzdata(reader, &header, entry)?)
-    } else {
-        None
-    })
+    let index = match flavor {
+        TzDataFlavor::Android => {
+            TzDataIndexes::find_timezone_streaming_android(&mut reader, &header, tz_name)?
+        }
+        TzDataFlavor::Ohos => {
+            TzDataIndexes::find_timezone_streaming_ohos(&mut reader, &header, tz_name)?
+        }
+    };
+    let Some(index) = index else { return Ok(None) };
+    read_tzdata(reader, &header, &index, &ParseOptions::default()).map(Some)
 }
 
-/// Get timezone data from the `tzdata` file of Android.
-pub fn find_tz_data_android_from_fs(tz_string: &str) -> Result<Option<Vec<u8>>> {
-    fn open_android_tz_data_file() -> Result<File> {
-        struct TzdataLocation {
-            env_var: &'static str,
-            path: &'static str,
+/// Get timezone data from a `tzdata` stream of the given flavor that can't seek, e.g. a pipe,
+/// a decompressor or a network body.
+///
+/// The stream is consumed in order: the header, the index one entry at a time until the zone
+/// is found or passed, then forward to its data, which must come after the index. Unlike
+/// [`find_tz_data_streaming`], this needs no [`Seek`] but reads up to the whole file.
+#[cfg(feature = "std")]
+pub fn find_tz_data_sequential(
+    reader: impl Read,
+    flavor: TzDataFlavor,
+    tz_name: &[u8],
+) -> Result<Option<Vec<u8>>> {
+    match flavor {
+        TzDataFlavor::Android => find_sequential::<SIZEOF_INDEX_ENTRY_ANDROID>(reader, tz_name),
+        TzDataFlavor::Ohos => find_sequential::<SIZEOF_INDEX_ENTRY_OHOS>(reader, tz_name),
+    }
+}
+
+#[cfg(feature = "std")]
+fn find_sequential<const SIZEOF_INDEX_ENTRY: usize>(
+    mut reader: impl Read,
+    tz_name: &[u8],
+) -> Result<Option<Vec<u8>>> {
+    /// Discard the next `len` bytes of the stream.
+    fn skip(reader: &mut impl Read, len: u64) -> Result<()> {
+        let skipped = std::io::copy(&mut reader.take(len), &mut std::io::sink())?;
+        if skipped < len {
+            return Err(std::io::Error::from(std::io::ErrorKind::UnexpectedEof).into());
         }
+        Ok(())
+    }
 
-        const TZDATA_LOCATIONS: [TzdataLocation; 2] = [
-            TzdataLocation { env_var: "ANDROID_DATA", path: "/misc/zoneinfo" },
-            TzdataLocation { env_var: "ANDROID_ROOT", path: "/usr/share/zoneinfo" },
-        ];
+    let header = TzDataHeader::new(&mut reader)?;
+    let gap = u64::from(header.index_offset).checked_sub(SIZEOF_HEADER as u64);
+    skip(&mut reader, gap.ok_or(ZoneInfoError::InvalidLayout)?)?;
+    let mut position = u64::from(header.index_offset);
 
-        for location in &TZDATA_LOCATIONS {
-            if let Ok(env_value) = std::env::var(location.env_var) {
-                if let Ok(file) = File::open(format!("{}{}/tzdata", env_value, location.path)) {
-                    return Ok(file);
-                }
-            }
+    let len = header.data_offset.saturating_sub(header.index_offset) as usize;
+    let mut chunk = [0; SIZEOF_INDEX_ENTRY];
+    let mut found = None;
+    for _ in 0..len / SIZEOF_INDEX_ENTRY {
+        reader.read_exact(&mut chunk)?;
+        position += SIZEOF_INDEX_ENTRY as u64;
+        let name = entry_name(&chunk);
+        // Names are sorted, and zero padding after the entries has an empty name.
+        if name.is_empty() || name > tz_name {
+            break;
+        }
+        if name == tz_name {
+            found = index_from_entry(&chunk);
+            break;
         }
-        Err(std::io::Error::from(std::io::ErrorKind::NotFound))
     }
-    let mut file = open_android_tz_data_file()?;
-    find_tz_data_android(&mut file, tz_string.as_bytes())
+    let Some(index) = found else {
+        trace!(debug!(zone = %tz_name.escape_ascii(), "zone not found in tzdata index"));
+        return Ok(None);
+    };
+
+    let start = checked_entry_range(&header, &index, &ParseOptions::default())?.start;
+    skip(&mut reader, start.checked_sub(position).ok_or(ZoneInfoError::InvalidLayout)?)?;
+    let mut buffer = vec![0; index.length as usize];
+    reader.read_exact(&mut buffer)?;
+    Ok(Some(buffer))
+}
+
+/// Get timezone data from the `tzdata` file reader of HarmonyOS NEXT.
+#[cfg(feature = "std")]
+pub fn find_tz_data_ohos(reader: impl Read + Seek, tz_name: &[u8]) -> Result<Option<Vec<u8>>> {
+    find_tz_data(reader, TzDataFlavor::Ohos, tz_name)
+}
+
+/// Get timezone data from the `tzdata` file reader of HarmonyOS NEXT within the limits of
+/// `options`.
+#[cfg(feature = "std")]
+pub fn find_tz_data_ohos_with_options(
+    reader: impl Read + Seek,
+    tz_name: &[u8],
+    options: &ParseOptions,
+) -> Result<Option<Vec<u8>>> {
+    find_tz_data_with_options(reader, TzDataFlavor::Ohos, tz_name, options)
+}
+
+/// Get timezone data from the system `tzdata` file of the given flavor, found by
+/// [`TzdataLocator::android`] or [`TzdataLocator::ohos`].
+///
+/// Custom offset IDs like `GMT+05:30`, which aren't in the file, are synthesized, see
+/// [`gmt_offset_tzdata`]. The parsed index is cached for the process until the file's
+/// modification time or length changes.
+#[cfg(feature = "fs")]
+pub fn find_tz_data_from_fs(flavor: TzDataFlavor, tz_string: &str) -> Result<Option<Vec<u8>>> {
+    let locator = match flavor {
+        TzDataFlavor::Android => TzdataLocator::android(),
+        TzDataFlavor::Ohos => TzdataLocator::ohos(),
+    };
+    let (file, path) = locator.open_with_path()?;
+    let tzdata = index_cache::find_tz_data_cached(file, path, flavor.into(), tz_string.as_bytes())?;
+    Ok(tzdata.or_else(|| gmt_offset_tzdata(tz_string.as_bytes())))
 }
 
-/// Get timezone data from the `tzdata` file of HarmonyOS NEXT.
+/// Get timezone data from the `tzdata` file of Android, see [`find_tz_data_from_fs`].
+#[cfg(feature = "fs")]
+pub fn find_tz_data_android_from_fs(tz_string: &str) -> Result<Option<Vec<u8>>> {
+    find_tz_data_from_fs(TzDataFlavor::Android, tz_string)
+}
+
+/// Get timezone data from the `tzdata` file of HarmonyOS NEXT, see [`find_tz_data_from_fs`].
+#[cfg(feature = "fs")]
 pub fn find_tz_data_ohos_from_fs(tz_string: &str) -> Result<Option<Vec<u8>>> {
-    const TZDATA_PATH: &str = "/system/etc/zoneinfo/tzdata";
-    match File::open(TZDATA_PATH) {
-        Ok(mut file) => Ok(find_tz_data_ohos(&mut file, tz_string.as_bytes())?),
-        Err(err) => Err(err),
-    }
+    find_tz_data_from_fs(TzDataFlavor::Ohos, tz_string)
+}
+
+/// Get timezone data from the newer of the updated and system `tzdata` files of HarmonyOS
+/// NEXT, also returning which one was used.
+///
+/// Unlike [`find_tz_data_ohos_from_fs`], `$TZDATA_PATH` isn't searched and the update copy
+/// isn't preferred just for existing; see [`OhosTzDataSource`] for the paths. Custom offset IDs
+/// are synthesized the same way.
+#[cfg(feature = "fs")]
+pub fn find_tz_data_ohos_from_fs_versioned(
+    tz_string: &str,
+) -> Result<(Option<Vec<u8>>, OhosTzDataSource)> {
+    find_tz_data_ohos_versioned_in(
+        OhosTzDataSource::Update.path(),
+        OhosTzDataSource::System.path(),
+        tz_string,
+    )
+}
+
+/// [`find_tz_data_ohos_from_fs_versioned`] with the given update and system paths.
+#[cfg(feature = "fs")]
+fn find_tz_data_ohos_versioned_in(
+    update: &std::path::Path,
+    system: &std::path::Path,
+    tz_string: &str,
+) -> Result<(Option<Vec<u8>>, OhosTzDataSource)> {
+    let (file, source) = locator::open_ohos_versioned(update, system)?;
+    let path = match source {
+        OhosTzDataSource::Update => update,
+        OhosTzDataSource::System => system,
+    };
+    let format = TzDataFormatVersion::NoRawOffset;
+    let tzdata = index_cache::find_tz_data_cached(file, path, format, tz_string.as_bytes())?;
+    Ok((tzdata.or_else(|| gmt_offset_tzdata(tz_string.as_bytes())), source))
 }
 
-#[cfg(test)]
+#[cfg(all(test, feature = "std"))]
 mod tests {
+    use std::fs::File;
+
     use super::*;
 
     #[test]
@@ -219,6 +1412,81 @@ mod tests {
         assert_eq!(iter.timezones().len(), 442);
         assert!(iter.find_timezone(b"Asia/Shanghai").is_some());
         assert!(iter.find_timezone(b"Pacific/Noumea").is_some());
+        assert!(iter.timezones().iter().all(|x| x.raw_utc_offset.is_none()));
+    }
+
+    #[test]
+    fn test_malformed_index_is_rejected_without_panicking() {
+        assert_eq!(parse_index_entry(b"Asia/Shanghai\0"), None);
+        assert_eq!(parse_index_entry(&[0; SIZEOF_TZNAME + 7]), None);
+        assert_eq!(entry_name(b"UTC"), b"UTC");
+        assert!(matches!(
+            TzDataHeader::from_bytes(b"tzdata2024a"),
+            Err(ZoneInfoError::TruncatedHeader)
+        ));
+
+        let data = std::fs::read("./tests/android/tzdata").unwrap();
+        let header = TzDataHeader::from_bytes(&data).unwrap();
+        let index_end = header.data_offset as usize;
+        for len in (0..=SIZEOF_HEADER).chain(index_end - 3 * SIZEOF_INDEX_ENTRY_ANDROID..index_end)
+        {
+            let truncated = &data[..len];
+            if let Ok(header) = TzDataHeader::from_bytes(truncated) {
+                assert!(TzDataIndexes::from_bytes_auto(truncated, &header).is_err());
+            }
+        }
+        // An index region ending in a partial entry with a name in it.
+        let mut shortened = header;
+        shortened.data_offset -= 7;
+        assert!(TzDataIndexes::from_bytes_android(&data, &shortened).is_err());
+        let mut corrupted = data.clone();
+        corrupted[index_end - 20..index_end].fill(0xff);
+        let indexes = TzDataIndexes::from_bytes_android(&corrupted, &header).unwrap();
+        assert_eq!(indexes.len(), 593);
+    }
+
+    #[test]
+    fn test_tzdata_header_sections() {
+        let data = std::fs::read("./tests/ohos/tzdata").unwrap();
+        let header = TzDataHeader::from_bytes(&data).unwrap();
+        let len = data.len() as u64;
+        let sections = header.sections(len);
+        assert_eq!(sections.header, 0..24);
+        assert_eq!(sections.index, 24..u64::from(header.data_offset));
+        assert_eq!(sections.data, u64::from(header.data_offset)..u64::from(header.zonetab_offset));
+        assert_eq!(sections.zonetab, u64::from(header.zonetab_offset)..len);
+        let index = &data[sections.index.start as usize..sections.index.end as usize];
+        assert_eq!(TzDataIndexes::from_bytes_ohos(&data, &header).unwrap().len(), index.len() / 48);
+
+        // Inverted offsets give empty ranges, and offsets past the end are clamped.
+        let inverted = TzDataHeader { data_offset: 10, zonetab_offset: u32::MAX, ..header };
+        assert_eq!(inverted.index_range(), 24..24);
+        assert_eq!(inverted.data_range(len), 10..len);
+        assert_eq!(inverted.zonetab_range(len), len..len);
+        assert_eq!(inverted.sections(12).header, 0..12);
+    }
+
+    #[test]
+    fn test_offsets_near_u32_max() {
+        let data = std::fs::read("./tests/ohos/tzdata").unwrap();
+        let header = TzDataHeader::from_bytes(&data).unwrap();
+        let indexes = TzDataIndexes::from_bytes_ohos(&data, &header).unwrap();
+        let index = TzDataIndex { offset: u32::MAX, length: u32::MAX, ..indexes[0].clone() };
+        let far = TzDataHeader { data_offset: u32::MAX, zonetab_offset: u32::MAX, ..header };
+        assert_eq!(entry_range(&far, &index), 2 * u64::from(u32::MAX)..3 * u64::from(u32::MAX));
+        assert_eq!(slice_at(&data, entry_range(&far, &index)), None);
+        let err = indexes.find_tzdata(std::io::Cursor::new(&data), &far, &index).unwrap_err();
+        assert!(matches!(err, ZoneInfoError::EntryOutOfBounds));
+
+        // An entry whose offset wraps around in `u32` arithmetic.
+        let mut crafted = data.clone();
+        let wrapping = u32::MAX - header.data_offset + 1;
+        let entry = header.index_offset as usize + SIZEOF_TZNAME;
+        crafted[entry..entry + 4].copy_from_slice(&wrapping.to_be_bytes());
+        let name = &indexes[0].name;
+        assert_eq!(TzDataRef::new_ohos(&crafted).unwrap().find_tzdata(name), None);
+        let db = ZoneInfoDb::from_bytes(crafted).unwrap();
+        assert_eq!(db.get(name), None);
     }
 
     #[test]
@@ -244,6 +1512,7 @@ mod tests {
         assert_eq!(iter.timezones().len(), 593);
         assert!(iter.find_timezone(b"Asia/Shanghai").is_some());
         assert!(iter.find_timezone(b"Pacific/Noumea").is_some());
+        assert!(iter.timezones().iter().all(|x| x.raw_utc_offset == Some(0)));
     }
 
     #[test]
@@ -256,6 +1525,232 @@ mod tests {
         assert_eq!(tzdata.len(), 573);
     }
 
+    #[test]
+    fn test_find_tz_data_by_flavor() {
+        for (path, flavor, len) in [
+            ("./tests/android/tzdata", TzDataFlavor::Android, 573),
+            ("./tests/ohos/tzdata", TzDataFlavor::Ohos, 393),
+        ] {
+            let tzdata = find_tz_data(File::open(path).unwrap(), flavor, b"Asia/Shanghai");
+            assert_eq!(tzdata.unwrap().unwrap().len(), len);
+        }
+        let android = find_tz_data_android(File::open("./tests/android/tzdata").unwrap(), b"UTC");
+        let tzdata = find_tz_data(
+            File::open("./tests/android/tzdata").unwrap(),
+            TzDataFlavor::Android,
+            b"UTC",
+        );
+        assert_eq!(tzdata.unwrap(), android.unwrap());
+        let native = cfg!(any(target_os = "android", target_env = "ohos"));
+        assert_eq!(TzDataFlavor::native().is_some(), native);
+    }
+
+    #[test]
+    fn test_tzdata_index_flavor_detection() {
+        for (path, flavor, count) in [
+            ("./tests/android/tzdata", TzDataFlavor::Android, 593),
+            ("./tests/ohos/tzdata", TzDataFlavor::Ohos, 442),
+        ] {
+            let file = File::open(path).unwrap();
+            let header = TzDataHeader::new(&file).unwrap();
+            let (iter, detected) = TzDataIndexes::new_auto(&file, &header).unwrap();
+            assert_eq!(detected, flavor);
+            assert_eq!(iter.timezones().len(), count);
+        }
+
+        // 624 entries of OHOS size are also 576 of Android size.
+        let mut data = std::fs::read("./tests/ohos/tzdata").unwrap();
+        let header = TzDataHeader::new(&data[..]).unwrap();
+        data.truncate(header.data_offset as usize);
+        let (entries, _) = data[24..].split_at(442 * SIZEOF_INDEX_ENTRY_OHOS);
+        let mut index = entries.to_vec();
+        index.extend_from_slice(&[0; (624 - 442) * SIZEOF_INDEX_ENTRY_OHOS]);
+        let header = TzDataHeader { data_offset: 24 + index.len() as u32, ..header };
+        let (iter, detected) = TzDataIndexes::new_auto(&index[..], &header).unwrap();
+        assert_eq!(detected, TzDataFlavor::Ohos);
+        assert_eq!(iter.timezones().len(), 442);
+
+        let garbage = vec![0xff; 624];
+        let header = TzDataHeader { data_offset: 24 + 624, ..header };
+        assert!(matches!(
+            TzDataIndexes::new_auto(&garbage[..], &header),
+            Err(ZoneInfoError::UnknownFlavor)
+        ));
+    }
+
+    #[test]
+    fn test_tzdata_format_version() {
+        for (path, format, count) in [
+            ("./tests/android/tzdata", TzDataFormatVersion::AndroidRawOffset, 593),
+            ("./tests/ohos/tzdata", TzDataFormatVersion::NoRawOffset, 442),
+        ] {
+            let data = std::fs::read(path).unwrap();
+            let header = TzDataHeader::from_bytes(&data).unwrap();
+            assert_eq!(TzDataFormatVersion::detect(&data, &header).unwrap(), format);
+            assert_eq!(TzDataFormatVersion::from(format.flavor()), format);
+            let indexes = TzDataIndexes::from_bytes_with_format(&data, &header, format).unwrap();
+            assert_eq!(indexes.timezones().len(), count);
+            let options = ParseOptions::default();
+            let indexes =
+                TzDataIndexes::new_with_format(&data[24..], &header, format, &options).unwrap();
+            assert_eq!(indexes.timezones().len(), count);
+        }
+        assert_eq!(TzDataFormatVersion::AndroidRawOffset.entry_size(), 52);
+        assert_eq!(TzDataFormatVersion::NoRawOffset.entry_size(), 48);
+    }
+
+    #[test]
+    fn test_tzdata_serialize_roundtrip() {
+        for (path, format) in [
+            ("./tests/android/tzdata", TzDataFormatVersion::AndroidRawOffset),
+            ("./tests/ohos/tzdata", TzDataFormatVersion::NoRawOffset),
+        ] {
+            let data = std::fs::read(path).unwrap();
+            let header = TzDataHeader::from_bytes(&data).unwrap();
+            assert_eq!(header.to_bytes(), data[..24]);
+            let mut written = Vec::new();
+            header.write_to(&mut written).unwrap();
+            let indexes = TzDataIndexes::from_bytes_with_format(&data, &header, format).unwrap();
+            for index in indexes.timezones() {
+                index.write_to(format, &mut written).unwrap();
+            }
+            assert_eq!(written, data[..written.len()]);
+            let reparsed =
+                TzDataIndexes::from_bytes_with_format(&written, &header, format).unwrap();
+            assert_eq!(reparsed.timezones(), indexes.timezones());
+        }
+
+        let mut index = TzDataIndex {
+            name: Arc::from(&[b'A'; 40][..]),
+            offset: 0,
+            length: 0,
+            raw_utc_offset: None,
+        };
+        let format = TzDataFormatVersion::NoRawOffset;
+        assert!(matches!(index.to_bytes(format), Err(ZoneInfoError::InvalidZoneName)));
+        index.name = Arc::from(&b"UTC"[..]);
+        assert_eq!(index.to_bytes(TzDataFormatVersion::AndroidRawOffset).unwrap().len(), 52);
+    }
+
+    #[test]
+    fn test_invalid_header_magic() {
+        let err = TzDataHeader::new(&b"tzdato2024a\0\0\0\0\0\0\0\0\0\0\0\0\0"[..]).unwrap_err();
+        assert!(matches!(err, ZoneInfoError::InvalidMagic));
+        let err = std::io::Error::from(err);
+        assert_eq!(err.kind(), std::io::ErrorKind::Other);
+        assert_eq!(err.to_string(), "invalid tzdata header magic");
+
+        let err = TzDataHeader::new(&b"tzdata"[..]).unwrap_err();
+        assert_eq!(std::io::Error::from(err).kind(), std::io::ErrorKind::UnexpectedEof);
+    }
+
+    #[test]
+    fn test_tzdata_header_validate_layout() {
+        let header = TzDataHeader::new(File::open("./tests/ohos/tzdata").unwrap()).unwrap();
+        header.validate_layout(272428).unwrap();
+        let err = header.validate_layout(272427).unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "tzdata zonetab section starts at byte 272428, past the end of the 272427-byte file"
+        );
+        let empty =
+            TzDataHeader { index_offset: 24, data_offset: 24, zonetab_offset: 24, ..header };
+        empty.validate_layout(24).unwrap();
+
+        for (header, first, second) in [
+            (TzDataHeader { index_offset: 12, ..header }, "header", "index"),
+            (TzDataHeader { data_offset: 20, ..header }, "index", "data"),
+            (TzDataHeader { zonetab_offset: 21239, ..header }, "data", "zonetab"),
+        ] {
+            let err = header.validate_layout(u64::MAX).unwrap_err();
+            assert!(matches!(
+                err,
+                ZoneInfoError::OverlappingSections { first: a, second: b } if (a, b) == (first, second)
+            ));
+        }
+    }
+
+    #[test]
+    fn test_tzdata_from_bytes() {
+        for (path, flavor) in [
+            ("./tests/android/tzdata", TzDataFlavor::Android),
+            ("./tests/ohos/tzdata", TzDataFlavor::Ohos),
+        ] {
+            let data = std::fs::read(path).unwrap();
+            let header = TzDataHeader::from_bytes(&data).unwrap();
+            assert_eq!(header, TzDataHeader::new(&data[..]).unwrap());
+            let indexes = match flavor {
+                TzDataFlavor::Android => TzDataIndexes::from_bytes_android(&data, &header),
+                TzDataFlavor::Ohos => TzDataIndexes::from_bytes_ohos(&data, &header),
+            }
+            .unwrap();
+            let (auto, detected) = TzDataIndexes::from_bytes_auto(&data, &header).unwrap();
+            assert_eq!(detected, flavor);
+            assert_eq!(auto.timezones(), indexes.timezones());
+            assert!(indexes.find_timezone(b"Asia/Shanghai").is_some());
+
+            assert!(matches!(
+                TzDataIndexes::from_bytes_ohos(&data[..100], &header),
+                Err(ZoneInfoError::TruncatedIndex)
+            ));
+        }
+        assert!(matches!(
+            TzDataHeader::from_bytes(b"tzdata2024a\0"),
+            Err(ZoneInfoError::TruncatedHeader)
+        ));
+    }
+
+    #[test]
+    fn test_ohos_tzdata_index_padding() {
+        let mut data = std::fs::read("./tests/ohos/tzdata").unwrap();
+        let header = TzDataHeader::new(&data[..]).unwrap();
+        let iter = TzDataIndexes::new_ohos(&data[24..], &header).unwrap();
+        assert_eq!(iter.padding(), 0);
+
+        // An empty entry and a partial one, as emitted by padded builds.
+        let padding = SIZEOF_INDEX_ENTRY_OHOS + 16;
+        data.splice(21240..21240, std::iter::repeat_n(0, padding));
+        data[16..20].copy_from_slice(&(21240 + padding as u32).to_be_bytes());
+        let header = TzDataHeader::new(&data[..]).unwrap();
+        let iter = TzDataIndexes::new_ohos(&data[24..], &header).unwrap();
+        assert_eq!(iter.timezones().len(), 442);
+        assert_eq!(iter.padding(), padding);
+        assert!(iter.find_timezone(b"Pacific/Noumea").is_some());
+
+        // Trailing bytes that aren't zeros are a truncated entry rather than padding.
+        data[21240 + SIZEOF_INDEX_ENTRY_OHOS] = b'A';
+        assert!(matches!(
+            TzDataIndexes::new_ohos(&data[24..], &header),
+            Err(ZoneInfoError::TruncatedIndex)
+        ));
+    }
+
+    #[test]
+    fn test_tzdata_index_clone_and_hash() {
+        let file = File::open("./tests/android/tzdata").unwrap();
+        let header = TzDataHeader::new(&file).unwrap();
+        let iter = TzDataIndexes::new_android(&file, &header).unwrap();
+        let timezone = iter.find_timezone(b"Asia/Shanghai").unwrap().clone();
+        assert!(Arc::ptr_eq(&timezone.name, &iter.find_timezone(b"Asia/Shanghai").unwrap().name));
+
+        let set: std::collections::HashSet<_> = iter.timezones().iter().cloned().collect();
+        assert_eq!(set.len(), 593);
+        assert!(set.contains(&timezone));
+        let sorted: std::collections::BTreeSet<_> = (&iter).into_iter().cloned().collect();
+        assert!(sorted.iter().eq(&iter));
+        assert_eq!(iter.len(), 593);
+        assert!(!iter.is_empty());
+        assert_eq!(iter[0].name, iter.timezones()[0].name);
+        assert_eq!(iter.into_iter().max().unwrap().name[..], b"Zulu"[..]);
+
+        let name = timezone.name_arc_str().unwrap();
+        assert_eq!(&*name, "Asia/Shanghai");
+        assert_eq!(name.as_ptr(), timezone.name.as_ptr());
+        let mut invalid = timezone.clone();
+        invalid.name = Arc::from(&b"Asia/\xff"[..]);
+        assert!(matches!(invalid.name_arc_str(), Err(ZoneInfoError::InvalidZoneName)));
+    }
+
     #[test]
     fn test_ohos_tzdata_find() {
         let file = File::open("./tests/ohos/tzdata").unwrap();
@@ -270,6 +1765,354 @@ mod tests {
         assert_eq!(tzdata.len(), 573);
     }
 
+    #[test]
+    fn test_tzdata_index_strict() {
+        let mut data = std::fs::read("./tests/android/tzdata").unwrap();
+        let header = TzDataHeader::new(&data[..]).unwrap();
+        let index = &data[header.index_offset as usize..];
+        assert_eq!(
+            TzDataIndexes::new_strict_android(index, &header).unwrap().timezones().len(),
+            593
+        );
+
+        // Fill the name slot of entry 3 so it loses its NUL terminator.
+        let start = 24 + 3 * SIZEOF_INDEX_ENTRY_ANDROID;
+        data[start..start + SIZEOF_TZNAME].fill(b'A');
+        let index = &data[header.index_offset as usize..];
+        assert_eq!(TzDataIndexes::new_android(index, &header).unwrap().skipped(), 1);
+        assert!(matches!(
+            TzDataIndexes::new_strict_android(index, &header),
+            Err(ZoneInfoError::MalformedEntry { entry: 3, offset: 180 })
+        ));
+    }
+
+    #[test]
+    fn test_tzdata_index_bounds() {
+        let data = std::fs::read("./tests/ohos/tzdata").unwrap();
+        let header = TzDataHeader::from_bytes(&data).unwrap();
+        let mut indexes = TzDataIndexes::from_bytes_ohos(&data, &header).unwrap();
+        indexes.validate(&header, data.len() as u64).unwrap();
+        assert!(matches!(
+            indexes.validate(&header, data.len() as u64 - 1),
+            Err(ZoneInfoError::EntryOutOfBounds)
+        ));
+
+        let last = indexes.indexes.last_mut().unwrap();
+        last.length = u32::MAX;
+        let last = last.clone();
+        assert!(matches!(
+            indexes.validate(&header, data.len() as u64),
+            Err(ZoneInfoError::EntryOutOfBounds)
+        ));
+        assert!(matches!(
+            indexes.find_tzdata(std::io::Cursor::new(&data), &header, &last),
+            Err(ZoneInfoError::EntryOutOfBounds)
+        ));
+    }
+
+    #[test]
+    fn test_tzdata_index_find_streaming() {
+        /// Counts the seeks of a reader.
+        struct Counting<R>(R, usize);
+        impl<R: Read> Read for Counting<R> {
+            fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+                self.0.read(buf)
+            }
+        }
+        impl<R: Seek> Seek for Counting<R> {
+            fn seek(&mut self, pos: SeekFrom) -> std::io::Result<u64> {
+                self.1 += 1;
+                self.0.seek(pos)
+            }
+        }
+
+        let file = File::open("./tests/android/tzdata").unwrap();
+        let header = TzDataHeader::new(&file).unwrap();
+        let indexes = TzDataIndexes::new_android(&file, &header).unwrap();
+        let mut reader = Counting(&file, 0);
+        for index in indexes.timezones() {
+            let found =
+                TzDataIndexes::find_timezone_streaming_android(&mut reader, &header, &index.name);
+            assert_eq!(found.unwrap().as_ref(), Some(index));
+            assert!(std::mem::take(&mut reader.1) <= 10);
+        }
+        let found = TzDataIndexes::find_timezone_streaming_android(&file, &header, b"Asia/Nowhere");
+        assert!(found.unwrap().is_none());
+
+        let file = File::open("./tests/ohos/tzdata").unwrap();
+        let header = TzDataHeader::new(&file).unwrap();
+        let found = TzDataIndexes::find_timezone_streaming_ohos(&file, &header, b"Asia/Shanghai");
+        assert_eq!(found.unwrap().unwrap().raw_utc_offset, None);
+
+        let open = |path| File::open(path).unwrap();
+        let shanghai = find_tz_data_ohos(open("./tests/ohos/tzdata"), b"Asia/Shanghai").unwrap();
+        let streamed = find_tz_data_streaming(
+            open("./tests/ohos/tzdata"),
+            TzDataFlavor::Ohos,
+            b"Asia/Shanghai",
+        );
+        assert_eq!(streamed.unwrap(), shanghai);
+        let android = std::fs::read("./tests/android/tzdata").unwrap();
+        let streamed = find_tz_data_streaming(
+            std::io::Cursor::new(&android),
+            TzDataFlavor::Android,
+            b"Asia/Shanghai",
+        );
+        assert_eq!(streamed.unwrap().unwrap().len(), 573);
+        let streamed = find_tz_data_streaming(
+            std::io::Cursor::new(&android),
+            TzDataFlavor::Android,
+            b"Asia/Nowhere",
+        );
+        assert!(streamed.unwrap().is_none());
+    }
+
+    #[test]
+    fn test_find_tz_data_sequential() {
+        // Slices implement `Read` but not `Seek`.
+        let android = std::fs::read("./tests/android/tzdata").unwrap();
+        let db = ZoneInfoDb::new_android(&android[..]).unwrap();
+        for index in db.indexes().timezones().iter().step_by(37) {
+            let tzdata =
+                find_tz_data_sequential(&android[..], TzDataFlavor::Android, &index.name).unwrap();
+            assert_eq!(tzdata.as_deref(), db.tzdata(index));
+        }
+        for name in [&b"Asia/Nowhere"[..], b"", b"Zzz"] {
+            assert!(
+                find_tz_data_sequential(&android[..], TzDataFlavor::Android, name)
+                    .unwrap()
+                    .is_none()
+            );
+        }
+        assert!(find_tz_data_sequential(&android[..100], TzDataFlavor::Android, b"Zulu").is_err());
+
+        let ohos = std::fs::read("./tests/ohos/tzdata").unwrap();
+        let shanghai =
+            find_tz_data_sequential(&ohos[..], TzDataFlavor::Ohos, b"Asia/Shanghai").unwrap();
+        assert_eq!(shanghai.unwrap().len(), 393);
+    }
+
+    #[cfg(feature = "fs")]
+    #[test]
+    fn test_find_tz_data_ohos_versioned_gmt_offset() {
+        let system = std::path::Path::new("./tests/ohos/tzdata");
+        let update = std::path::Path::new("./tests/ohos/nowhere/tzdata");
+        let find = |name| find_tz_data_ohos_versioned_in(update, system, name).unwrap();
+        let (shanghai, source) = find("Asia/Shanghai");
+        assert_eq!((shanghai.unwrap().len(), source), (393, OhosTzDataSource::System));
+        let (tzdata, source) = find("GMT+05:30");
+        assert_eq!(tzdata, gmt_offset_tzdata(b"GMT+05:30"));
+        assert!(tzdata.is_some() && source == OhosTzDataSource::System);
+        assert_eq!(find("Asia/Nowhere").0, None);
+    }
+
+    #[test]
+    fn test_tzdata_index_locate() {
+        let data = std::fs::read("./tests/android/tzdata").unwrap();
+        let header = TzDataHeader::from_bytes(&data).unwrap();
+        let indexes = TzDataIndexes::from_bytes_android(&data, &header).unwrap();
+        let (offset, length) = indexes.locate(&header, b"Asia/Shanghai").unwrap();
+        assert_eq!(length, 573);
+        let index = indexes.find_timezone(b"Asia/Shanghai").unwrap();
+        let tzdata = indexes.find_tzdata(std::io::Cursor::new(&data), &header, index).unwrap();
+        assert_eq!(data[offset as usize..][..length as usize], tzdata);
+        assert_eq!(indexes.locate(&header, b"Asia/Nowhere"), None);
+    }
+
+    #[test]
+    fn test_tzdata_index_areas() {
+        let file = File::open("./tests/ohos/tzdata").unwrap();
+        let header = TzDataHeader::new(&file).unwrap();
+        let indexes = TzDataIndexes::new_ohos(&file, &header).unwrap();
+        assert_eq!(
+            indexes.areas(),
+            [
+                "Africa",
+                "America",
+                "Antarctica",
+                "Arctic",
+                "Asia",
+                "Atlantic",
+                "Australia",
+                "Brazil",
+                "Europe",
+                "Indian",
+                "Pacific"
+            ]
+        );
+        let asia = indexes.zones_in_area("Asia");
+        assert_eq!(asia.len(), 85);
+        assert!(asia.iter().all(|x| x.name.starts_with(b"Asia/")));
+        assert_eq!(indexes.zones_in_area("America/Argentina").len(), 12);
+        assert!(indexes.zones_in_area("As").is_empty());
+        assert!(indexes.zones_in_area("Nowhere").is_empty());
+    }
+
+    #[test]
+    fn test_tzdata_index_zone_names() {
+        let file = File::open("./tests/ohos/tzdata").unwrap();
+        let header = TzDataHeader::new(&file).unwrap();
+        let mut indexes = TzDataIndexes::new_ohos(&file, &header).unwrap();
+        assert_eq!(indexes.zone_names().count(), 442);
+        assert!(indexes.zone_names().any(|x| x == "Asia/Shanghai"));
+
+        indexes.indexes[0].name = Arc::from(&b"Africa/Caf\xc3\xa9"[..]);
+        assert!(matches!(indexes.indexes[0].name_str(), Err(ZoneInfoError::InvalidZoneName)));
+        assert_eq!(indexes.zone_names().count(), 441);
+        indexes.indexes[0].name = Arc::from(&b""[..]);
+        assert!(indexes.indexes[0].name_str().is_err());
+    }
+
+    #[test]
+    fn test_tzdata_index_lookup_map() {
+        let file = File::open("./tests/android/tzdata").unwrap();
+        let header = TzDataHeader::new(&file).unwrap();
+        let indexes = TzDataIndexes::new_android(&file, &header).unwrap();
+        let map = indexes.build_lookup_map();
+        assert_eq!(map.len(), 593);
+        for (i, index) in indexes.timezones().iter().enumerate() {
+            assert_eq!(map[&*index.name], i);
+        }
+        assert!(!map.contains_key(&b"Asia/Nowhere"[..]));
+    }
+
+    #[test]
+    fn test_tzdata_index_iter_tzdata() {
+        let file = File::open("./tests/android/tzdata").unwrap();
+        let header = TzDataHeader::new(&file).unwrap();
+        let indexes = TzDataIndexes::new_android(&file, &header).unwrap();
+        let all: Vec<_> = indexes.iter_tzdata(&file, &header).collect::<Result<_>>().unwrap();
+        assert_eq!(all.len(), 593);
+        let (_, shanghai) = all.iter().find(|(name, _)| *name == b"Asia/Shanghai").unwrap();
+        let index = indexes.find_timezone(b"Asia/Shanghai").unwrap();
+        assert_eq!(*shanghai, indexes.find_tzdata(&file, &header, index).unwrap());
+
+        let header = TzDataHeader { zonetab_offset: header.data_offset, ..header };
+        let mut iter = indexes.iter_tzdata(&file, &header);
+        assert!(matches!(iter.next(), Some(Err(ZoneInfoError::EntryOutOfBounds))));
+        assert!(iter.next().is_none());
+    }
+
+    #[test]
+    fn test_tzdata_index_find_tzdata_many() {
+        let file = File::open("./tests/android/tzdata").unwrap();
+        let header = TzDataHeader::new(&file).unwrap();
+        let indexes = TzDataIndexes::new_android(&file, &header).unwrap();
+        let names: [&[u8]; 5] =
+            [b"Europe/Paris", b"Asia/Shanghai", b"Asia/Nowhere", b"Asia/Chongqing", b"UTC"];
+        let found = indexes.find_tzdata_many(&file, &header, &names).unwrap();
+        assert_eq!(found.len(), 5);
+        assert!(found[2].is_none());
+        for (name, data) in names.iter().zip(&found) {
+            let expected = indexes
+                .find_timezone(name)
+                .map(|index| indexes.find_tzdata(&file, &header, index).unwrap());
+            assert_eq!(*data, expected);
+        }
+        assert_eq!(found[1], found[3]);
+
+        let header = TzDataHeader { zonetab_offset: header.data_offset, ..header };
+        let err = indexes.find_tzdata_many(&file, &header, &names);
+        assert!(matches!(err, Err(ZoneInfoError::EntryOutOfBounds)));
+    }
+
+    #[test]
+    fn test_tzdata_index_duplicate_groups() {
+        let file = File::open("./tests/android/tzdata").unwrap();
+        let header = TzDataHeader::new(&file).unwrap();
+        let indexes = TzDataIndexes::new_android(&file, &header).unwrap();
+        let groups = indexes.duplicate_groups(&file, &header).unwrap();
+        assert_eq!(groups.len(), 105);
+        assert_eq!(groups.iter().map(Vec::len).sum::<usize>(), 312);
+        let shanghai = groups.iter().find(|names| names.contains(&&b"PRC"[..])).unwrap();
+        assert_eq!(
+            *shanghai,
+            [&b"Asia/Chongqing"[..], b"Asia/Chungking", b"Asia/Harbin", b"Asia/Shanghai", b"PRC"]
+        );
+        assert!(groups.is_sorted());
+
+        // OpenHarmony stores a copy per zone, yet many are identical.
+        let file = File::open("./tests/ohos/tzdata").unwrap();
+        let header = TzDataHeader::new(&file).unwrap();
+        let indexes = TzDataIndexes::new_ohos(&file, &header).unwrap();
+        assert_eq!(indexes.duplicate_groups(&file, &header).unwrap().len(), 42);
+    }
+
+    #[cfg(all(feature = "tracing", feature = "fs"))]
+    #[test]
+    fn test_tracing_events() {
+        use std::sync::{Arc, Mutex};
+
+        use tracing::{Event, Metadata, field, span};
+
+        /// Collects the message of every event.
+        struct Messages(Arc<Mutex<Vec<String>>>);
+        impl tracing::Subscriber for Messages {
+            fn enabled(&self, _: &Metadata<'_>) -> bool {
+                true
+            }
+            fn new_span(&self, _: &span::Attributes<'_>) -> span::Id {
+                span::Id::from_u64(1)
+            }
+            fn record(&self, _: &span::Id, _: &span::Record<'_>) {}
+            fn record_follows_from(&self, _: &span::Id, _: &span::Id) {}
+            fn event(&self, event: &Event<'_>) {
+                struct Message<'a>(&'a mut Vec<String>);
+                impl field::Visit for Message<'_> {
+                    fn record_debug(&mut self, field: &field::Field, value: &dyn Debug) {
+                        if field.name() == "message" {
+                            self.0.push(format!("{value:?}"));
+                        }
+                    }
+                }
+                event.record(&mut Message(&mut self.0.lock().unwrap()));
+            }
+            fn enter(&self, _: &span::Id) {}
+            fn exit(&self, _: &span::Id) {}
+        }
+
+        let messages = Arc::new(Mutex::new(Vec::new()));
+        tracing::subscriber::with_default(Messages(messages.clone()), || {
+            let mut locator = crate::TzdataLocator::new();
+            locator.push("./tests/nowhere/tzdata").push("./tests/ohos/tzdata");
+            let db = ZoneInfoDb::new_ohos(locator.open().unwrap()).unwrap();
+            assert!(db.get(b"Asia/Nowhere").is_none());
+        });
+        assert_eq!(
+            *messages.lock().unwrap(),
+            [
+                "can't open tzdata",
+                "opened tzdata",
+                "parsed tzdata header",
+                "parsed tzdata index",
+                "zone not found in tzdata index"
+            ]
+        );
+    }
+
+    #[cfg(all(feature = "serde", feature = "tzif"))]
+    #[test]
+    fn test_serde_roundtrip() {
+        let data = std::fs::read("./tests/android/tzdata").unwrap();
+        let header = TzDataHeader::from_bytes(&data).unwrap();
+        let json = serde_json::to_string(&header).unwrap();
+        assert_eq!(serde_json::from_str::<TzDataHeader>(&json).unwrap(), header);
+
+        let indexes = TzDataIndexes::from_bytes_android(&data, &header).unwrap();
+        let index = indexes.find_timezone(b"Asia/Shanghai").unwrap();
+        let json = serde_json::to_string(index).unwrap();
+        assert_eq!(&serde_json::from_str::<TzDataIndex>(&json).unwrap(), index);
+
+        let zone_info =
+            ZoneInfo::new(&data[(header.data_offset + index.offset) as usize..]).unwrap();
+        let json = serde_json::to_string(zone_info.tzif()).unwrap();
+        assert_eq!(&serde_json::from_str::<TzIf>(&json).unwrap(), zone_info.tzif());
+        assert_eq!(
+            serde_json::to_string(&zone_info.offset_at(0)).unwrap(),
+            r#"{"seconds":28800,"is_dst":false,"abbreviation":"CST"}"#
+        );
+    }
+
     #[cfg(target_env = "ohos")]
     #[test]
     fn test_ohos_machine_tz_data_loading() {
@@ -0,0 +1,278 @@
+//! Search paths of the `tzdata` file on the device.
+use std::{
+    ffi::OsStr,
+    fs::File,
+    io::{self, Seek},
+    path::{Path, PathBuf},
+};
+
+use crate::{Result, TzDataHeader};
+
+/// `tzdata` of the tzdata and runtime APEX modules of Android.
+pub(crate) const ANDROID_APEX_PATHS: [&str; 2] =
+    ["/apex/com.android.tzdata/etc/tz/tzdata", "/apex/com.android.runtime/etc/tz/tzdata"];
+/// `tzdata` at the legacy locations of Android, under the directories in these variables.
+pub(crate) const ANDROID_ENV_PATHS: [(&str, &str); 2] =
+    [("ANDROID_DATA", "/misc/zoneinfo/tzdata"), ("ANDROID_ROOT", "/usr/share/zoneinfo/tzdata")];
+/// `tzdata` installed by timezone data updates of HarmonyOS NEXT.
+pub(crate) const OHOS_UPDATE_PATH: &str =
+    "/data/service/el1/public/update/param_service/install/system/etc/zoneinfo/tzdata";
+/// `tzdata` shipped with the system image of HarmonyOS NEXT.
+pub(crate) const OHOS_SYSTEM_PATH: &str = "/system/etc/zoneinfo/tzdata";
+
+/// Which copy of the `tzdata` file of HarmonyOS NEXT was used, see
+/// [`find_tz_data_ohos_from_fs_versioned`](crate::find_tz_data_ohos_from_fs_versioned).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum OhosTzDataSource {
+    /// The copy installed by a timezone data update under `/data`.
+    Update,
+    /// The copy shipped with the system image.
+    System,
+}
+
+impl OhosTzDataSource {
+    /// Path of this copy on the device.
+    pub fn path(self) -> &'static Path {
+        Path::new(match self {
+            Self::Update => OHOS_UPDATE_PATH,
+            Self::System => OHOS_SYSTEM_PATH,
+        })
+    }
+}
+
+/// Ordered list of candidate paths of a `tzdata` file; the first one that opens wins.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct TzdataLocator {
+    paths: Vec<PathBuf>,
+}
+
+impl TzdataLocator {
+    /// Locator without any path.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Paths of the `tzdata` file of Android.
+    ///
+    /// The tzdata and runtime APEX modules come first, then the legacy locations under
+    /// `$ANDROID_DATA` and `$ANDROID_ROOT`, resolved when this is called.
+    pub fn android() -> Self {
+        let mut locator: Self = ANDROID_APEX_PATHS.into_iter().collect();
+        locator.extend(android_legacy_paths());
+        locator
+    }
+
+    /// Paths of the `tzdata` file of HarmonyOS NEXT.
+    ///
+    /// The paths listed in `$TZDATA_PATH`, separated like `$PATH`, come first, then the copy
+    /// installed by timezone data updates, then the one shipped with the system image.
+    pub fn ohos() -> Self {
+        Self::ohos_with_override(std::env::var_os("TZDATA_PATH").as_deref())
+    }
+
+    /// Paths of the `tzdata` file of HarmonyOS NEXT, with `tzdata_path` in place of the value
+    /// of `$TZDATA_PATH`.
+    pub(crate) fn ohos_with_override(tzdata_path: Option<&OsStr>) -> Self {
+        let mut locator = Self::new();
+        if let Some(tzdata_path) = tzdata_path {
+            locator
+                .extend(std::env::split_paths(tzdata_path).filter(|x| !x.as_os_str().is_empty()));
+        }
+        locator.extend([OHOS_UPDATE_PATH, OHOS_SYSTEM_PATH]);
+        locator
+    }
+
+    /// Append a path, tried after the existing ones.
+    pub fn push(&mut self, path: impl Into<PathBuf>) -> &mut Self {
+        self.paths.push(path.into());
+        self
+    }
+
+    /// Candidate paths, in search order.
+    pub fn paths(&self) -> &[PathBuf] {
+        &self.paths
+    }
+
+    /// Open the first path that can be opened.
+    ///
+    /// Fails with [`io::ErrorKind::NotFound`] if none can.
+    pub fn open(&self) -> Result<File> {
+        self.open_with_path().map(|(file, _)| file)
+    }
+
+    /// Open the first path that can be opened, also returning that path.
+    pub fn open_with_path(&self) -> Result<(File, &Path)> {
+        let found = self.paths.iter().find_map(|path| match File::open(path) {
+            Ok(file) => Some((file, path.as_path())),
+            Err(_err) => {
+                trace!(trace!(path = %path.display(), error = %_err, "can't open tzdata"));
+                None
+            }
+        });
+        let Some((file, path)) = found else {
+            trace!(warn!(paths = ?self.paths, "no tzdata found"));
+            return Err(io::Error::from(io::ErrorKind::NotFound).into());
+        };
+        trace!(info!(path = %path.display(), "opened tzdata"));
+        Ok((file, path))
+    }
+
+    /// Open the path whose header has the newest version, as Android does when both an updated
+    /// and a system copy exist, also returning that path.
+    ///
+    /// Paths that can't be opened or whose header can't be parsed are skipped; among equal
+    /// versions the earlier path wins. The file is rewound to the start.
+    pub fn open_newest(&self) -> Result<(File, &Path)> {
+        let mut newest: Option<([u8; 5], File, &Path)> = None;
+        for path in &self.paths {
+            let Ok(mut file) = File::open(path) else { continue };
+            let Ok(header) = TzDataHeader::new(&mut file) else { continue };
+            trace!(debug!(
+                path = %path.display(),
+                version = %header.version.escape_ascii(),
+                "found tzdata candidate"
+            ));
+            if newest.as_ref().is_none_or(|(version, ..)| header.version > *version) {
+                newest = Some((header.version, file, path));
+            }
+        }
+        let Some((_version, mut file, path)) = newest else {
+            trace!(warn!(paths = ?self.paths, "no tzdata found"));
+            return Err(io::Error::from(io::ErrorKind::NotFound).into());
+        };
+        trace!(info!(
+            path = %path.display(),
+            version = %_version.escape_ascii(),
+            "opened newest tzdata"
+        ));
+        file.rewind()?;
+        Ok((file, path))
+    }
+}
+
+/// Legacy `tzdata` paths of Android, resolved from the environment.
+pub(crate) fn android_legacy_paths() -> impl Iterator<Item = String> {
+    ANDROID_ENV_PATHS.into_iter().filter_map(|(env_var, path)| {
+        std::env::var(env_var).ok().map(|env_value| format!("{env_value}{path}"))
+    })
+}
+
+/// Open the newer of the update and system copies of the `tzdata` file of HarmonyOS NEXT.
+///
+/// Copies are compared by the version in their header, then by the package version in the
+/// `version.txt` next to them, e.g. `version=1.0.0.2`, as the parameter update service does.
+/// The update copy is only used if it's strictly newer, or if the system one can't be read.
+pub(crate) fn open_ohos_versioned(
+    update: &Path,
+    system: &Path,
+) -> Result<(File, OhosTzDataSource)> {
+    let open = |path: &Path| {
+        let mut file = File::open(path).ok()?;
+        let header = TzDataHeader::new(&mut file).ok()?;
+        file.rewind().ok()?;
+        Some((file, (header.version, package_version(path).unwrap_or_default())))
+    };
+    let chosen = match (open(update), open(system)) {
+        (Some((file, version)), Some((_, system_version))) if version > system_version => {
+            Some((file, OhosTzDataSource::Update))
+        }
+        (_, Some((file, _))) => Some((file, OhosTzDataSource::System)),
+        (Some((file, _)), None) => Some((file, OhosTzDataSource::Update)),
+        (None, None) => None,
+    };
+    let Some((file, source)) = chosen else {
+        trace!(warn!(update = %update.display(), system = %system.display(), "no tzdata found"));
+        return Err(io::Error::from(io::ErrorKind::NotFound).into());
+    };
+    trace!(info!(source = ?source, "opened versioned tzdata"));
+    Ok((file, source))
+}
+
+/// Dotted package version of the `version.txt` next to a `tzdata` file.
+fn package_version(tzdata_path: &Path) -> Option<Vec<u32>> {
+    let text = std::fs::read_to_string(tzdata_path.with_file_name("version.txt")).ok()?;
+    let line = text.lines().find_map(|x| x.trim().strip_prefix("version="))?;
+    line.trim().split('.').map(|x| x.parse().ok()).collect()
+}
+
+impl<P: Into<PathBuf>> FromIterator<P> for TzdataLocator {
+    fn from_iter<T: IntoIterator<Item = P>>(iter: T) -> Self {
+        Self { paths: iter.into_iter().map(Into::into).collect() }
+    }
+}
+
+impl<P: Into<PathBuf>> Extend<P> for TzdataLocator {
+    fn extend<T: IntoIterator<Item = P>>(&mut self, iter: T) {
+        self.paths.extend(iter.into_iter().map(Into::into));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{ZoneInfoError, find_tz_data_android};
+
+    #[test]
+    fn test_locator() {
+        let android = TzdataLocator::android();
+        assert_eq!(android.paths()[0], Path::new("/apex/com.android.tzdata/etc/tz/tzdata"));
+        let ohos = TzdataLocator::ohos_with_override(None);
+        assert_eq!(ohos.paths().last().unwrap(), "/system/etc/zoneinfo/tzdata");
+        assert!(ohos.paths()[0].starts_with("/data/service/el1/public/update"));
+        let ohos = TzdataLocator::ohos_with_override(Some(OsStr::new(
+            "./tests/nowhere/tzdata::./tests/ohos/tzdata",
+        )));
+        assert_eq!(ohos.paths().len(), 4);
+        let (_, path) = ohos.open_with_path().unwrap();
+        assert_eq!(path, Path::new("./tests/ohos/tzdata"));
+
+        let mut locator = TzdataLocator::new();
+        assert!(matches!(
+            locator.open(),
+            Err(ZoneInfoError::Io(err)) if err.kind() == io::ErrorKind::NotFound
+        ));
+        locator.push("./tests/nowhere/tzdata").push("./tests/android/tzdata");
+        let (file, path) = locator.open_with_path().unwrap();
+        assert_eq!(path, Path::new("./tests/android/tzdata"));
+        assert!(find_tz_data_android(file, b"Asia/Shanghai").unwrap().is_some());
+
+        locator.push("./tests/ohos/tzdata").push("./Cargo.toml");
+        let (file, path) = locator.open_newest().unwrap();
+        assert_eq!(path, Path::new("./tests/ohos/tzdata"));
+        assert_eq!(TzDataHeader::new(file).unwrap().version, *b"2024a");
+    }
+
+    #[test]
+    fn test_open_ohos_versioned() {
+        assert_eq!(OhosTzDataSource::System.path(), Path::new("/system/etc/zoneinfo/tzdata"));
+        let dir =
+            std::env::temp_dir().join(format!("zoneinfo-db-versioned-{}", std::process::id()));
+        let (update, system) = (dir.join("update/tzdata"), dir.join("system/tzdata"));
+        std::fs::create_dir_all(update.parent().unwrap()).unwrap();
+        std::fs::create_dir_all(system.parent().unwrap()).unwrap();
+        let source =
+            |update: &Path, system: &Path| open_ohos_versioned(update, system).map(|x| x.1);
+
+        // An update older than the system image is ignored.
+        std::fs::copy("./tests/android/tzdata", &update).unwrap();
+        std::fs::copy("./tests/ohos/tzdata", &system).unwrap();
+        assert_eq!(source(&update, &system).unwrap(), OhosTzDataSource::System);
+        assert_eq!(source(&update, &dir.join("nowhere")).unwrap(), OhosTzDataSource::Update);
+
+        // Equal rules versions are told apart by the package version.
+        std::fs::copy("./tests/ohos/tzdata", &update).unwrap();
+        assert_eq!(source(&update, &system).unwrap(), OhosTzDataSource::System);
+        std::fs::write(update.with_file_name("version.txt"), "version=1.0.0.10\n").unwrap();
+        std::fs::write(system.with_file_name("version.txt"), "version=1.0.0.9\n").unwrap();
+        let (file, source) = open_ohos_versioned(&update, &system).unwrap();
+        assert_eq!(source, OhosTzDataSource::Update);
+        assert_eq!(TzDataHeader::new(file).unwrap().version, *b"2024a");
+
+        let missing = open_ohos_versioned(&dir.join("a"), &dir.join("b"));
+        std::fs::remove_dir_all(&dir).unwrap();
+        assert!(matches!(
+            missing,
+            Err(ZoneInfoError::Io(err)) if err.kind() == io::ErrorKind::NotFound
+        ));
+    }
+}
@@ -0,0 +1,97 @@
+//! Lenient zone name lookup, for names read from configuration files or typed by users.
+#[cfg(feature = "std")]
+use alloc::borrow::Cow;
+use alloc::vec::Vec;
+
+#[cfg(feature = "std")]
+use crate::ZoneInfoDb;
+use crate::{
+    TzDataIndex, TzDataIndexes, fixed::parse_gmt_offset, fixed_offset_tzdata, fixed_tzdata,
+};
+
+/// Result of [`TzDataIndexes::find_timezone_normalized`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum NormalizedZone<'a> {
+    /// An entry of the index, whose name may differ in case from the one looked up.
+    Index(&'a TzDataIndex),
+    /// Synthesized TZif data of a fixed-offset zone not in the index.
+    Fixed(Vec<u8>),
+}
+
+impl TzDataIndexes {
+    /// Find a timezone by name, ignoring ASCII case, and synthesize fixed-offset zones.
+    ///
+    /// Names are tried in this order: an exact match; `Z` as `UTC`; a case-insensitive match;
+    /// an offset like `GMT+08:00`, `UTC-7` or `GMT+0530`, which unlike `Etc/GMT-8` counts
+    /// eastwards; and finally the fixed-offset zones of [`fixed_offset_tzdata`] in any case.
+    pub fn find_timezone_normalized(&self, name: &[u8]) -> Option<NormalizedZone<'_>> {
+        if let Some(index) = self.find_timezone(name) {
+            return Some(NormalizedZone::Index(index));
+        }
+        let name = if name == b"Z" { b"UTC" } else { name };
+        if let Some(index) = self.indexes.iter().find(|x| x.name.eq_ignore_ascii_case(name)) {
+            return Some(NormalizedZone::Index(index));
+        }
+        if let Some(utoff) = parse_gmt_offset(name) {
+            return fixed_tzdata(utoff).map(NormalizedZone::Fixed);
+        }
+        // `Etc/GMT+8`, `UTC`: the area is capitalized, the rest upper case.
+        let canonical = match name.get(..4) {
+            Some(area) if area.eq_ignore_ascii_case(b"Etc/") => {
+                [b"Etc/", &name[4..].to_ascii_uppercase()[..]].concat()
+            }
+            _ => name.to_ascii_uppercase(),
+        };
+        fixed_offset_tzdata(&canonical).map(NormalizedZone::Fixed)
+    }
+}
+
+#[cfg(feature = "std")]
+impl ZoneInfoDb {
+    /// Get the timezone data of a zone, looked up as by
+    /// [`TzDataIndexes::find_timezone_normalized`].
+    pub fn get_normalized(&self, tz_name: &[u8]) -> Option<Cow<'_, [u8]>> {
+        match self.indexes.find_timezone_normalized(tz_name)? {
+            NormalizedZone::Index(index) => self.tzdata(index).map(Cow::Borrowed),
+            NormalizedZone::Fixed(tzif) => Some(Cow::Owned(tzif)),
+        }
+    }
+}
+
+#[cfg(all(test, feature = "std", feature = "tzif"))]
+mod tests {
+    use std::fs::File;
+
+    use super::*;
+    use crate::TzIf;
+
+    #[test]
+    fn test_find_timezone_normalized() {
+        let db = ZoneInfoDb::new_ohos(File::open("./tests/ohos/tzdata").unwrap()).unwrap();
+        let shanghai = db.get(b"Asia/Shanghai").unwrap();
+        for name in [&b"Asia/Shanghai"[..], b"asia/shanghai", b"ASIA/SHANGHAI"] {
+            assert_eq!(db.get_normalized(name).as_deref(), Some(shanghai));
+        }
+        let Some(NormalizedZone::Index(index)) =
+            db.indexes().find_timezone_normalized(b"asia/shanghai")
+        else {
+            panic!("should find the index entry");
+        };
+        assert_eq!(&*index.name, b"Asia/Shanghai");
+
+        // The OpenHarmony database has no UTC or Etc zones.
+        let utoff = |name: &[u8]| {
+            let tzif = TzIf::new(&db.get_normalized(name).unwrap()).unwrap();
+            tzif.local_time_types[0].utoff
+        };
+        assert_eq!(utoff(b"Z"), 0);
+        assert_eq!(utoff(b"utc"), 0);
+        assert_eq!(utoff(b"etc/gmt+8"), -8 * 3600);
+        assert_eq!(utoff(b"GMT+08:00"), 8 * 3600);
+        assert_eq!(utoff(b"UTC-7"), -7 * 3600);
+        assert_eq!(utoff(b"gmt+0530"), 5 * 3600 + 1800);
+        for name in [&b"Asia/Nowhere"[..], b"GMT+8:00", b"UTC+19", b"GMT+08:60", b"z"] {
+            assert_eq!(db.get_normalized(name), None, "{}", String::from_utf8_lossy(name));
+        }
+    }
+}
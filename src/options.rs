@@ -0,0 +1,113 @@
+//! Limits on what parsing an untrusted `tzdata` file may allocate.
+#[cfg(feature = "std")]
+use crate::{Result, TzDataHeader, TzDataIndex, ZoneInfoError};
+
+/// Limits applied while reading a `tzdata` file, so forged offsets and lengths fail with
+/// [`ZoneInfoError::TooLarge`](crate::ZoneInfoError::TooLarge) instead of allocating gigabytes.
+///
+/// The reader-based functions without an `_with_options` suffix use [`ParseOptions::default`],
+/// whose limits are far above what real databases need.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ParseOptions {
+    /// Largest index region, from `index_offset` to `data_offset`, in bytes.
+    pub max_index_bytes: u32,
+    /// Largest TZif data of a single zone, in bytes.
+    pub max_zone_size: u32,
+    /// Largest number of index entries.
+    pub max_zones: usize,
+    /// Fail with [`ZoneInfoError::InvalidZoneName`](crate::ZoneInfoError::InvalidZoneName)
+    /// unless every name is non-empty printable ASCII, so
+    /// [`TzDataIndex::name_arc_str`] can't fail on the parsed entries.
+    pub ascii_names: bool,
+}
+
+impl Default for ParseOptions {
+    fn default() -> Self {
+        Self {
+            max_index_bytes: 1 << 20,
+            max_zone_size: 1 << 20,
+            max_zones: 1 << 14,
+            ascii_names: false,
+        }
+    }
+}
+
+impl ParseOptions {
+    /// Size of the index region, if within the limit.
+    #[cfg(feature = "std")]
+    pub(crate) fn index_len(&self, header: &TzDataHeader) -> Result<usize> {
+        let len = header.data_offset.saturating_sub(header.index_offset);
+        if len > self.max_index_bytes {
+            return Err(ZoneInfoError::TooLarge);
+        }
+        Ok(len as usize)
+    }
+
+    #[cfg(feature = "std")]
+    pub(crate) fn check_zones(&self, zones: usize) -> Result<()> {
+        if zones > self.max_zones {
+            return Err(ZoneInfoError::TooLarge);
+        }
+        Ok(())
+    }
+
+    #[cfg(feature = "std")]
+    pub(crate) fn check_names(&self, indexes: &[TzDataIndex]) -> Result<()> {
+        if self.ascii_names {
+            for index in indexes {
+                index.name_str()?;
+            }
+        }
+        Ok(())
+    }
+
+    #[cfg(feature = "std")]
+    pub(crate) fn check_zone(&self, index: &TzDataIndex) -> Result<()> {
+        if index.length > self.max_zone_size {
+            return Err(ZoneInfoError::TooLarge);
+        }
+        Ok(())
+    }
+}
+
+#[cfg(all(test, feature = "std"))]
+mod tests {
+    use std::{fs::File, io::Cursor};
+
+    use super::*;
+    use crate::{TzDataIndexes, find_tz_data_ohos_with_options};
+
+    #[test]
+    fn test_parse_options() {
+        let data = std::fs::read("./tests/ohos/tzdata").unwrap();
+        let header = TzDataHeader::from_bytes(&data).unwrap();
+        let options = ParseOptions::default();
+        let indexes = TzDataIndexes::new_ohos_with_options(&data[24..], &header, &options).unwrap();
+        assert_eq!(indexes.timezones().len(), 442);
+
+        let small = ParseOptions { max_index_bytes: 1024, ..options };
+        let result = TzDataIndexes::new_ohos_with_options(&data[24..], &header, &small);
+        assert!(matches!(result, Err(ZoneInfoError::TooLarge)));
+        let few = ParseOptions { max_zones: 100, ..options };
+        let result = TzDataIndexes::new_ohos_with_options(&data[24..], &header, &few);
+        assert!(matches!(result, Err(ZoneInfoError::TooLarge)));
+        let tiny = ParseOptions { max_zone_size: 100, ..options };
+        let result = find_tz_data_ohos_with_options(Cursor::new(&data), b"Asia/Shanghai", &tiny);
+        assert!(matches!(result, Err(ZoneInfoError::TooLarge)));
+        let ascii = ParseOptions { ascii_names: true, ..options };
+        let indexes = TzDataIndexes::new_ohos_with_options(&data[24..], &header, &ascii).unwrap();
+        assert!(indexes.timezones().iter().all(|x| x.name_arc_str().is_ok()));
+        let mut invalid = data.clone();
+        invalid[header.index_offset as usize] = 0xff;
+        let result = TzDataIndexes::new_ohos_with_options(&invalid[24..], &header, &ascii);
+        assert!(matches!(result, Err(ZoneInfoError::InvalidZoneName)));
+
+        // A forged index region of almost 4 GiB is refused before allocating.
+        let mut forged = data.clone();
+        forged[16..20].copy_from_slice(&u32::MAX.to_be_bytes());
+        forged[20..24].copy_from_slice(&u32::MAX.to_be_bytes());
+        let header = TzDataHeader::from_bytes(&forged).unwrap();
+        let result = TzDataIndexes::new_ohos(File::open("./tests/ohos/tzdata").unwrap(), &header);
+        assert!(matches!(result, Err(ZoneInfoError::TooLarge)));
+    }
+}
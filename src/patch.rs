@@ -0,0 +1,300 @@
+//! Zone-level deltas between two `tzdata` files, for shipping updates smaller than the file.
+//!
+//! Layout, all integers big-endian:
+//!
+//! ```text
+//! magic "ZIDBPATCH\0" | format version u32 | old SHA-256 | new SHA-256 | op count u32 | ops
+//! op = 0 u8 | offset u32 | length u32    copy bytes of the old file
+//!    | 1 u8 | length u32 | bytes          insert bytes
+//! ```
+//!
+//! Index entries, TZif data and the zonetab of the new file that are byte-identical to ones in
+//! the old file are copied from it, so a patch holds the changed zones, the rewritten index
+//! entries and the header.
+use std::{collections::HashMap, ops::Range};
+
+use crate::{
+    MAX_UNTRUSTED_LEN, Result, TzDataFormatVersion, TzDataHeader, TzDataIndexes, ZoneInfoDb,
+    ZoneInfoError, entry_range, fingerprint::sha256, slice_at,
+};
+
+const PATCH_MAGIC: &[u8; 10] = b"ZIDBPATCH\0";
+/// Bumped whenever the layout changes; patches of other versions are rejected.
+const PATCH_FORMAT_VERSION: u32 = 1;
+/// Size of the smallest op, an empty insert.
+const MIN_OP_LEN: usize = 5;
+
+/// A delta turning one `tzdata` file into another, see [`TzDataPatch::create`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TzDataPatch {
+    /// SHA-256 of the file the patch applies to.
+    old_fingerprint: [u8; 32],
+    /// SHA-256 of the file the patch produces.
+    new_fingerprint: [u8; 32],
+    ops: Vec<PatchOp>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum PatchOp {
+    Copy { offset: u32, length: u32 },
+    Insert(Vec<u8>),
+}
+
+impl TzDataPatch {
+    /// Compute the delta from `old` to `new`.
+    pub fn create(old: &ZoneInfoDb, new: &ZoneInfoDb) -> Self {
+        let (old_data, new_data) = (&old.data[..], &new.data[..]);
+        let old_len = old_data.len() as u64;
+        let new_len = new_data.len() as u64;
+
+        // Ranges of the new file found in the old one, with their offset there.
+        let mut copies: Vec<(Range<u64>, u64)> = Vec::new();
+        let old_blobs: HashMap<_, _> = zone_ranges(old)
+            .filter_map(|range| Some((slice_at(old_data, range.clone())?, range.start)))
+            .collect();
+        copies.extend(zone_ranges(new).filter_map(|range| {
+            let &start = old_blobs.get(slice_at(new_data, range.clone())?)?;
+            Some((range, start))
+        }));
+        if let (Ok((_, old_flavor)), Ok((_, new_flavor))) = (
+            TzDataIndexes::from_bytes_auto(old_data, &old.header),
+            TzDataIndexes::from_bytes_auto(new_data, &new.header),
+        ) && old_flavor == new_flavor
+        {
+            let size = TzDataFormatVersion::from(new_flavor).entry_size() as u64;
+            let entries = |header: &TzDataHeader| {
+                let range = header.index_range();
+                (range.start..range.end.saturating_sub(size - 1))
+                    .step_by(size as usize)
+                    .map(move |start| start..start + size)
+            };
+            let old_entries: HashMap<_, _> = entries(&old.header)
+                .filter_map(|range| Some((slice_at(old_data, range.clone())?, range.start)))
+                .collect();
+            copies.extend(entries(&new.header).filter_map(|range| {
+                let &start = old_entries.get(slice_at(new_data, range.clone())?)?;
+                Some((range, start))
+            }));
+        }
+        let (old_zonetab, new_zonetab) =
+            (old.header.zonetab_range(old_len), new.header.zonetab_range(new_len));
+        if !new_zonetab.is_empty()
+            && slice_at(old_data, old_zonetab.clone()) == slice_at(new_data, new_zonetab.clone())
+        {
+            copies.push((new_zonetab, old_zonetab.start));
+        }
+        copies.sort_by_key(|(range, _)| range.start);
+
+        let mut ops = Vec::new();
+        let mut cursor = 0;
+        for (range, start) in copies {
+            // Ranges of zones sharing their data overlap.
+            let (Ok(offset), Ok(length)) =
+                (u32::try_from(start), u32::try_from(range.end - range.start))
+            else {
+                continue;
+            };
+            if range.start < cursor {
+                continue;
+            }
+            push_insert(&mut ops, &new_data[cursor as usize..range.start as usize]);
+            push_copy(&mut ops, offset, length);
+            cursor = range.end;
+        }
+        push_insert(&mut ops, &new_data[cursor as usize..]);
+        Self { old_fingerprint: old.fingerprint(), new_fingerprint: new.fingerprint(), ops }
+    }
+
+    /// Apply a patch to the file it was created from, returning the bytes of the new file.
+    ///
+    /// Fails with [`ZoneInfoError::InvalidPatch`] if `old` isn't the file the patch was created
+    /// from, or if the result doesn't have the fingerprint of the new file, and with
+    /// [`ZoneInfoError::TooLarge`] as soon as the result exceeds [`MAX_UNTRUSTED_LEN`].
+    pub fn apply(old: &ZoneInfoDb, patch: &TzDataPatch) -> Result<Vec<u8>> {
+        if old.fingerprint() != patch.old_fingerprint {
+            return Err(ZoneInfoError::InvalidPatch("patch is for another tzdata file"));
+        }
+        let mut out = Vec::with_capacity(old.data.len());
+        for op in &patch.ops {
+            let bytes = match op {
+                PatchOp::Copy { offset, length } => {
+                    let start = u64::from(*offset);
+                    slice_at(&old.data, start..start + u64::from(*length))
+                        .ok_or(ZoneInfoError::InvalidPatch("copy past the end of the file"))?
+                }
+                PatchOp::Insert(bytes) => bytes,
+            };
+            if out.len() + bytes.len() > MAX_UNTRUSTED_LEN {
+                return Err(ZoneInfoError::TooLarge);
+            }
+            out.extend_from_slice(bytes);
+        }
+        if sha256(&out) != patch.new_fingerprint {
+            return Err(ZoneInfoError::InvalidPatch("patched tzdata fingerprint mismatch"));
+        }
+        Ok(out)
+    }
+
+    /// Serialize the patch.
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut out = Vec::new();
+        out.extend_from_slice(PATCH_MAGIC);
+        out.extend_from_slice(&PATCH_FORMAT_VERSION.to_be_bytes());
+        out.extend_from_slice(&self.old_fingerprint);
+        out.extend_from_slice(&self.new_fingerprint);
+        out.extend_from_slice(&(self.ops.len() as u32).to_be_bytes());
+        for op in &self.ops {
+            match op {
+                PatchOp::Copy { offset, length } => {
+                    out.push(0);
+                    out.extend_from_slice(&offset.to_be_bytes());
+                    out.extend_from_slice(&length.to_be_bytes());
+                }
+                PatchOp::Insert(bytes) => {
+                    out.push(1);
+                    out.extend_from_slice(&(bytes.len() as u32).to_be_bytes());
+                    out.extend_from_slice(bytes);
+                }
+            }
+        }
+        out
+    }
+
+    /// Parse a patch written by [`TzDataPatch::to_bytes`].
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self> {
+        let mut cursor = Cursor(bytes);
+        if &cursor.array()? != PATCH_MAGIC {
+            return Err(ZoneInfoError::InvalidPatch("invalid magic"));
+        }
+        if cursor.u32()? != PATCH_FORMAT_VERSION {
+            return Err(ZoneInfoError::InvalidPatch("unsupported format version"));
+        }
+        let old_fingerprint = cursor.array()?;
+        let new_fingerprint = cursor.array()?;
+        let count = cursor.u32()? as usize;
+        if count > cursor.0.len() / MIN_OP_LEN {
+            return Err(ZoneInfoError::InvalidPatch("truncated patch"));
+        }
+        let mut ops = Vec::with_capacity(count);
+        for _ in 0..count {
+            let op = match cursor.array()? {
+                [0] => PatchOp::Copy { offset: cursor.u32()?, length: cursor.u32()? },
+                [1] => {
+                    let length = cursor.u32()?;
+                    PatchOp::Insert(cursor.take(length as usize)?.to_vec())
+                }
+                _ => return Err(ZoneInfoError::InvalidPatch("unknown op")),
+            };
+            ops.push(op);
+        }
+        if !cursor.0.is_empty() {
+            return Err(ZoneInfoError::InvalidPatch("trailing bytes"));
+        }
+        Ok(Self { old_fingerprint, new_fingerprint, ops })
+    }
+}
+
+struct Cursor<'a>(&'a [u8]);
+
+impl<'a> Cursor<'a> {
+    fn take(&mut self, len: usize) -> Result<&'a [u8]> {
+        let (head, tail) =
+            self.0.split_at_checked(len).ok_or(ZoneInfoError::InvalidPatch("truncated patch"))?;
+        self.0 = tail;
+        Ok(head)
+    }
+
+    fn array<const N: usize>(&mut self) -> Result<[u8; N]> {
+        let (head, tail) =
+            self.0.split_first_chunk().ok_or(ZoneInfoError::InvalidPatch("truncated patch"))?;
+        self.0 = tail;
+        Ok(*head)
+    }
+
+    fn u32(&mut self) -> Result<u32> {
+        self.array().map(u32::from_be_bytes)
+    }
+}
+
+/// Ranges of the TZif data of the zones of a database, in index order.
+fn zone_ranges(db: &ZoneInfoDb) -> impl Iterator<Item = Range<u64>> + '_ {
+    db.indexes.timezones().iter().map(|index| entry_range(&db.header, index))
+}
+
+fn push_insert(ops: &mut Vec<PatchOp>, bytes: &[u8]) {
+    if bytes.is_empty() {
+        return;
+    }
+    match ops.last_mut() {
+        Some(PatchOp::Insert(last)) => last.extend_from_slice(bytes),
+        _ => ops.push(PatchOp::Insert(bytes.to_vec())),
+    }
+}
+
+fn push_copy(ops: &mut Vec<PatchOp>, offset: u32, length: u32) {
+    if let Some(PatchOp::Copy { offset: last, length: last_length }) = ops.last_mut()
+        && *last + *last_length == offset
+    {
+        *last_length += length;
+        return;
+    }
+    ops.push(PatchOp::Copy { offset, length });
+}
+
+#[cfg(all(test, feature = "builder"))]
+mod tests {
+    use std::fs::File;
+
+    use super::*;
+    use crate::TzDataFlavor;
+    use crate::testutil::fixture_builder;
+
+    #[test]
+    fn test_patch_roundtrip() {
+        let old = ZoneInfoDb::new_android(File::open("./tests/android/tzdata").unwrap()).unwrap();
+        let mut builder = fixture_builder(&old, *b"2021b");
+        builder.filter(|name| name != b"Africa/Abidjan");
+        builder.zone("Asia/Nowhere", old.get(b"Asia/Tokyo").unwrap());
+        let mut shanghai = old.get(b"Asia/Shanghai").unwrap().to_vec();
+        shanghai.push(b'\n');
+        builder.zone("Asia/Shanghai", shanghai);
+        let new = ZoneInfoDb::from_bytes(builder.build(TzDataFlavor::Android).unwrap()).unwrap();
+
+        let patch = TzDataPatch::create(&old, &new);
+        let bytes = patch.to_bytes();
+        assert!(bytes.len() < new.data_len() / 10, "{} bytes", bytes.len());
+        assert_eq!(TzDataPatch::from_bytes(&bytes).unwrap(), patch);
+        let patched = TzDataPatch::apply(&old, &patch).unwrap();
+        assert_eq!(sha256(&patched), new.fingerprint());
+
+        // The patch only applies to the file it was created from.
+        let err = TzDataPatch::apply(&new, &patch).unwrap_err();
+        assert!(matches!(err, ZoneInfoError::InvalidPatch(_)));
+        let mut corrupted = patch.clone();
+        corrupted.ops.push(PatchOp::Insert(b"x".to_vec()));
+        let err = TzDataPatch::apply(&old, &corrupted).unwrap_err();
+        assert!(matches!(err, ZoneInfoError::InvalidPatch(_)));
+        assert!(TzDataPatch::from_bytes(&bytes[..bytes.len() - 1]).is_err());
+        assert!(TzDataPatch::from_bytes(&[bytes.as_slice(), b"x"].concat()).is_err());
+    }
+
+    #[test]
+    fn test_patch_limits() {
+        let old = ZoneInfoDb::new_android(File::open("./tests/android/tzdata").unwrap()).unwrap();
+        // Copies of the whole file expanding past the limit are stopped before the hash check.
+        let whole = PatchOp::Copy { offset: 0, length: old.data.len() as u32 };
+        let patch = TzDataPatch {
+            old_fingerprint: old.fingerprint(),
+            new_fingerprint: [0; 32],
+            ops: vec![whole; MAX_UNTRUSTED_LEN / old.data.len() + 1],
+        };
+        assert!(matches!(TzDataPatch::apply(&old, &patch), Err(ZoneInfoError::TooLarge)));
+
+        // An op count the remaining bytes can't hold is rejected up front.
+        let mut bytes = TzDataPatch { ops: Vec::new(), ..patch }.to_bytes();
+        let count = bytes.len() - 4;
+        bytes[count..].copy_from_slice(&u32::MAX.to_be_bytes());
+        let err = TzDataPatch::from_bytes(&bytes).unwrap_err();
+        assert!(matches!(err, ZoneInfoError::InvalidPatch("truncated patch")));
+    }
+}
@@ -0,0 +1,106 @@
+//! Data for time zone pickers: the zones of each country with their local time at an instant.
+use std::collections::BTreeMap;
+
+use alloc::{string::String, sync::Arc, vec::Vec};
+
+use crate::{Result, ZoneInfo, ZoneInfoDb, exemplar_city};
+
+/// The zones of a country in [`ZoneInfoDb::picker_data`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct PickerCountry {
+    /// ISO 3166 alpha-2 code as in the zonetab, e.g. `US`.
+    pub country: String,
+    /// Zones in zonetab order.
+    pub zones: Vec<PickerZone>,
+}
+
+/// A zone of a [`PickerCountry`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct PickerZone {
+    pub zone: Arc<[u8]>,
+    /// Offset from UTC in seconds at the requested instant, east positive.
+    pub offset: i32,
+    /// Whether daylight saving time is observed at or after the requested instant, see
+    /// [`ZoneInfo::uses_dst`].
+    pub observes_dst: bool,
+    /// See [`exemplar_city`].
+    pub exemplar_city: Option<String>,
+    /// The zonetab comment, e.g. `Pacific`.
+    pub comment: Option<String>,
+}
+
+impl ZoneInfoDb {
+    /// The zones of each country in the zonetab, sorted by country code, with their offset and
+    /// DST use at a Unix timestamp.
+    ///
+    /// Zonetab rows of zones missing from the database are skipped, as are countries left
+    /// without zones.
+    pub fn picker_data(&self, unix_seconds: i64) -> Result<Vec<PickerCountry>> {
+        let mut countries: BTreeMap<&str, Vec<PickerZone>> = BTreeMap::new();
+        for entry in self.zone_tab()?.entries() {
+            let Some(tzif) = self.get(&entry.zone) else {
+                continue;
+            };
+            let zone_info = ZoneInfo::new(tzif)?;
+            let zone = PickerZone {
+                zone: entry.zone.clone(),
+                offset: zone_info.offset_at(unix_seconds).seconds,
+                observes_dst: zone_info.uses_dst(unix_seconds),
+                exemplar_city: core::str::from_utf8(&entry.zone)
+                    .ok()
+                    .and_then(exemplar_city)
+                    .map(String::from),
+                comment: entry.comment.clone(),
+            };
+            for country in &entry.countries {
+                countries.entry(country).or_default().push(zone.clone());
+            }
+        }
+        Ok(countries
+            .into_iter()
+            .map(|(country, zones)| PickerCountry { country: country.into(), zones })
+            .collect())
+    }
+}
+
+#[cfg(all(test, feature = "builder"))]
+mod tests {
+    use super::*;
+    use crate::testutil::android_fixture_with_zone_tab;
+
+    #[test]
+    fn test_picker_data() {
+        let db = android_fixture_with_zone_tab(
+            "US\t+404251-0740023\tAmerica/New_York\tEastern (most areas)\n\
+             CN\t+3114+12128\tAsia/Shanghai\tBeijing Time\n\
+             US\t+332654-1120424\tAmerica/Phoenix\tMST - AZ (most areas)\n\
+             XX\t+0000+00000\tMars/Olympus_Mons\n",
+        );
+
+        // 2024-07-01.
+        let countries = db.picker_data(1719792000).unwrap();
+        let codes: Vec<_> = countries.iter().map(|x| &*x.country).collect();
+        assert_eq!(codes, ["CN", "US"]);
+        let us = &countries[1].zones;
+        assert_eq!(
+            us[0],
+            PickerZone {
+                zone: Arc::from(&b"America/New_York"[..]),
+                offset: -4 * 3600,
+                observes_dst: true,
+                exemplar_city: Some("New York".into()),
+                comment: Some("Eastern (most areas)".into()),
+            }
+        );
+        assert_eq!(
+            (&*us[1].zone, us[1].offset, us[1].observes_dst),
+            (&b"America/Phoenix"[..], -7 * 3600, false)
+        );
+        assert_eq!(
+            (countries[0].zones[0].offset, countries[0].zones[0].observes_dst),
+            (8 * 3600, false)
+        );
+    }
+}
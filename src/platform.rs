@@ -0,0 +1,71 @@
+//! Detection of the platform from the well-known `tzdata` paths present.
+use std::path::{Path, PathBuf};
+
+use crate::{
+    TzDataFlavor, ZoneInfoDb,
+    locator::{ANDROID_APEX_PATHS, OHOS_SYSTEM_PATH, OHOS_UPDATE_PATH, android_legacy_paths},
+};
+
+/// Where the system `tzdata` file was found, see [`ZoneInfoDb::detect_platform`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Platform {
+    /// Android 10 and later, in the tzdata or runtime APEX module.
+    AndroidApex,
+    /// Older Android, under `$ANDROID_DATA` or `$ANDROID_ROOT`.
+    AndroidLegacy,
+    /// HarmonyOS NEXT, with a timezone data update installed.
+    OhosUpdate,
+    /// HarmonyOS NEXT, with the copy of the system image.
+    OhosSystem,
+}
+
+impl Platform {
+    /// Flavor of the `tzdata` file of the platform.
+    pub fn flavor(self) -> TzDataFlavor {
+        match self {
+            Self::AndroidApex | Self::AndroidLegacy => TzDataFlavor::Android,
+            Self::OhosUpdate | Self::OhosSystem => TzDataFlavor::Ohos,
+        }
+    }
+}
+
+impl ZoneInfoDb {
+    /// Detect the platform from the first well-known `tzdata` path that is a file: the Android
+    /// APEX modules, the legacy Android locations, then the update and system copies of
+    /// HarmonyOS NEXT. Returns `None` if there's none, e.g. on desktop systems.
+    pub fn detect_platform() -> Option<(Platform, PathBuf)> {
+        let apex = ANDROID_APEX_PATHS.into_iter().map(|x| (Platform::AndroidApex, x.into()));
+        let legacy = android_legacy_paths().map(|x| (Platform::AndroidLegacy, x.into()));
+        let ohos =
+            [(Platform::OhosUpdate, OHOS_UPDATE_PATH), (Platform::OhosSystem, OHOS_SYSTEM_PATH)];
+        let ohos = ohos.into_iter().map(|(platform, x)| (platform, x.into()));
+        detect(apex.chain(legacy).chain(ohos))
+    }
+}
+
+/// The first candidate whose path is a file.
+fn detect(
+    candidates: impl IntoIterator<Item = (Platform, PathBuf)>,
+) -> Option<(Platform, PathBuf)> {
+    candidates.into_iter().find(|(_, path)| Path::is_file(path))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_detect_platform() {
+        let candidates = [
+            (Platform::AndroidApex, "./tests/nowhere/tzdata"),
+            (Platform::OhosUpdate, "./tests/ohos"),
+            (Platform::OhosSystem, "./tests/ohos/tzdata"),
+            (Platform::AndroidLegacy, "./tests/android/tzdata"),
+        ];
+        let (platform, path) = detect(candidates.map(|(x, path)| (x, path.into()))).unwrap();
+        assert_eq!(platform, Platform::OhosSystem);
+        assert_eq!(path, Path::new("./tests/ohos/tzdata"));
+        assert_eq!(platform.flavor(), TzDataFlavor::Ohos);
+        assert_eq!(detect([(Platform::AndroidApex, "./tests/nowhere".into())]), None);
+    }
+}
@@ -0,0 +1,357 @@
+//! Parser and evaluator of POSIX TZ strings, e.g. `EST5EDT,M3.2.0,M11.1.0`, as found in the footer
+//! of TZif data, with the RFC 8536 extensions of hours up to 167 and negative rule times.
+use alloc::string::String;
+
+/// Seconds per day.
+const SECS_PER_DAY: i64 = 86400;
+
+/// A local time type named by a POSIX TZ string.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct PosixType {
+    pub(crate) abbreviation: String,
+    /// Offset from UTC in seconds, east positive, unlike the TZ string itself.
+    pub(crate) utoff: i32,
+}
+
+/// A decoded POSIX TZ string.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct PosixTz {
+    pub(crate) std: PosixType,
+    pub(crate) dst: Option<(PosixType, DstRules)>,
+}
+
+/// When daylight saving time starts and ends each year.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) struct DstRules {
+    /// Start, in local standard time.
+    pub(crate) start: Rule,
+    /// End, in local daylight saving time.
+    pub(crate) end: Rule,
+}
+
+/// A yearly transition date and the local time of day it happens at.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) struct Rule {
+    pub(crate) date: RuleDate,
+    /// Seconds after local midnight, possibly negative or past a day.
+    pub(crate) time: i32,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum RuleDate {
+    /// `Jn`: day 1 to 365, February 29 is never counted.
+    Julian1(u16),
+    /// `n`: day 0 to 365, February 29 is counted in leap years.
+    Julian0(u16),
+    /// `Mm.w.d`: day `d` (0 is Sunday) of week `w` (5 is the last) of month `m`.
+    MonthWeekDay { month: u8, week: u8, weekday: u8 },
+}
+
+impl PosixTz {
+    /// Parse a POSIX TZ string, or `None` if it's malformed.
+    ///
+    /// A daylight saving time without rules follows the US ones, `M3.2.0,M11.1.0`.
+    pub(crate) fn new(text: &str) -> Option<Self> {
+        let mut parser = Parser(text.as_bytes());
+        let std = PosixType { abbreviation: parser.abbreviation()?, utoff: -parser.time(24)? };
+        if parser.0.is_empty() {
+            return Some(Self { std, dst: None });
+        }
+
+        let abbreviation = parser.abbreviation()?;
+        let utoff = match parser.0.first() {
+            None | Some(b',') => std.utoff + 3600,
+            Some(_) => -parser.time(24)?,
+        };
+        let rules = if parser.0.is_empty() {
+            DstRules {
+                start: Rule {
+                    date: RuleDate::MonthWeekDay { month: 3, week: 2, weekday: 0 },
+                    time: 7200,
+                },
+                end: Rule {
+                    date: RuleDate::MonthWeekDay { month: 11, week: 1, weekday: 0 },
+                    time: 7200,
+                },
+            }
+        } else {
+            parser.expect(b',')?;
+            let start = parser.rule()?;
+            parser.expect(b',')?;
+            let end = parser.rule()?;
+            DstRules { start, end }
+        };
+        parser
+            .0
+            .is_empty()
+            .then_some(Self { std, dst: Some((PosixType { abbreviation, utoff }, rules)) })
+    }
+
+    /// Local time type in effect at a Unix timestamp, and whether it's daylight saving time.
+    pub(crate) fn local_type_at(&self, time: i64) -> (&PosixType, bool) {
+        let (Some((dst, _)), Some([start, end])) =
+            (&self.dst, self.transitions_in(self.year_at(time)))
+        else {
+            return (&self.std, false);
+        };
+        let time = i128::from(time);
+        let is_dst = if start <= end {
+            start <= time && time < end
+        } else {
+            // Southern hemisphere: daylight saving time spans the new year.
+            !(end <= time && time < start)
+        };
+        if is_dst { (dst, true) } else { (&self.std, false) }
+    }
+}
+
+impl PosixTz {
+    /// Year of a Unix timestamp in local standard time.
+    pub(crate) fn year_at(&self, time: i64) -> i64 {
+        year_of_days(time.saturating_add(self.std.utoff.into()).div_euclid(SECS_PER_DAY))
+    }
+
+    /// Unix timestamps of the start and end of daylight saving time in `year`, if there is any.
+    pub(crate) fn transitions_in(&self, year: i64) -> Option<[i128; 2]> {
+        let (dst, rules) = self.dst.as_ref()?;
+        Some([
+            rules.start.local_time(year) - i128::from(self.std.utoff),
+            rules.end.local_time(year) - i128::from(dst.utoff),
+        ])
+    }
+}
+
+impl Rule {
+    /// Local time of the transition in `year`, in seconds since the epoch.
+    fn local_time(&self, year: i64) -> i128 {
+        let day = match self.date {
+            RuleDate::Julian1(day) => {
+                let leap_day = is_leap(year) && day >= 60;
+                days_from_civil(year, 1, 1) + i64::from(day) - 1 + i64::from(leap_day)
+            }
+            RuleDate::Julian0(day) => days_from_civil(year, 1, 1) + i64::from(day),
+            RuleDate::MonthWeekDay { month, week, weekday } => {
+                let first = days_from_civil(year, month, 1);
+                let first_weekday = (first + 4).rem_euclid(7);
+                let mut day = first
+                    + (i64::from(weekday) - first_weekday).rem_euclid(7)
+                    + 7 * (i64::from(week) - 1);
+                while day >= first + i64::from(days_in_month(year, month)) {
+                    day -= 7;
+                }
+                day
+            }
+        };
+        i128::from(day) * i128::from(SECS_PER_DAY) + i128::from(self.time)
+    }
+}
+
+/// Days since the epoch of a proleptic Gregorian date.
+fn days_from_civil(year: i64, month: u8, day: u8) -> i64 {
+    let year = if month <= 2 { year - 1 } else { year };
+    let era = year.div_euclid(400);
+    let year_of_era = year.rem_euclid(400);
+    let day_of_year = (153 * ((i64::from(month) + 9) % 12) + 2) / 5 + i64::from(day) - 1;
+    let day_of_era = year_of_era * 365 + year_of_era / 4 - year_of_era / 100 + day_of_year;
+    era * 146097 + day_of_era - 719468
+}
+
+/// Proleptic Gregorian year of a day since the epoch.
+fn year_of_days(days: i64) -> i64 {
+    civil_from_days(days).0
+}
+
+/// Proleptic Gregorian year, month and day of a day since the epoch.
+pub(crate) fn civil_from_days(days: i64) -> (i64, u8, u8) {
+    let days = days + 719468;
+    let era = days.div_euclid(146097);
+    let day_of_era = days.rem_euclid(146097);
+    let year_of_era =
+        (day_of_era - day_of_era / 1460 + day_of_era / 36524 - day_of_era / 146096) / 365;
+    let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+    // Months of this form start in March.
+    let month = (5 * day_of_year + 2) / 153;
+    let day = day_of_year - (153 * month + 2) / 5 + 1;
+    let month = if month < 10 { month + 3 } else { month - 9 };
+    (year_of_era + era * 400 + i64::from(month <= 2), month as u8, day as u8)
+}
+
+fn is_leap(year: i64) -> bool {
+    year % 4 == 0 && (year % 100 != 0 || year % 400 == 0)
+}
+
+fn days_in_month(year: i64, month: u8) -> u8 {
+    match month {
+        2 if is_leap(year) => 29,
+        2 => 28,
+        4 | 6 | 9 | 11 => 30,
+        _ => 31,
+    }
+}
+
+/// Forward-only parser over the bytes of a TZ string.
+struct Parser<'a>(&'a [u8]);
+
+impl<'a> Parser<'a> {
+    fn expect(&mut self, byte: u8) -> Option<()> {
+        self.0 = self.0.strip_prefix(&[byte])?;
+        Some(())
+    }
+
+    /// Take the longest prefix matching `pred`.
+    fn take_while(&mut self, pred: impl Fn(u8) -> bool) -> &'a [u8] {
+        let len = self.0.iter().position(|&x| !pred(x)).unwrap_or(self.0.len());
+        let (head, tail) = self.0.split_at(len);
+        self.0 = tail;
+        head
+    }
+
+    /// `std` or `dst`: 3 or more letters, or `<...>` quoting letters, digits and signs.
+    fn abbreviation(&mut self) -> Option<String> {
+        let abbreviation = if self.expect(b'<').is_some() {
+            let abbreviation =
+                self.take_while(|x| x.is_ascii_alphanumeric() || x == b'+' || x == b'-');
+            self.expect(b'>')?;
+            abbreviation
+        } else {
+            self.take_while(|x| x.is_ascii_alphabetic())
+        };
+        (abbreviation.len() >= 3).then(|| String::from_utf8_lossy(abbreviation).into_owned())
+    }
+
+    /// Unsigned decimal number of up to 3 digits.
+    fn number(&mut self) -> Option<u16> {
+        let digits = self.take_while(|x| x.is_ascii_digit());
+        if digits.is_empty() || digits.len() > 3 {
+            return None;
+        }
+        Some(digits.iter().fold(0, |acc, &x| acc * 10 + u16::from(x - b'0')))
+    }
+
+    /// `[+|-]hh[:mm[:ss]]` in seconds, with hours up to `max_hours`.
+    fn time(&mut self, max_hours: u16) -> Option<i32> {
+        let sign = match self.0.first() {
+            Some(b'-') => -1,
+            Some(b'+') => 1,
+            _ => 0,
+        };
+        if sign != 0 {
+            self.0 = &self.0[1..];
+        }
+        let hours = self.number().filter(|&x| x <= max_hours)?;
+        let mut seconds = i32::from(hours) * 3600;
+        for unit in [60, 1] {
+            if self.expect(b':').is_none() {
+                break;
+            }
+            seconds += i32::from(self.number().filter(|&x| x <= 59)?) * unit;
+        }
+        Some(if sign < 0 { -seconds } else { seconds })
+    }
+
+    /// `date[/time]`, the time defaulting to 02:00.
+    fn rule(&mut self) -> Option<Rule> {
+        let date = if self.expect(b'J').is_some() {
+            RuleDate::Julian1(self.number().filter(|x| (1..=365).contains(x))?)
+        } else if self.expect(b'M').is_some() {
+            let month = self.number().filter(|x| (1..=12).contains(x))?;
+            self.expect(b'.')?;
+            let week = self.number().filter(|x| (1..=5).contains(x))?;
+            self.expect(b'.')?;
+            let weekday = self.number().filter(|&x| x <= 6)?;
+            RuleDate::MonthWeekDay { month: month as u8, week: week as u8, weekday: weekday as u8 }
+        } else {
+            RuleDate::Julian0(self.number().filter(|&x| x <= 365)?)
+        };
+        let time = match self.expect(b'/') {
+            Some(()) => self.time(167)?,
+            None => 7200,
+        };
+        Some(Rule { date, time })
+    }
+}
+
+#[cfg(all(test, feature = "std"))]
+mod tests {
+    use super::*;
+
+    fn offset_at(tz: &str, time: i64) -> (i32, bool, String) {
+        let tz = PosixTz::new(tz).unwrap();
+        let (local_type, is_dst) = tz.local_type_at(time);
+        (local_type.utoff, is_dst, local_type.abbreviation.clone())
+    }
+
+    #[test]
+    fn test_posix_tz_parse() {
+        assert_eq!(
+            PosixTz::new("CST-8").unwrap(),
+            PosixTz { std: PosixType { abbreviation: "CST".into(), utoff: 8 * 3600 }, dst: None }
+        );
+        let tz = PosixTz::new("<+0330>-3:30").unwrap();
+        assert_eq!(tz.std, PosixType { abbreviation: "+0330".into(), utoff: 3 * 3600 + 1800 });
+
+        let tz = PosixTz::new("<-03>3<-02>,M3.5.0/-2,M10.5.0/-1").unwrap();
+        let (dst, rules) = tz.dst.unwrap();
+        assert_eq!(dst.utoff, -2 * 3600);
+        assert_eq!(rules.start.time, -2 * 3600);
+        assert_eq!(rules.end.date, RuleDate::MonthWeekDay { month: 10, week: 5, weekday: 0 });
+
+        // Without rules, daylight saving time follows the US ones.
+        let (_, rules) = PosixTz::new("EST5EDT").unwrap().dst.unwrap();
+        assert_eq!(rules.start.date, RuleDate::MonthWeekDay { month: 3, week: 2, weekday: 0 });
+
+        for malformed in [
+            "",
+            "CS-8",
+            "CST",
+            "CST-8X",
+            "<CST-8",
+            "EST5EDT,M3.2.0",
+            "EST5EDT,M13.1.0,M11.1.0",
+            "EST5EDT,J0,J365",
+            "EST25",
+        ] {
+            assert_eq!(PosixTz::new(malformed), None, "{malformed}");
+        }
+    }
+
+    #[test]
+    fn test_posix_tz_local_type_at() {
+        const NEW_YORK: &str = "EST5EDT,M3.2.0,M11.1.0";
+        // 2024-03-10 07:00:00 UTC is 02:00 EST.
+        assert_eq!(offset_at(NEW_YORK, 1710053999), (-5 * 3600, false, "EST".into()));
+        assert_eq!(offset_at(NEW_YORK, 1710054000), (-4 * 3600, true, "EDT".into()));
+        // 2024-11-03 06:00:00 UTC is 02:00 EDT.
+        assert_eq!(offset_at(NEW_YORK, 1730613599), (-4 * 3600, true, "EDT".into()));
+        assert_eq!(offset_at(NEW_YORK, 1730613600), (-5 * 3600, false, "EST".into()));
+
+        const SYDNEY: &str = "AEST-10AEDT,M10.1.0,M4.1.0/3";
+        // 2100-01-15 and 2100-07-15.
+        assert_eq!(offset_at(SYDNEY, 4103654400), (11 * 3600, true, "AEDT".into()));
+        assert_eq!(offset_at(SYDNEY, 4119292800), (10 * 3600, false, "AEST".into()));
+
+        // Daylight saving time all year.
+        assert!(offset_at("EST5EDT,0/0,J365/25", 1720000000).1);
+        assert!(offset_at("EST5EDT,0/0,J365/25", 1704067200).1);
+
+        assert_eq!(offset_at("CST-8", i64::MAX).0, 8 * 3600);
+        PosixTz::new(NEW_YORK).unwrap().local_type_at(i64::MIN);
+        PosixTz::new(SYDNEY).unwrap().local_type_at(i64::MAX);
+    }
+
+    #[test]
+    fn test_civil_days() {
+        assert_eq!(days_from_civil(1970, 1, 1), 0);
+        assert_eq!(days_from_civil(2000, 3, 1), 11017);
+        assert_eq!(year_of_days(0), 1970);
+        assert_eq!(year_of_days(-1), 1969);
+        assert_eq!(year_of_days(11016), 2000);
+        for year in [-400, 1600, 1900, 2000, 2024, 2100] {
+            assert_eq!(year_of_days(days_from_civil(year, 1, 1)), year);
+            assert_eq!(year_of_days(days_from_civil(year, 12, 31)), year);
+            for (month, day) in [(1, 1), (2, 28), (3, 1), (12, 31)] {
+                assert_eq!(civil_from_days(days_from_civil(year, month, day)), (year, month, day));
+            }
+        }
+    }
+}
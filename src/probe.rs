@@ -0,0 +1,61 @@
+//! Cheap inspection of a `tzdata` file without parsing its index.
+use std::{
+    fs::File,
+    io::{Read, Seek, SeekFrom},
+    path::Path,
+};
+
+use crate::{Result, SIZEOF_INDEX_ENTRY_ANDROID, SIZEOF_INDEX_ENTRY_OHOS, TzDataHeader};
+
+/// Summary of a `tzdata` file, computed from its header and length only.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TzDataProbe {
+    pub version: [u8; 5],
+    /// Number of index entries, derived from the size of the index section.
+    pub entry_count: usize,
+    pub file_len: u64,
+}
+
+impl TzDataProbe {
+    /// Probe the `tzdata` file reader of Android.
+    pub fn new_android<R: Read + Seek>(reader: R) -> Result<Self> {
+        Self::new::<SIZEOF_INDEX_ENTRY_ANDROID, R>(reader)
+    }
+
+    /// Probe the `tzdata` file reader of HarmonyOS NEXT.
+    pub fn new_ohos<R: Read + Seek>(reader: R) -> Result<Self> {
+        Self::new::<SIZEOF_INDEX_ENTRY_OHOS, R>(reader)
+    }
+
+    fn new<const SIZEOF_INDEX_ENTRY: usize, R: Read + Seek>(mut reader: R) -> Result<Self> {
+        reader.seek(SeekFrom::Start(0))?;
+        let header = TzDataHeader::new(&mut reader)?;
+        let file_len = reader.seek(SeekFrom::End(0))?;
+        let index_len = header.data_offset.saturating_sub(header.index_offset) as usize;
+        Ok(Self { version: header.version, entry_count: index_len / SIZEOF_INDEX_ENTRY, file_len })
+    }
+}
+
+/// Probe the `tzdata` file of Android at `path`.
+pub fn probe_android(path: impl AsRef<Path>) -> Result<TzDataProbe> {
+    TzDataProbe::new_android(File::open(path)?)
+}
+
+/// Probe the `tzdata` file of HarmonyOS NEXT at `path`.
+pub fn probe_ohos(path: impl AsRef<Path>) -> Result<TzDataProbe> {
+    TzDataProbe::new_ohos(File::open(path)?)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_probe() {
+        let probe = probe_android("./tests/android/tzdata").unwrap();
+        assert_eq!(probe, TzDataProbe { version: *b"2021a", entry_count: 593, file_len: 491837 });
+
+        let probe = probe_ohos("./tests/ohos/tzdata").unwrap();
+        assert_eq!(probe, TzDataProbe { version: *b"2024a", entry_count: 442, file_len: 272428 });
+    }
+}
@@ -0,0 +1,72 @@
+//! Reading `tzdata` bundled in the rawfiles of an OpenHarmony app.
+use std::{borrow::Cow, ffi::c_long, io, ptr::NonNull, sync::Mutex};
+
+use ohos_rawfile_sys::{
+    RawFile,
+    raw_file::{
+        OH_ResourceManager_CloseRawFile, OH_ResourceManager_ReadRawFile,
+        OH_ResourceManager_SeekRawFile,
+    },
+};
+
+use crate::{Result, TzDataSource};
+
+/// A [`TzDataSource`] over an opened [`RawFile`], so a `tzdata` file bundled in the HAP can be
+/// read without copying it to disk first. The handle is closed on drop.
+#[derive(Debug)]
+pub struct RawFileSource {
+    raw_file: NonNull<RawFile>,
+    /// The rawfile reading functions aren't thread safe.
+    lock: Mutex<()>,
+}
+
+// SAFETY: the handle is owned, and reads through it are serialized by `lock`.
+unsafe impl Send for RawFileSource {}
+unsafe impl Sync for RawFileSource {}
+
+impl RawFileSource {
+    /// Take ownership of a handle returned by `OH_ResourceManager_OpenRawFile`, or return `None`
+    /// if it's null.
+    ///
+    /// # Safety
+    ///
+    /// `raw_file` must be null or an open handle that isn't used or closed elsewhere afterwards.
+    pub unsafe fn from_raw(raw_file: *mut RawFile) -> Option<Self> {
+        let raw_file = NonNull::new(raw_file)?;
+        Some(Self { raw_file, lock: Mutex::new(()) })
+    }
+}
+
+impl TzDataSource for RawFileSource {
+    fn read_at(&self, offset: u64, len: usize) -> Result<Cow<'_, [u8]>> {
+        let eof = || io::Error::from(io::ErrorKind::UnexpectedEof);
+        let offset = c_long::try_from(offset).map_err(|_| eof())?;
+        let _guard = self.lock.lock().unwrap_or_else(|x| x.into_inner());
+        let raw_file = self.raw_file.as_ptr();
+        // SAFETY: `raw_file` is open while `self` lives, and no other thread uses it.
+        if unsafe { OH_ResourceManager_SeekRawFile(raw_file, offset, 0) } != 0 {
+            return Err(eof().into());
+        }
+        let mut buffer = vec![0u8; len];
+        let mut filled = 0;
+        while filled < len {
+            let rest = &mut buffer[filled..];
+            // SAFETY: as above, and `rest` is valid for writes of its length.
+            let read = unsafe {
+                OH_ResourceManager_ReadRawFile(raw_file, rest.as_mut_ptr().cast(), rest.len())
+            };
+            if read <= 0 {
+                return Err(eof().into());
+            }
+            filled += read as usize;
+        }
+        Ok(Cow::Owned(buffer))
+    }
+}
+
+impl Drop for RawFileSource {
+    fn drop(&mut self) {
+        // SAFETY: the handle is owned and not used after this.
+        unsafe { OH_ResourceManager_CloseRawFile(self.raw_file.as_ptr()) };
+    }
+}
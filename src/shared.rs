@@ -0,0 +1,209 @@
+//! [`ZoneInfoDb`] shared across threads, caching the decoded [`ZoneInfo`] of recently used zones.
+use std::{
+    collections::HashMap,
+    sync::{Arc, Mutex, PoisonError},
+    thread::{self, JoinHandle},
+};
+
+use crate::{Result, ZoneInfo, ZoneInfoDb};
+
+/// A cheaply cloneable handle to a [`ZoneInfoDb`] that caches decoded zones.
+///
+/// At most `capacity` zones are kept; the least recently used one is evicted first. Zones are
+/// decoded outside of the lock, so threads resolving different zones don't wait on each other.
+///
+/// Entries hold only the decoded [`ZoneInfo`]; the TZif data stays in the database and isn't
+/// copied. A decoded zone takes about 2 KiB of heap, so caching all 593 zones of tzdata 2021a
+/// costs about 1.1 MiB, against 450 KiB for their TZif data, as measured by the
+/// `test_cache_memory` test.
+#[derive(Clone)]
+pub struct SharedZoneInfoDb(Arc<Shared>);
+
+struct Shared {
+    db: Arc<ZoneInfoDb>,
+    capacity: usize,
+    cache: Mutex<Lru>,
+}
+
+#[derive(Default)]
+struct Lru {
+    tick: u64,
+    entries: HashMap<Arc<[u8]>, (Arc<ZoneInfo>, u64)>,
+}
+
+impl SharedZoneInfoDb {
+    /// Number of zones cached by [`SharedZoneInfoDb::new`].
+    pub const DEFAULT_CAPACITY: usize = 64;
+
+    /// Share a database, caching up to [`SharedZoneInfoDb::DEFAULT_CAPACITY`] zones.
+    pub fn new(db: impl Into<Arc<ZoneInfoDb>>) -> Self {
+        Self::with_capacity(db, Self::DEFAULT_CAPACITY)
+    }
+
+    /// Share a database, caching up to `capacity` zones; 0 disables caching.
+    pub fn with_capacity(db: impl Into<Arc<ZoneInfoDb>>, capacity: usize) -> Self {
+        Self(Arc::new(Shared { db: db.into(), capacity, cache: Mutex::default() }))
+    }
+
+    /// The underlying database.
+    pub fn db(&self) -> &Arc<ZoneInfoDb> {
+        &self.0.db
+    }
+
+    /// Decode the data of a zone, or `None` if it isn't in the database, reusing the cached
+    /// result of an earlier call.
+    pub fn zone_info(&self, tz_name: &[u8]) -> Result<Option<Arc<ZoneInfo>>> {
+        let Some(index) = self.0.db.indexes().find_timezone(tz_name) else {
+            return Ok(None);
+        };
+        {
+            let mut cache = self.lock();
+            cache.tick += 1;
+            let tick = cache.tick;
+            if let Some((zone, used)) = cache.entries.get_mut(tz_name) {
+                *used = tick;
+                return Ok(Some(zone.clone()));
+            }
+        }
+        let Some(data) = self.0.db.tzdata(index) else {
+            return Ok(None);
+        };
+        let zone = Arc::new(ZoneInfo::new(data)?);
+        if self.0.capacity > 0 {
+            let mut cache = self.lock();
+            if cache.entries.len() >= self.0.capacity
+                && !cache.entries.contains_key(tz_name)
+                && let Some(oldest) =
+                    cache.entries.iter().min_by_key(|(_, (_, used))| *used).map(|(x, _)| x.clone())
+            {
+                cache.entries.remove(&oldest);
+            }
+            cache.tick += 1;
+            let tick = cache.tick;
+            cache.entries.insert(index.name.clone(), (zone.clone(), tick));
+        }
+        Ok(Some(zone))
+    }
+
+    /// Decode zones into the cache on a background thread, e.g. right after opening, so the
+    /// first callers needing them don't pay for it.
+    ///
+    /// Zones missing from the database or failing to decode are skipped. Callers aren't
+    /// blocked meanwhile; one resolving a zone still being warmed decodes it as well.
+    pub fn warm<I>(&self, names: I) -> JoinHandle<()>
+    where
+        I: IntoIterator,
+        I::Item: Into<Vec<u8>>,
+    {
+        let names: Vec<Vec<u8>> = names.into_iter().map(Into::into).collect();
+        let shared = self.clone();
+        thread::spawn(move || {
+            for name in names {
+                let _ = shared.zone_info(&name);
+            }
+        })
+    }
+
+    /// [`SharedZoneInfoDb::warm`] the zone the system is set to, `UTC` and `GMT`.
+    pub fn warm_defaults(&self) -> JoinHandle<()> {
+        let mut names = Vec::new();
+        #[cfg(feature = "fs")]
+        names.extend(crate::system_timezone_name().map(String::into_bytes));
+        names.extend([b"UTC".to_vec(), b"GMT".to_vec()]);
+        self.warm(names)
+    }
+
+    /// Number of zones currently cached.
+    pub fn cached_len(&self) -> usize {
+        self.lock().entries.len()
+    }
+
+    /// Drop all cached zones.
+    pub fn clear_cache(&self) {
+        self.lock().entries.clear();
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, Lru> {
+        self.0.cache.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fs::File;
+
+    use super::*;
+
+    #[test]
+    fn test_shared_lru() {
+        let db = ZoneInfoDb::new_ohos(File::open("./tests/ohos/tzdata").unwrap()).unwrap();
+        let shared = SharedZoneInfoDb::with_capacity(db, 2);
+        let shanghai = shared.zone_info(b"Asia/Shanghai").unwrap().unwrap();
+        assert!(Arc::ptr_eq(&shanghai, &shared.zone_info(b"Asia/Shanghai").unwrap().unwrap()));
+        assert_eq!(*shanghai, shared.db().zone_info(b"Asia/Shanghai").unwrap().unwrap());
+
+        // Tokyo is now the least recently used and makes room for Seoul.
+        shared.zone_info(b"Asia/Tokyo").unwrap().unwrap();
+        shared.zone_info(b"Asia/Shanghai").unwrap().unwrap();
+        shared.zone_info(b"Asia/Seoul").unwrap().unwrap();
+        assert_eq!(shared.cached_len(), 2);
+        assert!(Arc::ptr_eq(&shanghai, &shared.zone_info(b"Asia/Shanghai").unwrap().unwrap()));
+        assert!(shared.zone_info(b"Asia/Nowhere").unwrap().is_none());
+
+        let threads: Vec<_> = (0..4)
+            .map(|_| {
+                let shared = shared.clone();
+                std::thread::spawn(move || shared.zone_info(b"Europe/Paris").unwrap().unwrap())
+            })
+            .collect();
+        threads.into_iter().for_each(|x| assert!(!x.join().unwrap().tzif().transitions.is_empty()));
+        assert_eq!(shared.cached_len(), 2);
+        shared.clear_cache();
+        assert_eq!(shared.cached_len(), 0);
+    }
+
+    /// Heap bytes of a cached zone: its `Arc` allocation and the vectors and strings it owns.
+    fn heap_size(zone: &ZoneInfo) -> usize {
+        let tzif = zone.tzif();
+        let types = tzif.local_time_types.iter().map(|x| x.abbreviation.capacity());
+        2 * size_of::<usize>()
+            + size_of::<ZoneInfo>()
+            + tzif.transitions.capacity() * size_of::<crate::Transition>()
+            + tzif.local_time_types.capacity() * size_of::<crate::LocalTimeType>()
+            + types.sum::<usize>()
+            + tzif.footer.as_ref().map_or(0, String::capacity)
+            + tzif.leap_seconds.capacity() * size_of::<crate::LeapSecond>()
+    }
+
+    /// Backs the figures in the docs of [`SharedZoneInfoDb`].
+    #[test]
+    fn test_cache_memory() {
+        let db = ZoneInfoDb::new_android(File::open("./tests/android/tzdata").unwrap()).unwrap();
+        let header = db.header();
+        let data_len = header.zonetab_offset - header.data_offset;
+        let shared = SharedZoneInfoDb::with_capacity(db, usize::MAX);
+        let names: Vec<_> = shared.db().indexes().timezones().iter().map(|x| &x.name).collect();
+        let total: usize =
+            names.iter().map(|x| heap_size(&shared.zone_info(x).unwrap().unwrap())).sum();
+        assert_eq!((names.len(), shared.cached_len()), (593, 593));
+        assert_eq!(data_len, 460_977);
+        assert!((1_750..2_250).contains(&(total / names.len())), "{total}");
+        assert!((1_050 << 10..1_200 << 10).contains(&total), "{total}");
+    }
+
+    #[test]
+    fn test_warm() {
+        let db = ZoneInfoDb::new_ohos(File::open("./tests/ohos/tzdata").unwrap()).unwrap();
+        let shared = SharedZoneInfoDb::new(db);
+        shared.warm(["Asia/Shanghai", "Asia/Nowhere", "Europe/Paris"]).join().unwrap();
+        assert_eq!(shared.cached_len(), 2);
+        let paris = shared.zone_info(b"Europe/Paris").unwrap().unwrap();
+        shared.warm(["Europe/Paris"]).join().unwrap();
+        assert!(Arc::ptr_eq(&paris, &shared.zone_info(b"Europe/Paris").unwrap().unwrap()));
+
+        let db = ZoneInfoDb::new_android(File::open("./tests/android/tzdata").unwrap()).unwrap();
+        let shared = SharedZoneInfoDb::new(db);
+        shared.warm_defaults().join().unwrap();
+        assert!(shared.cached_len() >= 2);
+    }
+}
@@ -0,0 +1,77 @@
+//! The local time of every zone at once, for services refreshing a full offset table.
+use std::sync::Arc;
+
+#[cfg(feature = "rayon")]
+use rayon::prelude::*;
+
+use crate::{Result, TzDataIndex, ZoneInfo, ZoneInfoDb};
+
+/// Local time of a zone in [`ZoneInfoDb::current_offsets_snapshot`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct ZoneOffset {
+    /// Name of the zone, sharing the allocation of its index entry.
+    pub zone: Arc<[u8]>,
+    /// Offset from UTC in seconds, east positive.
+    pub offset: i32,
+    pub is_dst: bool,
+    /// Abbreviation of the local time, e.g. `CST`.
+    pub abbreviation: String,
+}
+
+impl ZoneInfoDb {
+    /// The offset, DST flag and abbreviation of every zone at a Unix timestamp, in index order,
+    /// in one pass instead of a lookup per zone.
+    ///
+    /// Entries pointing outside the file are skipped, like [`ZoneInfoDb::iter`] does. With the
+    /// `rayon` feature, zones are parsed across threads.
+    pub fn current_offsets_snapshot(&self, unix_seconds: i64) -> Result<Vec<ZoneOffset>> {
+        let offset = |index: &TzDataIndex| {
+            let zone_info = ZoneInfo::new(self.tzdata(index)?);
+            Some(zone_info.map(|zone_info| {
+                let offset = zone_info.offset_at(unix_seconds);
+                ZoneOffset {
+                    zone: index.name.clone(),
+                    offset: offset.seconds,
+                    is_dst: offset.is_dst,
+                    abbreviation: offset.abbreviation.into(),
+                }
+            }))
+        };
+        #[cfg(feature = "rayon")]
+        return self.indexes.timezones().par_iter().filter_map(offset).collect();
+        #[cfg(not(feature = "rayon"))]
+        self.indexes.timezones().iter().filter_map(offset).collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fs::File;
+
+    use super::*;
+
+    #[test]
+    fn test_current_offsets_snapshot() {
+        let db = ZoneInfoDb::new_android(File::open("./tests/android/tzdata").unwrap()).unwrap();
+        // 2024-07-01.
+        let snapshot = db.current_offsets_snapshot(1719792000).unwrap();
+        assert_eq!(snapshot.len(), db.zone_count());
+        assert!(
+            snapshot.iter().map(|x| &x.zone).eq(db.indexes().timezones().iter().map(|x| &x.name))
+        );
+        for zone in &snapshot {
+            let zone_info = db.zone_info(&zone.zone).unwrap().unwrap();
+            let offset = zone_info.offset_at(1719792000);
+            assert_eq!(
+                (zone.offset, zone.is_dst, &*zone.abbreviation),
+                (offset.seconds, offset.is_dst, offset.abbreviation)
+            );
+        }
+        let new_york = snapshot.iter().find(|x| &*x.zone == b"America/New_York").unwrap();
+        assert_eq!(
+            (new_york.offset, new_york.is_dst, &*new_york.abbreviation),
+            (-4 * 3600, true, "EDT")
+        );
+    }
+}
@@ -0,0 +1,113 @@
+//! Random-access storage that the timezone data of a `tzdata` file is read from.
+#[cfg(any(unix, windows))]
+use std::fs::File;
+use std::{borrow::Cow, io};
+
+use crate::{ParseOptions, Result, TzDataHeader, TzDataIndex, TzDataIndexes, checked_entry_range};
+
+/// Storage of a `tzdata` file that can be read at any offset through a shared reference, e.g. an
+/// asset manager, an encrypted file or a remote blob.
+///
+/// Implemented for [`File`] on Unix and Windows, for byte slices, and with the `mmap` feature
+/// for `memmap2::Mmap`.
+pub trait TzDataSource {
+    /// Read `len` bytes at `offset`, borrowing them if the source is in memory.
+    ///
+    /// Fails with [`io::ErrorKind::UnexpectedEof`] if fewer bytes are available.
+    fn read_at(&self, offset: u64, len: usize) -> Result<Cow<'_, [u8]>>;
+}
+
+impl TzDataSource for [u8] {
+    fn read_at(&self, offset: u64, len: usize) -> Result<Cow<'_, [u8]>> {
+        let start = usize::try_from(offset).ok();
+        let data = start.and_then(|start| self.get(start..start.checked_add(len)?));
+        let data = data.ok_or_else(|| io::Error::from(io::ErrorKind::UnexpectedEof))?;
+        Ok(Cow::Borrowed(data))
+    }
+}
+
+#[cfg(unix)]
+impl TzDataSource for File {
+    fn read_at(&self, offset: u64, len: usize) -> Result<Cow<'_, [u8]>> {
+        let mut buffer = vec![0; len];
+        std::os::unix::fs::FileExt::read_exact_at(self, &mut buffer, offset)?;
+        Ok(Cow::Owned(buffer))
+    }
+}
+
+#[cfg(windows)]
+impl TzDataSource for File {
+    fn read_at(&self, offset: u64, len: usize) -> Result<Cow<'_, [u8]>> {
+        let mut buffer = vec![0; len];
+        let mut filled = 0;
+        while filled < len {
+            let read = std::os::windows::fs::FileExt::seek_read(
+                self,
+                &mut buffer[filled..],
+                offset + filled as u64,
+            )?;
+            if read == 0 {
+                return Err(io::Error::from(io::ErrorKind::UnexpectedEof).into());
+            }
+            filled += read;
+        }
+        Ok(Cow::Owned(buffer))
+    }
+}
+
+#[cfg(feature = "mmap")]
+impl TzDataSource for memmap2::Mmap {
+    fn read_at(&self, offset: u64, len: usize) -> Result<Cow<'_, [u8]>> {
+        self[..].read_at(offset, len)
+    }
+}
+
+impl TzDataIndexes {
+    /// Retrieve a chunk of timezone data by the index from a [`TzDataSource`], borrowing it from
+    /// sources in memory; the counterpart of [`TzDataIndexes::find_tzdata`] for storage that
+    /// isn't a reader.
+    pub fn find_tzdata_at<'s, S: TzDataSource + ?Sized>(
+        &self,
+        source: &'s S,
+        header: &TzDataHeader,
+        index: &TzDataIndex,
+    ) -> Result<Cow<'s, [u8]>> {
+        let offset = checked_entry_range(header, index, &ParseOptions::default())?.start;
+        source.read_at(offset, index.length as usize)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ZoneInfoError;
+
+    #[test]
+    fn test_find_tzdata_at() {
+        let data = std::fs::read("./tests/android/tzdata").unwrap();
+        let header = TzDataHeader::from_bytes(&data).unwrap();
+        let indexes = TzDataIndexes::from_bytes_android(&data, &header).unwrap();
+        let index = indexes.find_timezone(b"Asia/Shanghai").unwrap();
+        let expected = indexes.find_tzdata(io::Cursor::new(&data), &header, index).unwrap();
+
+        let from_slice = indexes.find_tzdata_at(&data[..], &header, index).unwrap();
+        assert!(matches!(from_slice, Cow::Borrowed(_)));
+        assert_eq!(from_slice, expected);
+        let file = File::open("./tests/android/tzdata").unwrap();
+        assert_eq!(indexes.find_tzdata_at(&file, &header, index).unwrap(), expected);
+        #[cfg(feature = "mmap")]
+        {
+            // SAFETY: the fixture isn't modified while mapped.
+            let mmap = unsafe { memmap2::Mmap::map(&file) }.unwrap();
+            assert_eq!(indexes.find_tzdata_at(&mmap, &header, index).unwrap(), expected);
+        }
+
+        let truncated = &data[..data.len() - 1000];
+        let last = indexes.timezones().iter().max_by_key(|x| x.offset).unwrap();
+        let err = indexes.find_tzdata_at(truncated, &header, last).unwrap_err();
+        assert!(
+            matches!(err, ZoneInfoError::Io(err) if err.kind() == io::ErrorKind::UnexpectedEof)
+        );
+        assert!(matches!(file.read_at(u64::MAX - 1, 10), Err(ZoneInfoError::Io(_))));
+    }
+}
@@ -0,0 +1,68 @@
+//! The legacy `zoneinfo.idx` and `zoneinfo.dat` pair of Android releases before 4.0.
+use std::{fs, io, path::Path};
+
+use crate::{Result, SIZEOF_HEADER, TzDataHeader, ZoneInfoDb, ZoneInfoError};
+
+impl ZoneInfoDb {
+    /// Load the separate index and data files that Android shipped before the unified `tzdata`,
+    /// as found in old device dumps and some vendor forks.
+    ///
+    /// `zoneinfo.idx` holds entries laid out as in the Android `tzdata` index, with offsets into
+    /// `zoneinfo.dat`, the concatenated TZif data. The version is read from a `zoneinfo.version`
+    /// file next to the index, and is all zero bytes if there's none. The pair is checked as by
+    /// [`ZoneInfoDb::open_untrusted_android`], and there is no zonetab.
+    pub fn open_split(idx_path: impl AsRef<Path>, dat_path: impl AsRef<Path>) -> Result<Self> {
+        let idx_path = idx_path.as_ref();
+        let version = match fs::read(idx_path.with_file_name("zoneinfo.version")) {
+            Ok(text) => {
+                text.trim_ascii().try_into().map_err(|_| ZoneInfoError::InvalidTzVersion)?
+            }
+            Err(err) if err.kind() == io::ErrorKind::NotFound => [0; 5],
+            Err(err) => return Err(err.into()),
+        };
+        let index = fs::read(idx_path)?;
+        let data = fs::read(dat_path)?;
+
+        let offset = |x: usize| u32::try_from(x).map_err(|_| ZoneInfoError::TooLarge);
+        let data_offset = SIZEOF_HEADER + index.len();
+        let zonetab_offset = data_offset.checked_add(data.len()).ok_or(ZoneInfoError::TooLarge)?;
+        let mut bytes = Vec::with_capacity(zonetab_offset);
+        let header = TzDataHeader {
+            version,
+            index_offset: offset(SIZEOF_HEADER)?,
+            data_offset: offset(data_offset)?,
+            zonetab_offset: offset(zonetab_offset)?,
+        };
+        bytes.extend_from_slice(&header.to_bytes());
+        bytes.extend_from_slice(&index);
+        bytes.extend_from_slice(&data);
+        Self::open_untrusted_android(&bytes)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_open_split() {
+        let tzdata = std::fs::read("./tests/android/tzdata").unwrap();
+        let db = ZoneInfoDb::new_android(&tzdata[..]).unwrap();
+        let header = db.header();
+        let dir = std::env::temp_dir().join(format!("zoneinfo-db-split-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let (idx, dat) = (dir.join("zoneinfo.idx"), dir.join("zoneinfo.dat"));
+        let index = &tzdata[header.index_offset as usize..header.data_offset as usize];
+        std::fs::write(&idx, index).unwrap();
+        std::fs::write(&dat, &tzdata[header.data_offset as usize..]).unwrap();
+
+        let split = ZoneInfoDb::open_split(&idx, &dat).unwrap();
+        assert_eq!(split.header().version, [0; 5]);
+        assert!(split.iter().eq(db.iter()));
+        std::fs::write(dir.join("zoneinfo.version"), "2021a\n").unwrap();
+        let split = ZoneInfoDb::open_split(&idx, &dat).unwrap();
+        std::fs::remove_dir_all(&dir).unwrap();
+        assert_eq!(split.header().version, *b"2021a");
+        assert_eq!(split.get(b"Asia/Shanghai").unwrap().len(), 573);
+    }
+}
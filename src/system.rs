@@ -0,0 +1,128 @@
+//! Process-wide cached [`ZoneInfoDb`] of the running system.
+use std::{
+    path::PathBuf,
+    sync::{Arc, OnceLock, PoisonError, RwLock},
+};
+
+use crate::{Result, TzdataLocator, ZoneInfoDb};
+
+static SYSTEM: SharedDb = SharedDb::new();
+
+impl ZoneInfoDb {
+    /// The `tzdata` file of the system, loaded on first call and shared by the whole process.
+    ///
+    /// The newest file among the [`TzdataLocator::android`] paths is used, or else the newest
+    /// among the [`TzdataLocator::ohos`] ones, see [`ZoneInfoDb::best_tzdata`].
+    /// A failed load isn't cached, so the next call tries again.
+    pub fn system() -> Result<Arc<Self>> {
+        SYSTEM.get(load_system)
+    }
+
+    /// Load the `tzdata` file of the system again, e.g. after the tzdata module was updated, and
+    /// replace the instance returned by [`ZoneInfoDb::system`].
+    ///
+    /// Instances returned earlier stay valid. On error, the cached instance is kept.
+    pub fn reload() -> Result<Arc<Self>> {
+        SYSTEM.reload(load_system)
+    }
+
+    /// Load the freshest `tzdata` file of the system, along with its path.
+    ///
+    /// Same as [`ZoneInfoDb::best_tzdata_in`] with the [`TzdataLocator::android`] and
+    /// [`TzdataLocator::ohos`] paths.
+    pub fn best_tzdata() -> Result<(Self, PathBuf)> {
+        Self::best_tzdata_in(&TzdataLocator::android(), &TzdataLocator::ohos())
+    }
+
+    /// Load the freshest `tzdata` file among the given paths, along with its path.
+    ///
+    /// Of the `android` paths, the one with the newest version wins, see
+    /// [`TzdataLocator::open_newest`]; if none opens, the same goes for the `ohos` paths.
+    pub fn best_tzdata_in(
+        android: &TzdataLocator,
+        ohos: &TzdataLocator,
+    ) -> Result<(Self, PathBuf)> {
+        if let Ok((file, path)) = android.open_newest() {
+            return Ok((Self::new_android(file)?, path.to_owned()));
+        }
+        let (file, path) = ohos.open_newest()?;
+        Ok((Self::new_ohos(file)?, path.to_owned()))
+    }
+}
+
+fn load_system() -> Result<ZoneInfoDb> {
+    ZoneInfoDb::best_tzdata().map(|(db, _)| db)
+}
+
+/// A lazily loaded instance shared by its callers, which a reload replaces.
+struct SharedDb(OnceLock<RwLock<Arc<ZoneInfoDb>>>);
+
+impl SharedDb {
+    const fn new() -> Self {
+        Self(OnceLock::new())
+    }
+
+    /// The cached instance, or the one `load` returns if there's none yet.
+    fn get(&self, load: impl FnOnce() -> Result<ZoneInfoDb>) -> Result<Arc<ZoneInfoDb>> {
+        if let Some(db) = self.0.get() {
+            return Ok(db.read().unwrap_or_else(PoisonError::into_inner).clone());
+        }
+        let db = Arc::new(load()?);
+        Ok(self
+            .0
+            .get_or_init(|| RwLock::new(db))
+            .read()
+            .unwrap_or_else(PoisonError::into_inner)
+            .clone())
+    }
+
+    /// Replace the cached instance with the one `load` returns, keeping it on error.
+    fn reload(&self, load: impl FnOnce() -> Result<ZoneInfoDb>) -> Result<Arc<ZoneInfoDb>> {
+        let db = Arc::new(load()?);
+        if let Err(new) = self.0.set(RwLock::new(db.clone())) {
+            let new = new.into_inner().unwrap_or_else(PoisonError::into_inner);
+            let lock = self.0.get().unwrap();
+            *lock.write().unwrap_or_else(PoisonError::into_inner) = new;
+        }
+        Ok(db)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_shared_and_reload() {
+        let root = std::env::temp_dir().join(format!("zoneinfo-db-system-{}", std::process::id()));
+        std::fs::create_dir_all(root.join("misc/zoneinfo")).unwrap();
+        std::fs::copy("./tests/android/tzdata", root.join("misc/zoneinfo/tzdata")).unwrap();
+        let android: TzdataLocator =
+            [root.join("nowhere/tzdata"), root.join("misc/zoneinfo/tzdata")].into_iter().collect();
+        let ohos: TzdataLocator = ["./tests/ohos/tzdata"].into_iter().collect();
+        let load = || ZoneInfoDb::best_tzdata_in(&android, &ohos).map(|(db, _)| db);
+        let fail = || ZoneInfoDb::best_tzdata_in(&TzdataLocator::new(), &TzdataLocator::new());
+        let fail = || fail().map(|(db, _)| db);
+
+        // A failed load isn't cached.
+        let shared = SharedDb::new();
+        assert!(shared.get(fail).is_err());
+        let db = shared.get(load).unwrap();
+        assert!(Arc::ptr_eq(&db, &shared.get(load).unwrap()));
+        assert_eq!(db.get(b"Asia/Shanghai").unwrap().len(), 573);
+
+        let reloaded = shared.reload(load).unwrap();
+        assert!(!Arc::ptr_eq(&db, &reloaded));
+        assert!(Arc::ptr_eq(&reloaded, &shared.get(load).unwrap()));
+        assert_eq!(reloaded.header().version, *b"2021a");
+        assert!(shared.reload(fail).is_err());
+        assert!(Arc::ptr_eq(&reloaded, &shared.get(load).unwrap()));
+
+        let (_, path) = ZoneInfoDb::best_tzdata_in(&android, &ohos).unwrap();
+        assert_eq!(path, root.join("misc/zoneinfo/tzdata"));
+        let (db, path) = ZoneInfoDb::best_tzdata_in(&TzdataLocator::new(), &ohos).unwrap();
+        assert_eq!((db.header().version, path), (*b"2024a", "./tests/ohos/tzdata".into()));
+
+        std::fs::remove_dir_all(root).unwrap();
+    }
+}
@@ -0,0 +1,103 @@
+//! Generator of synthetic `tzdata` files, for roundtrip tests here and for downstream crates
+//! mocking the platform database.
+use alloc::{format, string::String, vec::Vec};
+
+use crate::{TzDataBuilder, TzDataFlavor, fixed_tzdata};
+
+/// Version written in the header of synthetic files.
+pub const SYNTHETIC_VERSION: [u8; 5] = *b"2099z";
+
+/// Name of the `i`th zone of a synthetic file, e.g. `Test/Zone00042`; names sort by `i`.
+pub fn synthetic_zone_name(i: usize) -> String {
+    format!("Test/Zone{i:05}")
+}
+
+/// UTC offset in seconds of the `i`th zone of the synthetic file generated from `seed`, a whole
+/// number of quarter hours within 14 hours of UTC.
+pub fn synthetic_offset(seed: u64, i: usize) -> i32 {
+    // SplitMix64, so neighbouring seeds and zones get unrelated offsets.
+    let mut x = seed.wrapping_add((i as u64 + 1).wrapping_mul(0x9E37_79B9_7F4A_7C15));
+    x = (x ^ (x >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+    x = (x ^ (x >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+    x ^= x >> 31;
+    ((x % 113) as i32 - 56) * 900
+}
+
+/// Build a `tzdata` file of `flavor` with `zones` fixed-offset zones named by
+/// [`synthetic_zone_name`] with offsets from [`synthetic_offset`], and a zonetab row for each.
+///
+/// The same arguments always give the same bytes. Panics if `zones` exceeds 100000.
+pub fn synthetic_tzdata(seed: u64, zones: usize, flavor: TzDataFlavor) -> Vec<u8> {
+    assert!(zones <= 100_000, "synthetic zone names have 5 digits");
+    let mut builder = TzDataBuilder::new(SYNTHETIC_VERSION);
+    let mut zone_tab = String::new();
+    for i in 0..zones {
+        let name = synthetic_zone_name(i);
+        builder.zone(name.as_bytes(), fixed_tzdata(synthetic_offset(seed, i)).unwrap());
+        zone_tab += &format!("ZZ\t+0000+00000\t{name}\n");
+    }
+    builder.zone_tab(zone_tab).build(flavor).unwrap()
+}
+
+/// Builder holding every zone of `db`, for tests deriving a new file from a fixture.
+#[cfg(all(test, feature = "std"))]
+pub(crate) fn fixture_builder(db: &crate::ZoneInfoDb, version: [u8; 5]) -> TzDataBuilder {
+    let mut builder = TzDataBuilder::new(version);
+    for (name, tzif) in db.iter() {
+        builder.zone(name, tzif);
+    }
+    builder
+}
+
+/// The Android fixture rebuilt with `zone_tab` as its zonetab, which the fixture lacks.
+#[cfg(all(test, feature = "std"))]
+pub(crate) fn android_fixture_with_zone_tab(zone_tab: &str) -> crate::ZoneInfoDb {
+    use crate::ZoneInfoDb;
+    let db =
+        ZoneInfoDb::new_android(std::fs::File::open("./tests/android/tzdata").unwrap()).unwrap();
+    let mut builder = fixture_builder(&db, db.header().version);
+    builder.zone_tab(zone_tab);
+    ZoneInfoDb::from_bytes(builder.build(TzDataFlavor::Android).unwrap()).unwrap()
+}
+
+#[cfg(all(test, feature = "std", feature = "tzif", feature = "zonetab"))]
+mod tests {
+    use super::*;
+    use crate::ZoneInfoDb;
+
+    #[test]
+    fn test_synthetic_roundtrip() {
+        for seed in 0..8 {
+            for zones in [0, 1, 2, 37, 600] {
+                for flavor in [TzDataFlavor::Android, TzDataFlavor::Ohos] {
+                    let data = synthetic_tzdata(seed, zones, flavor);
+                    assert_eq!(data, synthetic_tzdata(seed, zones, flavor));
+                    let db = match flavor {
+                        TzDataFlavor::Android => ZoneInfoDb::open_untrusted_android(&data),
+                        TzDataFlavor::Ohos => ZoneInfoDb::open_untrusted_ohos(&data),
+                    }
+                    .unwrap();
+                    assert_eq!(db.header().version, SYNTHETIC_VERSION);
+                    assert_eq!(db.zone_count(), zones);
+                    assert!(db.verify().is_ok());
+                    for i in 0..zones {
+                        let name = synthetic_zone_name(i);
+                        let offset = synthetic_offset(seed, i);
+                        assert!(offset.abs() <= 14 * 3600 && offset % 900 == 0);
+                        let tzif = db.get(name.as_bytes()).unwrap();
+                        assert_eq!(tzif, fixed_tzdata(offset).unwrap());
+                        assert_eq!(
+                            db.zone_info(name.as_bytes()).unwrap().unwrap().raw_offset(),
+                            offset
+                        );
+                    }
+                    assert_eq!(db.zone_tab().unwrap().entries().len(), zones);
+                }
+            }
+        }
+        assert_ne!(
+            synthetic_tzdata(0, 10, TzDataFlavor::Ohos),
+            synthetic_tzdata(1, 10, TzDataFlavor::Ohos)
+        );
+    }
+}
@@ -0,0 +1,45 @@
+//! Conversions of [`ZoneInfo`] offsets to the `time` crate, behind the `time` feature.
+use ::time::{OffsetDateTime, UtcOffset};
+
+use crate::ZoneInfo;
+
+impl ZoneInfo {
+    /// Offset from UTC at a Unix timestamp, as a [`time::UtcOffset`](UtcOffset).
+    ///
+    /// Offsets of 26 hours or more, which no real zone has, aren't representable and fall back to
+    /// UTC.
+    pub fn time_offset_at(&self, unix_seconds: i64) -> UtcOffset {
+        UtcOffset::from_whole_seconds(self.offset_at(unix_seconds).seconds)
+            .unwrap_or(UtcOffset::UTC)
+    }
+
+    /// Offset from UTC of the zone at the instant of `datetime`, whatever offset it carries.
+    ///
+    /// Convert with `datetime.to_offset(zone.local_offset_at(datetime))`.
+    pub fn local_offset_at(&self, datetime: OffsetDateTime) -> UtcOffset {
+        self.time_offset_at(datetime.unix_timestamp())
+    }
+}
+
+#[cfg(all(test, feature = "std"))]
+mod tests {
+    use std::fs::File;
+
+    use super::*;
+    use crate::ZoneInfoDb;
+
+    #[test]
+    fn test_time_offset() {
+        let db = ZoneInfoDb::new_android(File::open("./tests/android/tzdata").unwrap()).unwrap();
+        let shanghai = db.zone_info(b"Asia/Shanghai").unwrap().unwrap();
+        assert_eq!(shanghai.time_offset_at(1719792000), UtcOffset::from_hms(8, 0, 0).unwrap());
+
+        let new_york = db.zone_info(b"America/New_York").unwrap().unwrap();
+        let datetime = OffsetDateTime::from_unix_timestamp(1719792000).unwrap();
+        let offset = new_york.local_offset_at(datetime);
+        assert_eq!(offset, UtcOffset::from_hms(-4, 0, 0).unwrap());
+        let local = datetime.to_offset(offset);
+        assert_eq!((local.day(), local.hour()), (30, 20));
+        assert_eq!(local, datetime);
+    }
+}
@@ -0,0 +1,125 @@
+//! Async variants of the reader-based parsers on tokio's I/O traits, behind the `tokio` feature.
+use ::tokio::io::{AsyncRead, AsyncReadExt, AsyncSeek, AsyncSeekExt};
+use std::io::SeekFrom;
+
+use crate::{
+    ParseOptions, Result, SIZEOF_HEADER, SIZEOF_INDEX_ENTRY_ANDROID, SIZEOF_INDEX_ENTRY_OHOS,
+    TzDataHeader, TzDataIndex, TzDataIndexes, checked_entry_range,
+};
+
+impl TzDataHeader {
+    /// Parse the header of the `tzdata` file from an async reader.
+    pub async fn new_async<R: AsyncRead + Unpin>(mut data: R) -> Result<Self> {
+        let mut header = [0; SIZEOF_HEADER];
+        data.read_exact(&mut header).await?;
+        Self::from_bytes(&header)
+    }
+}
+
+impl TzDataIndexes {
+    /// Parse the indexes of the `tzdata` file of Android from an async reader.
+    pub async fn new_android_async<R: AsyncRead + Unpin>(
+        reader: R,
+        header: &TzDataHeader,
+    ) -> Result<Self> {
+        Self::parse::<SIZEOF_INDEX_ENTRY_ANDROID>(&read_index(reader, header).await?)
+    }
+
+    /// Parse the indexes of the `tzdata` file of HarmonyOS NEXT from an async reader.
+    pub async fn new_ohos_async<R: AsyncRead + Unpin>(
+        reader: R,
+        header: &TzDataHeader,
+    ) -> Result<Self> {
+        Self::parse::<SIZEOF_INDEX_ENTRY_OHOS>(&read_index(reader, header).await?)
+    }
+
+    /// Read the timezone data of an entry from an async reader.
+    pub async fn find_tzdata_async<R: AsyncRead + AsyncSeek + Unpin>(
+        &self,
+        mut reader: R,
+        header: &TzDataHeader,
+        index: &TzDataIndex,
+    ) -> Result<Vec<u8>> {
+        let start = checked_entry_range(header, index, &ParseOptions::default())?.start;
+        reader.seek(SeekFrom::Start(start)).await?;
+        let mut buffer = vec![0; index.length as usize];
+        reader.read_exact(&mut buffer).await?;
+        Ok(buffer)
+    }
+}
+
+async fn read_index<R: AsyncRead + Unpin>(mut reader: R, header: &TzDataHeader) -> Result<Vec<u8>> {
+    let mut buf = vec![0; ParseOptions::default().index_len(header)?];
+    reader.read_exact(&mut buf).await?;
+    Ok(buf)
+}
+
+/// Get timezone data from the `tzdata` file async reader of Android.
+pub async fn find_tz_data_android_async(
+    mut reader: impl AsyncRead + AsyncSeek + Unpin,
+    tz_name: &[u8],
+) -> Result<Option<Vec<u8>>> {
+    let header = TzDataHeader::new_async(&mut reader).await?;
+    let index = TzDataIndexes::new_android_async(&mut reader, &header).await?;
+    Ok(match index.find_timezone(tz_name) {
+        Some(entry) => Some(index.find_tzdata_async(reader, &header, entry).await?),
+        None => None,
+    })
+}
+
+/// Get timezone data from the `tzdata` file async reader of HarmonyOS NEXT.
+pub async fn find_tz_data_ohos_async(
+    mut reader: impl AsyncRead + AsyncSeek + Unpin,
+    tz_name: &[u8],
+) -> Result<Option<Vec<u8>>> {
+    let header = TzDataHeader::new_async(&mut reader).await?;
+    let index = TzDataIndexes::new_ohos_async(&mut reader, &header).await?;
+    Ok(match index.find_timezone(tz_name) {
+        Some(entry) => Some(index.find_tzdata_async(reader, &header, entry).await?),
+        None => None,
+    })
+}
+
+/// Get timezone data from the `tzdata` file of Android, found by [`TzdataLocator::android`].
+///
+/// Only opening the file blocks, as probing the candidate paths is done synchronously.
+#[cfg(feature = "fs")]
+pub async fn find_tz_data_android_from_fs_async(tz_string: &str) -> Result<Option<Vec<u8>>> {
+    let file = ::tokio::fs::File::from_std(crate::TzdataLocator::android().open()?);
+    find_tz_data_android_async(file, tz_string.as_bytes()).await
+}
+
+/// Get timezone data from the `tzdata` file of HarmonyOS NEXT.
+///
+/// Only opening the file blocks, as probing the candidate paths is done synchronously.
+#[cfg(feature = "fs")]
+pub async fn find_tz_data_ohos_from_fs_async(tz_string: &str) -> Result<Option<Vec<u8>>> {
+    let file = ::tokio::fs::File::from_std(crate::TzdataLocator::ohos().open()?);
+    find_tz_data_ohos_async(file, tz_string.as_bytes()).await
+}
+
+#[cfg(test)]
+mod tests {
+    use ::tokio::fs::File;
+
+    use super::*;
+
+    #[::tokio::test]
+    async fn test_async_matches_sync() {
+        let file = File::open("./tests/android/tzdata").await.unwrap();
+        let android = find_tz_data_android_async(file, b"Asia/Shanghai").await.unwrap();
+        let file = std::fs::File::open("./tests/android/tzdata").unwrap();
+        assert_eq!(android, crate::find_tz_data_android(file, b"Asia/Shanghai").unwrap());
+        assert_eq!(android.unwrap().len(), 573);
+
+        let mut file = File::open("./tests/ohos/tzdata").await.unwrap();
+        let header = TzDataHeader::new_async(&mut file).await.unwrap();
+        let indexes = TzDataIndexes::new_ohos_async(&mut file, &header).await.unwrap();
+        assert_eq!(indexes.timezones().len(), 442);
+        let entry = indexes.find_timezone(b"Asia/Shanghai").unwrap();
+        let data = indexes.find_tzdata_async(&mut file, &header, entry).await.unwrap();
+        assert_eq!(data.len(), 393);
+        file.rewind().await.unwrap();
+        assert_eq!(find_tz_data_ohos_async(file, b"Asia/Nowhere").await.unwrap(), None);
+    }
+}
@@ -0,0 +1,204 @@
+//! Parser of Android's `tz_version` file, which records the versions of a tzdata module, and the
+//! IANA release version.
+use core::{fmt, str::FromStr};
+
+#[cfg(feature = "std")]
+use crate::ZoneInfoDb;
+use crate::{Result, ZoneInfoError};
+
+/// An IANA tz database release, e.g. `2023c`, ordered by year and then release letter.
+///
+/// Wraps the 5 bytes of a header version as they are, so comparisons work for any header; only
+/// the parsers and [`TzdbVersion::new`] ensure the `YYYYx` format.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct TzdbVersion([u8; 5]);
+
+impl TzdbVersion {
+    /// Version of a release.
+    ///
+    /// # Panics
+    ///
+    /// If `year` has more than 4 digits or `release` isn't a lowercase ASCII letter.
+    pub const fn new(year: u16, release: char) -> Self {
+        assert!(year <= 9999 && release.is_ascii_lowercase());
+        let [a, b, c, d] = [year / 1000, year / 100 % 10, year / 10 % 10, year % 10];
+        Self([b'0' + a as u8, b'0' + b as u8, b'0' + c as u8, b'0' + d as u8, release as u8])
+    }
+
+    /// Wrap the raw version of a header.
+    pub const fn from_bytes(version: [u8; 5]) -> Self {
+        Self(version)
+    }
+
+    /// The raw version.
+    pub const fn as_bytes(&self) -> &[u8; 5] {
+        &self.0
+    }
+
+    /// The year, or `None` if the version isn't well-formed.
+    pub fn year(&self) -> Option<u16> {
+        let digits = is_rules_version(&self.0).then_some(&self.0[..4])?;
+        Some(digits.iter().fold(0, |year, x| year * 10 + u16::from(x - b'0')))
+    }
+
+    /// The release letter, `a` for the first release of a year.
+    pub fn release(&self) -> char {
+        self.0[4] as char
+    }
+}
+
+impl FromStr for TzdbVersion {
+    type Err = ZoneInfoError;
+
+    fn from_str(s: &str) -> Result<Self> {
+        let version = s.as_bytes().try_into().map_err(|_| ZoneInfoError::InvalidTzVersion)?;
+        if !is_rules_version(&version) {
+            return Err(ZoneInfoError::InvalidTzVersion);
+        }
+        Ok(Self(version))
+    }
+}
+
+impl fmt::Display for TzdbVersion {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.0.escape_ascii())
+    }
+}
+
+/// The content of a `tz_version` file, e.g. `003.001|2021a|1`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct TzVersion {
+    /// Major version of the file formats, bumped on incompatible changes.
+    pub format_major: u16,
+    /// Minor version of the file formats.
+    pub format_minor: u16,
+    /// IANA rules version, e.g. `2021a`.
+    pub rules_version: [u8; 5],
+    /// Android revision of the rules, starting from 1.
+    pub revision: u32,
+}
+
+impl TzVersion {
+    /// Parse the content of a `tz_version` file; a trailing newline is allowed.
+    pub fn new(text: &[u8]) -> Result<Self> {
+        let text = core::str::from_utf8(text).map_err(|_| ZoneInfoError::InvalidTzVersion)?;
+        let mut fields = text.trim_end().split('|');
+        let (Some(format), Some(rules_version), Some(revision), None) =
+            (fields.next(), fields.next(), fields.next(), fields.next())
+        else {
+            return Err(ZoneInfoError::InvalidTzVersion);
+        };
+        let (major, minor) = format.split_once('.').ok_or(ZoneInfoError::InvalidTzVersion)?;
+        let rules_version: [u8; 5] =
+            rules_version.as_bytes().try_into().map_err(|_| ZoneInfoError::InvalidTzVersion)?;
+        if !is_rules_version(&rules_version) {
+            return Err(ZoneInfoError::InvalidTzVersion);
+        }
+        Ok(Self {
+            format_major: number(major)?,
+            format_minor: number(minor)?,
+            rules_version,
+            revision: number(revision)?,
+        })
+    }
+
+    /// Read and parse a `tz_version` file, which Android ships next to `tzdata`.
+    #[cfg(feature = "std")]
+    pub fn from_path(path: impl AsRef<std::path::Path>) -> Result<Self> {
+        Self::new(&std::fs::read(path)?)
+    }
+
+    /// Whether a runtime reading format `major.minor` can use these files, as Android checks
+    /// before installing a tzdata module: the major versions must be equal, and the files'
+    /// minor version at least the runtime's, as minor versions only add to the formats.
+    pub fn is_compatible_with_runtime(&self, format_major: u16, format_minor: u16) -> bool {
+        self.format_major == format_major && self.format_minor >= format_minor
+    }
+}
+
+impl FromStr for TzVersion {
+    type Err = ZoneInfoError;
+
+    fn from_str(s: &str) -> Result<Self> {
+        Self::new(s.as_bytes())
+    }
+}
+
+/// Whether a version is of the `YYYYx` form.
+fn is_rules_version(version: &[u8; 5]) -> bool {
+    version[..4].iter().all(u8::is_ascii_digit) && version[4].is_ascii_lowercase()
+}
+
+/// Parse a decimal number without sign.
+fn number<T: FromStr>(text: &str) -> Result<T> {
+    if text.is_empty() || !text.bytes().all(|x| x.is_ascii_digit()) {
+        return Err(ZoneInfoError::InvalidTzVersion);
+    }
+    text.parse().map_err(|_| ZoneInfoError::InvalidTzVersion)
+}
+
+#[cfg(feature = "std")]
+impl ZoneInfoDb {
+    /// Version of the database, from the header.
+    pub fn version(&self) -> TzdbVersion {
+        TzdbVersion(self.header().version)
+    }
+
+    /// Check that the version in the header matches the rules version of `tz_version`.
+    pub fn check_consistency(&self, tz_version: &TzVersion) -> Result<()> {
+        if self.header().version != tz_version.rules_version {
+            return Err(ZoneInfoError::VersionMismatch {
+                tzdata: self.header().version,
+                tz_version: tz_version.rules_version,
+            });
+        }
+        Ok(())
+    }
+}
+
+#[cfg(all(test, feature = "std"))]
+mod tests {
+    use std::fs::File;
+
+    use super::*;
+
+    #[test]
+    fn test_tz_version() {
+        let version: TzVersion = "003.001|2021a|1\n".parse().unwrap();
+        assert_eq!(
+            version,
+            TzVersion { format_major: 3, format_minor: 1, rules_version: *b"2021a", revision: 1 }
+        );
+        assert!(version.is_compatible_with_runtime(3, 1));
+        assert!(version.is_compatible_with_runtime(3, 0));
+        assert!(!version.is_compatible_with_runtime(3, 2));
+        assert!(!version.is_compatible_with_runtime(2, 1));
+        assert!(!version.is_compatible_with_runtime(4, 0));
+        for text in ["003.001|2021a", "003|2021a|1", "003.001|2021|1", "003.001|2021a|+1", ""] {
+            assert!(text.parse::<TzVersion>().is_err(), "{text}");
+        }
+
+        let db = ZoneInfoDb::new_android(File::open("./tests/android/tzdata").unwrap()).unwrap();
+        db.check_consistency(&version).unwrap();
+        let newer: TzVersion = "004.001|2024a|1".parse().unwrap();
+        let error = db.check_consistency(&newer).unwrap_err();
+        assert_eq!(error.to_string(), "tzdata version 2021a does not match tz_version 2024a");
+    }
+
+    #[test]
+    fn test_tzdb_version() {
+        let db = ZoneInfoDb::new_android(File::open("./tests/android/tzdata").unwrap()).unwrap();
+        assert_eq!(db.version(), "2021a".parse().unwrap());
+        assert_eq!(db.version().year(), Some(2021));
+        assert_eq!(db.version().release(), 'a');
+        assert_eq!(db.version().to_string(), "2021a");
+        assert!(db.version() >= TzdbVersion::new(2020, 'd'));
+        assert!(db.version() < TzdbVersion::new(2021, 'b'));
+        assert!(TzdbVersion::new(2023, 'c') < TzdbVersion::new(2024, 'a'));
+        assert_eq!(TzdbVersion::new(998, 'z').as_bytes(), b"0998z");
+        assert_eq!(TzdbVersion::from_bytes(*b"2021 ").year(), None);
+        assert!("2021A".parse::<TzdbVersion>().is_err());
+    }
+}
@@ -0,0 +1,133 @@
+//! Zero-copy access to a `tzdata` file held in memory.
+use core::cmp::Ordering;
+
+#[cfg(feature = "zonetab")]
+use crate::ZoneTab;
+use crate::{
+    Result, SIZEOF_INDEX_ENTRY_ANDROID, SIZEOF_INDEX_ENTRY_OHOS, SIZEOF_TZNAME, TzDataHeader,
+    TzDataIndexes, ZoneInfoError, parse_index_entry, slice_at, strip_index_padding,
+};
+
+/// A `tzdata` file borrowed from a byte slice, e.g. an mmap or an `include_bytes!`.
+///
+/// This needs no `std`, so it's the entry point for WASM and embedded targets. Lookups
+/// binary-search the index in place and return slices of the original bytes, so nothing
+/// is allocated after construction.
+#[derive(Debug, Clone, Copy)]
+pub struct TzDataRef<'a> {
+    data: &'a [u8],
+    header: TzDataHeader,
+    /// Index entries, without trailing padding.
+    entries: &'a [u8],
+    entry_size: usize,
+}
+
+impl<'a> TzDataRef<'a> {
+    /// Borrow the `tzdata` file of Android.
+    pub fn new_android(data: &'a [u8]) -> Result<Self> {
+        Self::new::<SIZEOF_INDEX_ENTRY_ANDROID>(data)
+    }
+
+    /// Borrow the `tzdata` file of HarmonyOS NEXT.
+    pub fn new_ohos(data: &'a [u8]) -> Result<Self> {
+        Self::new::<SIZEOF_INDEX_ENTRY_OHOS>(data)
+    }
+
+    fn new<const SIZEOF_INDEX_ENTRY: usize>(data: &'a [u8]) -> Result<Self> {
+        let header = TzDataHeader::from_bytes(data)?;
+        let index = slice_at(data, u64::from(header.index_offset)..u64::from(header.data_offset))
+            .ok_or(ZoneInfoError::TruncatedIndex)?;
+        let (entries, _) = strip_index_padding::<SIZEOF_INDEX_ENTRY>(index)?;
+        Ok(Self { data, header, entries, entry_size: SIZEOF_INDEX_ENTRY })
+    }
+
+    /// Header of the `tzdata` file.
+    pub fn header(&self) -> &TzDataHeader {
+        &self.header
+    }
+
+    /// The underlying bytes.
+    pub fn as_bytes(&self) -> &'a [u8] {
+        self.data
+    }
+
+    /// Parse the indexes into an owned [`TzDataIndexes`].
+    pub fn indexes(&self) -> Result<TzDataIndexes> {
+        if self.entry_size == SIZEOF_INDEX_ENTRY_ANDROID {
+            TzDataIndexes::from_bytes_android(self.data, &self.header)
+        } else {
+            TzDataIndexes::from_bytes_ohos(self.data, &self.header)
+        }
+    }
+
+    /// Parse the zonetab section, from `zonetab_offset` to the end of the file.
+    #[cfg(feature = "zonetab")]
+    pub fn zonetab(&self) -> Result<ZoneTab> {
+        let text = self
+            .data
+            .get(self.header.zonetab_offset as usize..)
+            .ok_or(ZoneInfoError::InvalidLayout)?;
+        ZoneTab::new(text)
+    }
+
+    /// Get the timezone data of a zone, borrowed from the underlying bytes; the same as
+    /// [`TzDataRef::find_tzdata`].
+    pub fn get(&self, name: &[u8]) -> Option<&'a [u8]> {
+        self.find_tzdata(name)
+    }
+
+    /// Find the timezone data of a zone, borrowed from the underlying bytes.
+    pub fn find_tzdata(&self, name: &[u8]) -> Option<&'a [u8]> {
+        let entry = |i: usize| &self.entries[i * self.entry_size..(i + 1) * self.entry_size];
+        // Entries without a NUL-terminated name sort by their full name slot but never match.
+        let key = |entry: &'a [u8]| {
+            let name = &entry[..SIZEOF_TZNAME];
+            name.iter().position(|&x| x == 0).map_or(name, |end| &name[..end])
+        };
+        // timezones in tzdata are sorted by name.
+        let (mut low, mut high) = (0, self.entries.len() / self.entry_size);
+        let i = loop {
+            if low >= high {
+                return None;
+            }
+            let mid = low + (high - low) / 2;
+            match key(entry(mid)).cmp(name) {
+                Ordering::Less => low = mid + 1,
+                Ordering::Greater => high = mid,
+                Ordering::Equal => break mid,
+            }
+        };
+        let (_, offset, length) = parse_index_entry(entry(i))?;
+        let start = u64::from(self.header.data_offset) + u64::from(offset);
+        slice_at(self.data, start..start + u64::from(length))
+    }
+}
+
+#[cfg(all(test, feature = "std"))]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_tzdata_ref_find() {
+        let android = std::fs::read("./tests/android/tzdata").unwrap();
+        let tzdata = TzDataRef::new_android(&android).unwrap();
+        assert_eq!(tzdata.header().version, *b"2021a");
+        let shanghai = tzdata.find_tzdata(b"Asia/Shanghai").unwrap();
+        assert_eq!(shanghai.len(), 573);
+        assert!(android.as_ptr_range().contains(&shanghai.as_ptr()));
+        assert!(tzdata.find_tzdata(b"Africa/Abidjan").is_some());
+        assert!(tzdata.find_tzdata(b"Zulu").is_some());
+        assert!(tzdata.find_tzdata(b"Asia/Nowhere").is_none());
+
+        let ohos = std::fs::read("./tests/ohos/tzdata").unwrap();
+        let tzdata = TzDataRef::new_ohos(&ohos).unwrap();
+        assert_eq!(tzdata.find_tzdata(b"Asia/Shanghai").unwrap().len(), 393);
+        assert_eq!(tzdata.indexes().unwrap().timezones().len(), 442);
+        #[cfg(feature = "zonetab")]
+        assert!(tzdata.zonetab().unwrap().entries().is_empty());
+        assert_eq!(tzdata.as_bytes().len(), ohos.len());
+        // The borrow outlives the temporary handle.
+        let shanghai = TzDataRef::new_ohos(&ohos).unwrap().get(b"Asia/Shanghai").unwrap();
+        assert_eq!(shanghai.len(), 393);
+    }
+}
@@ -0,0 +1,502 @@
+//! Parser of the TZif format ([RFC 8536](https://datatracker.ietf.org/doc/html/rfc8536)) of the
+//! per-zone data in `tzdata`.
+use alloc::{
+    string::{String, ToString},
+    vec::Vec,
+};
+
+use crate::{Result, ZoneInfoError};
+
+/// Magic of a TZif file.
+const TZIF_MAGIC: &[u8] = b"TZif";
+/// Size of a TZif header: `magic + version + reserved + 6 counts`.
+const SIZEOF_TZIF_HEADER: usize = 4 + 1 + 15 + 6 * size_of::<u32>();
+
+/// A local time type of a zone, e.g. `CST` at UTC+8.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct LocalTimeType {
+    /// Offset from UTC in seconds.
+    pub utoff: i32,
+    pub is_dst: bool,
+    pub abbreviation: String,
+}
+
+/// A change of local time type at a point in time.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Transition {
+    /// Unix timestamp of the transition.
+    pub time: i64,
+    /// Index into [`TzIf::local_time_types`] of the type in effect from `time` on.
+    pub local_time_type: usize,
+}
+
+/// A leap second record: from `time` on, `correction` leap seconds have been applied in total.
+///
+/// Only zones of the `right/` timescale, counting leap seconds in their timestamps, have any;
+/// POSIX ones, as shipped on Android and OpenHarmony, have none.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct LeapSecond {
+    /// Timestamp, counting earlier leap seconds, at which the correction takes effect.
+    pub time: i64,
+    /// Total correction in seconds, e.g. 27 after the leap second of 2016.
+    pub correction: i32,
+}
+
+/// Decoded TZif data of a zone.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct TzIf {
+    /// Format version: 1, 2, 3 or 4.
+    pub version: u8,
+    /// Transitions, in ascending order of time.
+    pub transitions: Vec<Transition>,
+    /// Local time types; the first one applies before the first transition.
+    pub local_time_types: Vec<LocalTimeType>,
+    /// POSIX TZ string describing local time after the last transition, e.g. `CST-8` or
+    /// `EST5EDT,M3.2.0,M11.1.0`. Only present in version 2 and later; may be empty.
+    pub footer: Option<String>,
+    /// Leap second records, in ascending order of time.
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub leap_seconds: Vec<LeapSecond>,
+}
+
+/// Counts of a TZif header, in file order.
+struct Counts {
+    isutcnt: usize,
+    isstdcnt: usize,
+    leapcnt: usize,
+    timecnt: usize,
+    typecnt: usize,
+    charcnt: usize,
+}
+
+impl Counts {
+    /// Size of the data block following the header, for `TIME_SIZE`-byte times.
+    fn data_len<const TIME_SIZE: usize>(&self) -> Result<usize> {
+        [
+            (self.timecnt, TIME_SIZE + 1),
+            (self.typecnt, 6),
+            (self.charcnt, 1),
+            (self.leapcnt, TIME_SIZE + 4),
+            (self.isstdcnt, 1),
+            (self.isutcnt, 1),
+        ]
+        .into_iter()
+        .try_fold(0usize, |len, (count, size)| len.checked_add(count.checked_mul(size)?))
+        .ok_or_else(counts_overflow)
+    }
+}
+
+impl TzIf {
+    /// Parse TZif data, e.g. as returned by the `find_tz_data_*` functions.
+    ///
+    /// For version 2 and later files, the 64-bit data block and the footer are used and the
+    /// version 1 block is skipped.
+    pub fn new(data: &[u8]) -> Result<Self> {
+        let mut cursor = Cursor(data);
+        let (version, counts) = read_header(&mut cursor)?;
+        if version == 1 {
+            return Self::read_block::<4>(&mut cursor, version, &counts);
+        }
+
+        cursor.take(counts.data_len::<4>()?)?;
+        let (_, counts) = read_header(&mut cursor)?;
+        let mut tzif = Self::read_block::<8>(&mut cursor, version, &counts)?;
+        tzif.footer = Some(read_footer(&mut cursor)?);
+        Ok(tzif)
+    }
+
+    fn read_block<const TIME_SIZE: usize>(
+        cursor: &mut Cursor,
+        version: u8,
+        counts: &Counts,
+    ) -> Result<Self> {
+        let len = |count: usize, size: usize| count.checked_mul(size).ok_or_else(counts_overflow);
+        let times = cursor.take(len(counts.timecnt, TIME_SIZE)?)?;
+        let types = cursor.take(counts.timecnt)?;
+        let ttinfos = cursor.take(len(counts.typecnt, 6)?)?;
+        let chars = cursor.take(counts.charcnt)?;
+        let leaps = cursor.take(len(counts.leapcnt, TIME_SIZE + 4)?)?;
+        // Standard/wall and UT/local indicators aren't exposed.
+        cursor.take(counts.isstdcnt.checked_add(counts.isutcnt).ok_or_else(counts_overflow)?)?;
+
+        let transitions = times
+            .chunks_exact(TIME_SIZE)
+            .zip(types)
+            .map(|(time, &local_time_type)| {
+                let time = match TIME_SIZE {
+                    4 => i32::from_be_bytes(time.try_into().unwrap()).into(),
+                    _ => i64::from_be_bytes(time.try_into().unwrap()),
+                };
+                let local_time_type = local_time_type as usize;
+                if local_time_type >= counts.typecnt {
+                    return Err(ZoneInfoError::InvalidTzif("transition type out of range"));
+                }
+                Ok(Transition { time, local_time_type })
+            })
+            .collect::<Result<Vec<_>>>()?;
+        if !transitions.is_sorted_by(|a, b| a.time < b.time) {
+            return Err(ZoneInfoError::InvalidTzif("transitions are not in ascending order"));
+        }
+
+        let local_time_types = ttinfos
+            .chunks_exact(6)
+            .map(|ttinfo| {
+                let utoff = i32::from_be_bytes(ttinfo[..4].try_into().unwrap());
+                let is_dst = match ttinfo[4] {
+                    0 => false,
+                    1 => true,
+                    _ => return Err(ZoneInfoError::InvalidTzif("bad DST indicator")),
+                };
+                let abbreviation = chars
+                    .get(ttinfo[5] as usize..)
+                    .and_then(|x| core::ffi::CStr::from_bytes_until_nul(x).ok())
+                    .ok_or(ZoneInfoError::InvalidTzif("abbreviation index out of range"))?;
+                let abbreviation = String::from_utf8_lossy(abbreviation.to_bytes()).into_owned();
+                Ok(LocalTimeType { utoff, is_dst, abbreviation })
+            })
+            .collect::<Result<Vec<_>>>()?;
+
+        let leap_seconds: Vec<_> = leaps
+            .chunks_exact(TIME_SIZE + 4)
+            .map(|record| {
+                let (time, correction) = record.split_at(TIME_SIZE);
+                let time = match TIME_SIZE {
+                    4 => i32::from_be_bytes(time.try_into().unwrap()).into(),
+                    _ => i64::from_be_bytes(time.try_into().unwrap()),
+                };
+                LeapSecond { time, correction: i32::from_be_bytes(correction.try_into().unwrap()) }
+            })
+            .collect();
+        if !leap_seconds.is_sorted_by(|a, b| a.time < b.time) {
+            return Err(ZoneInfoError::InvalidTzif("leap seconds are not in ascending order"));
+        }
+
+        Ok(Self { version, transitions, local_time_types, footer: None, leap_seconds })
+    }
+
+    /// Serialize as minimal version 3 TZif data: a version 1 block holding a single placeholder
+    /// type, as `zic -b slim` writes, then the 64-bit block, with the leap seconds if any, and
+    /// the footer, empty if there's none.
+    ///
+    /// Types no transition uses are dropped, except the first, and equal abbreviations are
+    /// stored once. Fails with [`ZoneInfoError::InvalidTzif`] if a transition refers to a
+    /// missing type, or the types or abbreviations don't fit the 8-bit indexes of the format.
+    pub fn to_bytes_v3(&self) -> Result<Vec<u8>> {
+        // New index of each kept type, and the kept types in order.
+        let mut remap = alloc::vec![None; self.local_time_types.len()];
+        let mut types = Vec::new();
+        for i in core::iter::once(0).chain(self.transitions.iter().map(|x| x.local_time_type)) {
+            let slot = remap
+                .get_mut(i)
+                .ok_or(ZoneInfoError::InvalidTzif("transition type out of range"))?;
+            if slot.is_none() {
+                *slot = Some(u8::try_from(types.len()).map_err(|_| index_overflow())?);
+                types.push(&self.local_time_types[i]);
+            }
+        }
+
+        let mut chars = Vec::new();
+        let mut ttinfos = Vec::with_capacity(types.len() * 6);
+        for ty in &types {
+            let abbreviation = [ty.abbreviation.as_bytes(), b"\0"].concat();
+            let index = match chars.windows(abbreviation.len()).position(|x| x == abbreviation) {
+                Some(index) => index,
+                None => {
+                    chars.extend_from_slice(&abbreviation);
+                    chars.len() - abbreviation.len()
+                }
+            };
+            ttinfos.extend_from_slice(&ty.utoff.to_be_bytes());
+            ttinfos.push(ty.is_dst.into());
+            ttinfos.push(u8::try_from(index).map_err(|_| index_overflow())?);
+        }
+
+        let header = |leapcnt: usize, timecnt: usize, typecnt: usize, charcnt: usize| {
+            let mut header = [0; SIZEOF_TZIF_HEADER];
+            header[..4].copy_from_slice(TZIF_MAGIC);
+            header[4] = b'3';
+            // isutcnt and isstdcnt stay 0.
+            for (i, count) in [leapcnt, timecnt, typecnt, charcnt].into_iter().enumerate() {
+                header[28 + 4 * i..32 + 4 * i].copy_from_slice(&(count as u32).to_be_bytes());
+            }
+            header
+        };
+        let mut out = Vec::new();
+        out.extend_from_slice(&header(0, 0, 1, 1));
+        out.extend_from_slice(&[0; 7]);
+        let leapcnt = self.leap_seconds.len();
+        out.extend_from_slice(&header(leapcnt, self.transitions.len(), types.len(), chars.len()));
+        for transition in &self.transitions {
+            out.extend_from_slice(&transition.time.to_be_bytes());
+        }
+        out.extend(self.transitions.iter().map(|x| remap[x.local_time_type].unwrap()));
+        out.extend_from_slice(&ttinfos);
+        out.extend_from_slice(&chars);
+        for leap in &self.leap_seconds {
+            out.extend_from_slice(&leap.time.to_be_bytes());
+            out.extend_from_slice(&leap.correction.to_be_bytes());
+        }
+        out.push(b'\n');
+        out.extend_from_slice(self.footer.as_deref().unwrap_or_default().as_bytes());
+        out.push(b'\n');
+        Ok(out)
+    }
+}
+
+fn counts_overflow() -> ZoneInfoError {
+    ZoneInfoError::InvalidTzif("counts overflow")
+}
+
+fn index_overflow() -> ZoneInfoError {
+    ZoneInfoError::InvalidTzif("types or abbreviations overflow 8-bit indexes")
+}
+
+/// Extract the POSIX TZ string footer of TZif data, e.g. `CST-8`, skipping the data blocks
+/// without decoding them.
+///
+/// Returns `None` for version 1 data, which has no footer.
+pub fn posix_tz_string(tzif: &[u8]) -> Result<Option<String>> {
+    let mut cursor = Cursor(tzif);
+    let (version, counts) = read_header(&mut cursor)?;
+    if version == 1 {
+        return Ok(None);
+    }
+    cursor.take(counts.data_len::<4>()?)?;
+    let (_, counts) = read_header(&mut cursor)?;
+    cursor.take(counts.data_len::<8>()?)?;
+    read_footer(&mut cursor).map(Some)
+}
+
+/// Number of leap second records of TZif data, read from its headers without decoding it.
+#[cfg(feature = "std")]
+pub(crate) fn leap_second_count(tzif: &[u8]) -> Result<usize> {
+    let mut cursor = Cursor(tzif);
+    let (version, counts) = read_header(&mut cursor)?;
+    if version == 1 {
+        return Ok(counts.leapcnt);
+    }
+    cursor.take(counts.data_len::<4>()?)?;
+    Ok(read_header(&mut cursor)?.1.leapcnt)
+}
+
+fn read_header(cursor: &mut Cursor) -> Result<(u8, Counts)> {
+    let header = cursor.take(SIZEOF_TZIF_HEADER)?;
+    if !header.starts_with(TZIF_MAGIC) {
+        return Err(ZoneInfoError::InvalidTzif("bad magic"));
+    }
+    let version = match header[4] {
+        0 => 1,
+        version @ b'2'..=b'4' => version - b'0',
+        _ => return Err(ZoneInfoError::InvalidTzif("unsupported version")),
+    };
+    let count = |i: usize| {
+        let start = 20 + 4 * i;
+        u32::from_be_bytes(header[start..start + 4].try_into().unwrap()) as usize
+    };
+    let counts = Counts {
+        isutcnt: count(0),
+        isstdcnt: count(1),
+        leapcnt: count(2),
+        timecnt: count(3),
+        typecnt: count(4),
+        charcnt: count(5),
+    };
+    if counts.typecnt == 0
+        || counts.charcnt == 0
+        || (counts.isutcnt != 0 && counts.isutcnt != counts.typecnt)
+        || (counts.isstdcnt != 0 && counts.isstdcnt != counts.typecnt)
+    {
+        return Err(ZoneInfoError::InvalidTzif("bad header counts"));
+    }
+    Ok((version, counts))
+}
+
+fn read_footer(cursor: &mut Cursor) -> Result<String> {
+    let footer =
+        cursor.0.strip_prefix(b"\n").ok_or(ZoneInfoError::InvalidTzif("missing footer"))?;
+    let end = footer
+        .iter()
+        .position(|&x| x == b'\n')
+        .ok_or(ZoneInfoError::InvalidTzif("unterminated footer"))?;
+    let footer = core::str::from_utf8(&footer[..end])
+        .map_err(|_| ZoneInfoError::InvalidTzif("footer is not UTF-8"))?
+        .to_string();
+    cursor.0 = &cursor.0[end + 2..];
+    Ok(footer)
+}
+
+/// Forward-only reader over a byte slice.
+struct Cursor<'a>(&'a [u8]);
+
+impl<'a> Cursor<'a> {
+    fn take(&mut self, len: usize) -> Result<&'a [u8]> {
+        let (head, tail) =
+            self.0.split_at_checked(len).ok_or(ZoneInfoError::InvalidTzif("truncated data"))?;
+        self.0 = tail;
+        Ok(head)
+    }
+}
+
+#[cfg(all(test, feature = "std"))]
+mod tests {
+    use std::fs::File;
+
+    use super::*;
+    use crate::{find_tz_data_android, find_tz_data_ohos};
+
+    #[test]
+    fn test_android_tzif_shanghai() {
+        let file = File::open("./tests/android/tzdata").unwrap();
+        let tzdata = find_tz_data_android(file, b"Asia/Shanghai").unwrap().unwrap();
+        let tzif = TzIf::new(&tzdata).unwrap();
+        assert_eq!(tzif.version, 2);
+        assert_eq!(tzif.footer.as_deref(), Some("CST-8"));
+        assert_eq!(tzif.transitions.len(), 29);
+        let last = tzif.transitions.last().unwrap();
+        assert_eq!(last.time, 684867600);
+        assert_eq!(
+            tzif.local_time_types[last.local_time_type],
+            LocalTimeType { utoff: 8 * 3600, is_dst: false, abbreviation: "CST".to_string() }
+        );
+    }
+
+    #[test]
+    fn test_ohos_tzif_new_york() {
+        let file = File::open("./tests/ohos/tzdata").unwrap();
+        let tzdata = find_tz_data_ohos(file, b"America/New_York").unwrap().unwrap();
+        let tzif = TzIf::new(&tzdata).unwrap();
+        assert_eq!(tzif.footer.as_deref(), Some("EST5EDT,M3.2.0,M11.1.0"));
+        let abbreviations: Vec<_> =
+            tzif.local_time_types.iter().map(|x| x.abbreviation.as_str()).collect();
+        assert!(abbreviations.contains(&"EDT"));
+        assert!(abbreviations.contains(&"EST"));
+        assert!(tzif.transitions.is_sorted_by_key(|x| x.time));
+    }
+
+    #[test]
+    fn test_tzif_every_zone() {
+        for db in [
+            crate::ZoneInfoDb::new_android(File::open("./tests/android/tzdata").unwrap()).unwrap(),
+            crate::ZoneInfoDb::new_ohos(File::open("./tests/ohos/tzdata").unwrap()).unwrap(),
+        ] {
+            for index in db.indexes().timezones() {
+                let tzif = TzIf::new(db.tzdata(index).unwrap()).unwrap();
+                assert!(tzif.footer.is_some(), "{index:?}");
+            }
+        }
+    }
+
+    #[test]
+    fn test_tzif_to_bytes_v3() {
+        let db =
+            crate::ZoneInfoDb::new_android(File::open("./tests/android/tzdata").unwrap()).unwrap();
+        let (mut total, mut written) = (0, 0);
+        for (name, data) in db.iter() {
+            let tzif = TzIf::new(data).unwrap();
+            let bytes = crate::ZoneInfo::from_tzif(tzif.clone()).unwrap().to_tzif_v3().unwrap();
+            let parsed = TzIf::new(&bytes).unwrap();
+            assert_eq!(parsed.version, 3);
+            assert_eq!(parsed.footer, tzif.footer);
+            assert_eq!(parsed.local_time_types[0], tzif.local_time_types[0]);
+            assert_eq!(parsed.transitions.len(), tzif.transitions.len(), "{name:?}");
+            for (a, b) in parsed.transitions.iter().zip(&tzif.transitions) {
+                assert_eq!(a.time, b.time);
+                assert_eq!(
+                    parsed.local_time_types[a.local_time_type],
+                    tzif.local_time_types[b.local_time_type]
+                );
+            }
+            assert_eq!(parsed.to_bytes_v3().unwrap(), bytes);
+            (total, written) = (total + data.len(), written + bytes.len());
+        }
+        assert!(written < total, "{written} >= {total}");
+
+        let mut tzif = TzIf::new(db.get(b"Asia/Shanghai").unwrap()).unwrap();
+        tzif.transitions[0].local_time_type = 99;
+        assert!(matches!(tzif.to_bytes_v3(), Err(ZoneInfoError::InvalidTzif(_))));
+    }
+
+    #[test]
+    fn test_tzif_leap_seconds() {
+        let db =
+            crate::ZoneInfoDb::new_android(File::open("./tests/android/tzdata").unwrap()).unwrap();
+        assert!(!db.uses_leap_seconds());
+        let mut tzif = TzIf::new(db.get(b"Asia/Shanghai").unwrap()).unwrap();
+        assert!(tzif.leap_seconds.is_empty());
+
+        // 1972-07-01 and 1973-01-01, each counting the leap seconds before it.
+        tzif.leap_seconds = alloc::vec![
+            LeapSecond { time: 78796800, correction: 1 },
+            LeapSecond { time: 94694401, correction: 2 },
+        ];
+        let bytes = tzif.to_bytes_v3().unwrap();
+        let zone = crate::ZoneInfo::new(&bytes).unwrap();
+        assert_eq!(zone.leap_seconds(), tzif.leap_seconds);
+        #[cfg(feature = "builder")]
+        {
+            let mut builder = crate::TzDataBuilder::new(*b"2024a");
+            builder.zone("right/Asia/Shanghai", &bytes[..]);
+            let right = builder.build(crate::TzDataFlavor::Ohos).unwrap();
+            assert!(crate::ZoneInfoDb::new_ohos(&right[..]).unwrap().uses_leap_seconds());
+        }
+
+        tzif.leap_seconds.swap(0, 1);
+        let bytes = tzif.to_bytes_v3().unwrap();
+        assert!(matches!(TzIf::new(&bytes), Err(ZoneInfoError::InvalidTzif(_))));
+    }
+
+    #[test]
+    fn test_posix_tz_string() {
+        let file = File::open("./tests/ohos/tzdata").unwrap();
+        let tzdata = find_tz_data_ohos(file, b"America/New_York").unwrap().unwrap();
+        assert_eq!(posix_tz_string(&tzdata).unwrap().as_deref(), Some("EST5EDT,M3.2.0,M11.1.0"));
+
+        let mut tzdata = crate::fixed_offset_tzdata(b"Etc/GMT+8").unwrap();
+        assert_eq!(posix_tz_string(&tzdata).unwrap().as_deref(), Some("<-08>8"));
+        assert!(posix_tz_string(&tzdata[..tzdata.len() - 1]).is_err());
+        tzdata[4] = 0;
+        assert_eq!(posix_tz_string(&tzdata).unwrap(), None);
+    }
+
+    #[test]
+    fn test_tzif_v1_and_malformed() {
+        let mut tzdata = crate::fixed_offset_tzdata(b"Etc/GMT-14").unwrap();
+        // The version 1 block alone is a complete version 1 file.
+        tzdata[4] = 0;
+        let tzif = TzIf::new(&tzdata).unwrap();
+        assert_eq!(tzif.version, 1);
+        assert_eq!(tzif.footer, None);
+        assert_eq!(tzif.local_time_types[0].utoff, 14 * 3600);
+        assert_eq!(tzif.local_time_types[0].abbreviation, "+14");
+
+        tzdata[4] = b'2';
+        assert!(TzIf::new(&tzdata[..tzdata.len() - 1]).is_err());
+        tzdata[0] = b'X';
+        assert!(TzIf::new(&tzdata).is_err());
+    }
+
+    #[test]
+    fn test_tzif_counts_overflow() {
+        let counts = |timecnt| Counts {
+            isutcnt: 1,
+            isstdcnt: 1,
+            leapcnt: 0,
+            timecnt,
+            typecnt: 1,
+            charcnt: 4,
+        };
+        assert_eq!(counts(2).data_len::<8>().unwrap(), 2 * 9 + 6 + 4 + 2);
+        for timecnt in [usize::MAX / 9 + 1, usize::MAX - 8] {
+            assert!(matches!(
+                counts(timecnt).data_len::<8>(),
+                Err(ZoneInfoError::InvalidTzif("counts overflow"))
+            ));
+        }
+        let err = TzIf::read_block::<8>(&mut Cursor(&[]), 2, &counts(usize::MAX / 8 + 1));
+        assert!(matches!(err, Err(ZoneInfoError::InvalidTzif("counts overflow"))));
+    }
+}
@@ -0,0 +1,209 @@
+//! Parser of Android's `tzlookup.xml`, which maps countries to zones next to the tzdata file,
+//! behind the `tzlookup` feature.
+use alloc::{
+    string::{String, ToString},
+    sync::Arc,
+    vec::Vec,
+};
+
+use crate::{Result, TzDataIndexes, ZoneInfoError};
+
+/// The zones of a country in `tzlookup.xml`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct CountryZones {
+    /// Lowercase ISO 3166 alpha-2 code, e.g. `us`.
+    pub code: String,
+    pub default_zone: Arc<[u8]>,
+    /// Whether the country has ever used UTC.
+    pub ever_utc: bool,
+    /// Zones in file order, most populous first.
+    pub zones: Vec<Arc<[u8]>>,
+}
+
+/// The parsed `tzlookup.xml`.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct TzLookup {
+    iana_version: Option<String>,
+    countries: Vec<CountryZones>,
+}
+
+impl TzLookup {
+    /// Parse the text of a `tzlookup.xml`.
+    ///
+    /// Only the `timezones`, `country` and `id` elements are interpreted; other elements,
+    /// comments and processing instructions are skipped.
+    pub fn new(text: &[u8]) -> Result<Self> {
+        let text = core::str::from_utf8(text)
+            .map_err(|_| ZoneInfoError::InvalidTzLookup("text is not UTF-8"))?;
+        let mut lookup = Self::default();
+        let mut country: Option<CountryZones> = None;
+        let mut rest = text;
+        while let Some(start) = rest.find('<') {
+            let tag = &rest[start + 1..];
+            if let Some(tag) = tag.strip_prefix("!--") {
+                let end =
+                    tag.find("-->").ok_or(ZoneInfoError::InvalidTzLookup("unclosed comment"))?;
+                rest = &tag[end + 3..];
+                continue;
+            }
+            let end = tag.find('>').ok_or(ZoneInfoError::InvalidTzLookup("unclosed tag"))?;
+            let (tag, after) = (&tag[..end], &tag[end + 1..]);
+            rest = after;
+            let (name, attributes) = tag.split_once(char::is_whitespace).unwrap_or((tag, ""));
+            match name {
+                "timezones" => lookup.iana_version = attribute(attributes, "ianaversion")?,
+                "country" => {
+                    if country.is_some() {
+                        return Err(ZoneInfoError::InvalidTzLookup("nested country"));
+                    }
+                    let code = attribute(attributes, "code")?
+                        .filter(|x| x.len() == 2 && x.bytes().all(|x| x.is_ascii_alphabetic()))
+                        .ok_or(ZoneInfoError::InvalidTzLookup("bad country code"))?;
+                    let default_zone = attribute(attributes, "default")?
+                        .ok_or(ZoneInfoError::InvalidTzLookup("country has no default"))?;
+                    country = Some(CountryZones {
+                        code: code.to_ascii_lowercase(),
+                        default_zone: Arc::from(default_zone.as_bytes()),
+                        ever_utc: attribute(attributes, "everutc")?.as_deref() == Some("y"),
+                        zones: Vec::new(),
+                    });
+                }
+                "/country" => lookup.countries.push(
+                    country.take().ok_or(ZoneInfoError::InvalidTzLookup("stray </country>"))?,
+                ),
+                "id" => {
+                    let country = country
+                        .as_mut()
+                        .ok_or(ZoneInfoError::InvalidTzLookup("id outside of country"))?;
+                    let end =
+                        rest.find("</id>").ok_or(ZoneInfoError::InvalidTzLookup("unclosed id"))?;
+                    country.zones.push(Arc::from(rest[..end].trim().as_bytes()));
+                    rest = &rest[end + 5..];
+                }
+                _ => {}
+            }
+        }
+        if country.is_some() {
+            return Err(ZoneInfoError::InvalidTzLookup("unclosed country"));
+        }
+        Ok(lookup)
+    }
+
+    /// Read and parse a `tzlookup.xml` file.
+    #[cfg(feature = "std")]
+    pub fn from_path(path: impl AsRef<std::path::Path>) -> Result<Self> {
+        Self::new(&std::fs::read(path)?)
+    }
+
+    /// The `ianaversion` attribute, e.g. `2021a`.
+    pub fn iana_version(&self) -> Option<&str> {
+        self.iana_version.as_deref()
+    }
+
+    /// All countries, in file order.
+    pub fn countries(&self) -> &[CountryZones] {
+        &self.countries
+    }
+
+    /// Find a country by code, ignoring case.
+    pub fn find_country(&self, code: &str) -> Option<&CountryZones> {
+        self.countries.iter().find(|x| x.code.eq_ignore_ascii_case(code))
+    }
+
+    /// Zones of a country, e.g. `us`, in file order.
+    pub fn zones_for_country_code(&self, code: &str) -> impl Iterator<Item = &[u8]> {
+        self.find_country(code).into_iter().flat_map(|x| x.zones.iter().map(|x| &**x))
+    }
+
+    /// Default zone of a country.
+    pub fn default_zone_for_country(&self, code: &str) -> Option<&[u8]> {
+        self.find_country(code).map(|x| &*x.default_zone)
+    }
+
+    /// Replace the zone names of countries with those of the matching entries of `indexes`, so
+    /// both share one allocation per name.
+    pub fn share_names(&mut self, indexes: &TzDataIndexes) {
+        let share = |name: &mut Arc<[u8]>| {
+            if let Some(index) = indexes.find_timezone(name) {
+                *name = index.name.clone();
+            }
+        };
+        for country in &mut self.countries {
+            share(&mut country.default_zone);
+            country.zones.iter_mut().for_each(share);
+        }
+    }
+
+    /// Zones referenced by the file, including defaults, that are missing from `indexes`.
+    pub fn missing_zones<'a>(
+        &'a self,
+        indexes: &'a TzDataIndexes,
+    ) -> impl Iterator<Item = &'a [u8]> + 'a {
+        self.countries
+            .iter()
+            .flat_map(|x| core::iter::once(&x.default_zone).chain(&x.zones))
+            .map(|x| &**x)
+            .filter(|x| indexes.find_timezone(x).is_none())
+    }
+}
+
+/// Value of the attribute `name` of a tag, without entity decoding.
+fn attribute(attributes: &str, name: &str) -> Result<Option<String>> {
+    let mut rest = attributes.trim().trim_end_matches('/');
+    while let Some((key, value)) = rest.split_once('=') {
+        let value = value.trim_start();
+        let quote = value
+            .chars()
+            .next()
+            .filter(|x| matches!(x, '"' | '\''))
+            .ok_or(ZoneInfoError::InvalidTzLookup("unquoted attribute"))?;
+        let end =
+            value[1..].find(quote).ok_or(ZoneInfoError::InvalidTzLookup("unclosed attribute"))?;
+        if key.trim() == name {
+            return Ok(Some(value[1..end + 1].to_string()));
+        }
+        rest = &value[end + 2..];
+    }
+    Ok(None)
+}
+
+#[cfg(all(test, feature = "std"))]
+mod tests {
+    use std::fs::File;
+
+    use super::*;
+
+    #[test]
+    fn test_tzlookup() {
+        let lookup = TzLookup::from_path("./tests/android/tzlookup.xml").unwrap();
+        assert_eq!(lookup.iana_version(), Some("2021a"));
+        assert_eq!(lookup.countries().len(), 4);
+        assert_eq!(lookup.default_zone_for_country("US"), Some(&b"America/New_York"[..]));
+        assert_eq!(lookup.zones_for_country_code("us").count(), 8);
+        assert_eq!(
+            lookup.zones_for_country_code("us").nth(2),
+            Some(&b"America/Indiana/Indianapolis"[..])
+        );
+        assert!(lookup.find_country("gb").unwrap().ever_utc);
+        assert_eq!(lookup.zones_for_country_code("fr").count(), 0);
+
+        let db =
+            crate::ZoneInfoDb::new_android(File::open("./tests/android/tzdata").unwrap()).unwrap();
+        let indexes = db.indexes();
+        assert!(lookup.missing_zones(indexes).all(|x| x == b"Asia/Nowhere"));
+        assert_eq!(lookup.missing_zones(indexes).count(), 2);
+
+        let mut lookup = lookup;
+        lookup.share_names(indexes);
+        let new_york = &indexes.find_timezone(b"America/New_York").unwrap().name;
+        let us = lookup.find_country("us").unwrap();
+        assert!(Arc::ptr_eq(&us.default_zone, new_york) && Arc::ptr_eq(&us.zones[0], new_york));
+        assert_eq!(lookup.missing_zones(indexes).count(), 2);
+
+        assert!(TzLookup::new(b"<country code=\"us\" default=\"UTC\">").is_err());
+        assert!(TzLookup::new(b"<country code=us default=\"UTC\"></country>").is_err());
+        assert!(TzLookup::new(b"<id>UTC</id>").is_err());
+    }
+}
@@ -0,0 +1,193 @@
+//! Structural verification of a `tzdata` database.
+use std::{fmt, sync::Arc};
+
+#[cfg(feature = "rayon")]
+use rayon::prelude::*;
+
+use crate::{TzDataIndex, TzIf, ZoneInfoDb};
+
+/// A problem found by [`ZoneInfoDb::verify`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum VerifyIssue {
+    /// A name isn't strictly after the one before it in the index.
+    UnsortedName { previous: Arc<[u8]>, name: Arc<[u8]> },
+    /// The data of a zone lies outside the data section.
+    OutOfBounds { name: Arc<[u8]> },
+    /// The data of two zones partially overlap; zones sharing the exact same range are fine.
+    Overlap { name: Arc<[u8]>, other: Arc<[u8]> },
+    /// The data of a zone can't be parsed as TZif.
+    InvalidTzif { name: Arc<[u8]>, reason: String },
+    /// The zonetab section can't be parsed.
+    InvalidZoneTab { reason: String },
+    /// A zonetab row references a zone missing from the index.
+    UnknownZoneTabZone { zone: Arc<[u8]> },
+}
+
+impl fmt::Display for VerifyIssue {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::UnsortedName { previous, name } => {
+                write!(f, "{} is not sorted after {}", name.escape_ascii(), previous.escape_ascii())
+            }
+            Self::OutOfBounds { name } => {
+                write!(f, "{}: data outside of the data section", name.escape_ascii())
+            }
+            Self::Overlap { name, other } => {
+                write!(f, "{}: data overlaps {}", name.escape_ascii(), other.escape_ascii())
+            }
+            Self::InvalidTzif { name, reason } => write!(f, "{}: {reason}", name.escape_ascii()),
+            Self::InvalidZoneTab { reason } => write!(f, "zonetab: {reason}"),
+            Self::UnknownZoneTabZone { zone } => {
+                write!(f, "zonetab: unknown zone {}", zone.escape_ascii())
+            }
+        }
+    }
+}
+
+/// Result of [`ZoneInfoDb::verify`].
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct VerifyReport {
+    /// Number of index entries checked.
+    pub zones: usize,
+    /// CRC-32 (IEEE) of the whole file, as computed by `zlib` and `cksum -a crc32b`.
+    pub checksum: u32,
+    /// Problems found, in the order the checks ran.
+    pub issues: Vec<VerifyIssue>,
+}
+
+impl VerifyReport {
+    /// Whether no problem was found.
+    pub fn is_ok(&self) -> bool {
+        self.issues.is_empty()
+    }
+}
+
+impl ZoneInfoDb {
+    /// Check the structure of the database: names are sorted, data ranges lie in the data
+    /// section and don't partially overlap, every zone parses as TZif, and every zonetab row
+    /// references an indexed zone.
+    ///
+    /// Unlike the checks done when loading, this collects every problem instead of failing on
+    /// the first one. With the `rayon` feature, zones are parsed across threads.
+    pub fn verify(&self) -> VerifyReport {
+        let timezones = self.indexes.timezones();
+        let mut issues = Vec::new();
+
+        for pair in timezones.windows(2) {
+            if pair[0].name >= pair[1].name {
+                issues.push(VerifyIssue::UnsortedName {
+                    previous: pair[0].name.clone(),
+                    name: pair[1].name.clone(),
+                });
+            }
+        }
+
+        let data_len =
+            u64::from(self.header.zonetab_offset.saturating_sub(self.header.data_offset))
+                .min((self.data.len() as u64).saturating_sub(u64::from(self.header.data_offset)));
+        let mut ranges = Vec::with_capacity(timezones.len());
+        for index in timezones {
+            let range =
+                (u64::from(index.offset), u64::from(index.offset) + u64::from(index.length));
+            if range.1 > data_len {
+                issues.push(VerifyIssue::OutOfBounds { name: index.name.clone() });
+            } else {
+                ranges.push((range, &index.name));
+            }
+        }
+        ranges.sort();
+        // The range reaching furthest so far, which any later range must start after or equal.
+        let mut furthest = ((0, 0), None::<&Arc<[u8]>>);
+        for (range, name) in ranges {
+            if let (other_range, Some(other)) = furthest
+                && range.0 < other_range.1
+                && range != other_range
+            {
+                issues.push(VerifyIssue::Overlap { name: name.clone(), other: other.clone() });
+            }
+            if furthest.1.is_none() || range.1 > furthest.0.1 {
+                furthest = (range, Some(name));
+            }
+        }
+
+        let invalid_tzif = |index: &TzDataIndex| {
+            let err = self.tzdata(index).map(TzIf::new)?.err()?;
+            Some(VerifyIssue::InvalidTzif { name: index.name.clone(), reason: err.to_string() })
+        };
+        #[cfg(feature = "rayon")]
+        let (invalid, checksum) = rayon::join(
+            || timezones.par_iter().filter_map(invalid_tzif).collect::<Vec<_>>(),
+            || crc32(&self.data),
+        );
+        #[cfg(not(feature = "rayon"))]
+        let (invalid, checksum) =
+            (timezones.iter().filter_map(invalid_tzif).collect::<Vec<_>>(), crc32(&self.data));
+        issues.extend(invalid);
+
+        match self.zone_tab() {
+            Ok(zone_tab) => {
+                for entry in zone_tab.entries() {
+                    if self.indexes.find_timezone(&entry.zone).is_none() {
+                        issues.push(VerifyIssue::UnknownZoneTabZone { zone: entry.zone.clone() });
+                    }
+                }
+            }
+            Err(err) => issues.push(VerifyIssue::InvalidZoneTab { reason: err.to_string() }),
+        }
+
+        VerifyReport { zones: timezones.len(), checksum, issues }
+    }
+}
+
+/// CRC-32 with the IEEE polynomial, bit by bit; a `tzdata` file is small enough not to need a
+/// table.
+fn crc32(data: &[u8]) -> u32 {
+    let mut crc = !0u32;
+    for &byte in data {
+        crc ^= u32::from(byte);
+        for _ in 0..8 {
+            crc = (crc >> 1) ^ (0xEDB8_8320 & (crc & 1).wrapping_neg());
+        }
+    }
+    !crc
+}
+
+#[cfg(all(test, feature = "builder"))]
+mod tests {
+    use std::fs::File;
+
+    use super::*;
+    use crate::{TzDataBuilder, TzDataFlavor};
+
+    #[test]
+    fn test_verify() {
+        let db = ZoneInfoDb::new_ohos(File::open("./tests/ohos/tzdata").unwrap()).unwrap();
+        let report = db.verify();
+        assert!(report.is_ok(), "{:?}", report.issues);
+        assert_eq!(report.zones, 442);
+        assert_eq!(report.checksum, 0x7dce_ab48);
+        // Android shares the data of linked zones, which isn't an overlap.
+        let db = ZoneInfoDb::new_android(File::open("./tests/android/tzdata").unwrap()).unwrap();
+        assert!(db.verify().is_ok());
+
+        let shanghai = db.get(b"Asia/Shanghai").unwrap();
+        let mut builder = TzDataBuilder::new(*b"2099z");
+        builder
+            .zone("Asia/Shanghai", shanghai)
+            .zone("Asia/Broken", &b"TZif junk"[..])
+            .zone_tab("CN\t+3114+12128\tAsia/Shanghai\nXX\t+0000+00000\tAsia/Nowhere\n");
+        let db = ZoneInfoDb::new_ohos(&builder.build(TzDataFlavor::Ohos).unwrap()[..]).unwrap();
+        let report = db.verify();
+        assert_eq!(report.zones, 2);
+        assert!(matches!(
+            &report.issues[..],
+            [
+                VerifyIssue::InvalidTzif { name, .. },
+                VerifyIssue::UnknownZoneTabZone { zone },
+            ] if &**name == b"Asia/Broken" && &**zone == b"Asia/Nowhere"
+        ));
+        assert_eq!(report.issues[1].to_string(), "zonetab: unknown zone Asia/Nowhere");
+    }
+}
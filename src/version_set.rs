@@ -0,0 +1,115 @@
+//! Several `tzdata` databases queried side by side.
+use crate::ZoneInfoDb;
+#[cfg(feature = "tzif")]
+use crate::{Result, ZoneInfo};
+
+/// A set of databases of different versions, e.g. the file shipped with the device and a newer
+/// update, ordered from the oldest version to the newest.
+#[derive(Default)]
+pub struct VersionSet {
+    dbs: Vec<ZoneInfoDb>,
+}
+
+impl VersionSet {
+    /// Create an empty set.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Add a database, keeping the set ordered by version.
+    pub fn insert(&mut self, db: ZoneInfoDb) {
+        let at = self.dbs.partition_point(|x| x.header().version <= db.header().version);
+        self.dbs.insert(at, db);
+    }
+
+    /// All databases, from the oldest version to the newest.
+    pub fn databases(&self) -> &[ZoneInfoDb] {
+        &self.dbs
+    }
+
+    /// Get the timezone data of a zone from every database, along with the database version.
+    pub fn get_in_all(&self, tz_name: &[u8]) -> Vec<([u8; 5], Option<&[u8]>)> {
+        self.dbs.iter().map(|db| (db.header().version, db.get(tz_name))).collect()
+    }
+
+    /// Offset from UTC in seconds of a zone at a Unix timestamp in every database, along with
+    /// the database version, or `None` where the zone is missing, to tell whether an update
+    /// changes local time rather than just the data.
+    #[cfg(feature = "tzif")]
+    pub fn offset_at_in_all(
+        &self,
+        tz_name: &[u8],
+        unix_seconds: i64,
+    ) -> Result<Vec<([u8; 5], Option<i32>)>> {
+        self.dbs
+            .iter()
+            .map(|db| {
+                let offset = db.get(tz_name).map(ZoneInfo::new).transpose()?;
+                Ok((db.header().version, offset.map(|x| x.offset_at(unix_seconds).seconds)))
+            })
+            .collect()
+    }
+
+    /// Whether the timezone data of a zone differs between any of the databases, including the
+    /// zone being added or removed.
+    pub fn changed(&self, tz_name: &[u8]) -> bool {
+        let mut data = self.dbs.iter().map(|db| db.get(tz_name));
+        match data.next() {
+            Some(first) => data.any(|x| x != first),
+            None => false,
+        }
+    }
+}
+
+impl FromIterator<ZoneInfoDb> for VersionSet {
+    fn from_iter<T: IntoIterator<Item = ZoneInfoDb>>(iter: T) -> Self {
+        let mut set = Self::new();
+        iter.into_iter().for_each(|db| set.insert(db));
+        set
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fs::File;
+
+    use super::*;
+
+    fn version_set() -> VersionSet {
+        [
+            ZoneInfoDb::new_ohos(File::open("./tests/ohos/tzdata").unwrap()).unwrap(),
+            ZoneInfoDb::new_android(File::open("./tests/android/tzdata").unwrap()).unwrap(),
+        ]
+        .into_iter()
+        .collect()
+    }
+
+    #[test]
+    fn test_version_set_get_in_all() {
+        let set = version_set();
+        let versions: Vec<_> = set.databases().iter().map(|db| db.header().version).collect();
+        assert_eq!(versions, [*b"2021a", *b"2024a"]);
+
+        let data = set.get_in_all(b"Asia/Shanghai");
+        assert_eq!(data[0].0, *b"2021a");
+        assert_eq!(data[0].1.unwrap().len(), 573);
+        assert_eq!(data[1].0, *b"2024a");
+        assert_eq!(data[1].1.unwrap().len(), 393);
+        assert!(set.changed(b"Asia/Shanghai"));
+        assert!(!set.changed(b"Asia/Nowhere"));
+        assert!(!VersionSet::new().changed(b"Asia/Shanghai"));
+    }
+
+    #[cfg(feature = "tzif")]
+    #[test]
+    fn test_version_set_offset_at_in_all() {
+        let set = version_set();
+        // 2024-07-01: Mexico abolished daylight saving time in 2022.
+        let mexico = set.offset_at_in_all(b"America/Mexico_City", 1719792000).unwrap();
+        assert_eq!(mexico, [(*b"2021a", Some(-5 * 3600)), (*b"2024a", Some(-6 * 3600))]);
+        let shanghai = set.offset_at_in_all(b"Asia/Shanghai", 1719792000).unwrap();
+        assert_eq!(shanghai, [(*b"2021a", Some(8 * 3600)), (*b"2024a", Some(8 * 3600))]);
+        let nowhere = set.offset_at_in_all(b"Asia/Nowhere", 0).unwrap();
+        assert_eq!(nowhere, [(*b"2021a", None), (*b"2024a", None)]);
+    }
+}
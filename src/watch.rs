@@ -0,0 +1,236 @@
+//! Notification of updates of the system `tzdata` file, behind the `watch` feature.
+use std::{
+    fs,
+    path::PathBuf,
+    sync::{Arc, mpsc},
+    thread,
+    time::{Duration, SystemTime},
+};
+
+#[cfg(feature = "tzif")]
+use crate::{Result, ZoneInfo, system_timezone_name};
+use crate::{TzdataLocator, ZoneInfoDb};
+
+/// Handle of a subscription made by [`ZoneInfoDb::subscribe_updates`]; dropping it stops
+/// watching.
+#[derive(Debug)]
+pub struct UpdateSubscription {
+    _stop: mpsc::Sender<()>,
+}
+
+impl ZoneInfoDb {
+    /// Call `callback` with the reloaded [`ZoneInfoDb::system`] instance whenever the system
+    /// `tzdata` files change, e.g. when a tzdata module update is installed, checking once a
+    /// minute.
+    ///
+    /// See [`ZoneInfoDb::subscribe_updates_with_interval`].
+    pub fn subscribe_updates(
+        callback: impl FnMut(Arc<ZoneInfoDb>) + Send + 'static,
+    ) -> UpdateSubscription {
+        Self::subscribe_updates_with_interval(Duration::from_secs(60), callback)
+    }
+
+    /// Call `callback` with the reloaded [`ZoneInfoDb::system`] instance whenever the system
+    /// `tzdata` files change, checking every `interval`.
+    ///
+    /// A background thread polls the modification time and length of every
+    /// [`TzdataLocator::android`] and [`TzdataLocator::ohos`] path, so files appearing,
+    /// disappearing or being replaced are all noticed. If the reload fails, the callback isn't
+    /// called and the next change is waited for.
+    pub fn subscribe_updates_with_interval(
+        interval: Duration,
+        mut callback: impl FnMut(Arc<ZoneInfoDb>) + Send + 'static,
+    ) -> UpdateSubscription {
+        watch(system_paths(), interval, move || {
+            if let Ok(db) = ZoneInfoDb::reload() {
+                callback(db);
+            }
+        })
+    }
+
+    /// Call `callback` with the name and parsed data of the zone the system is set to whenever
+    /// that setting or the system `tzdata` files change, checking once a minute.
+    ///
+    /// See [`ZoneInfoDb::watch_system_timezone_with_interval`].
+    #[cfg(feature = "tzif")]
+    pub fn watch_system_timezone(
+        callback: impl FnMut(&str, ZoneInfo) + Send + 'static,
+    ) -> UpdateSubscription {
+        Self::watch_system_timezone_with_interval(Duration::from_secs(60), callback)
+    }
+
+    /// Call `callback` with the name and parsed data of the zone the system is set to whenever
+    /// that setting or the system `tzdata` files change, checking every `interval`.
+    ///
+    /// A background thread polls [`system_timezone_name`] along with the files, as
+    /// [`ZoneInfoDb::subscribe_updates_with_interval`] does. On a file change the
+    /// [`ZoneInfoDb::system`] instance is reloaded first. If no zone is set, it isn't in the
+    /// database or loading fails, the callback isn't called and the next change is waited for.
+    #[cfg(feature = "tzif")]
+    pub fn watch_system_timezone_with_interval(
+        interval: Duration,
+        callback: impl FnMut(&str, ZoneInfo) + Send + 'static,
+    ) -> UpdateSubscription {
+        let load = |changed| if changed { ZoneInfoDb::reload() } else { ZoneInfoDb::system() };
+        watch_zone(system_paths(), interval, system_timezone_name, load, callback)
+    }
+}
+
+/// The [`TzdataLocator::android`] and [`TzdataLocator::ohos`] paths.
+fn system_paths() -> Vec<PathBuf> {
+    let mut paths = TzdataLocator::android().paths().to_vec();
+    paths.extend_from_slice(TzdataLocator::ohos().paths());
+    paths
+}
+
+/// Modification time and length of each path, `None` for those that can't be read.
+fn snapshot(paths: &[PathBuf]) -> Vec<Option<(SystemTime, u64)>> {
+    let state = |path| {
+        let metadata = fs::metadata(path).ok()?;
+        Some((metadata.modified().ok()?, metadata.len()))
+    };
+    paths.iter().map(state).collect()
+}
+
+/// Call `on_change` from a background thread whenever the snapshot of `paths` changes.
+fn watch(
+    paths: Vec<PathBuf>,
+    interval: Duration,
+    mut on_change: impl FnMut() + Send + 'static,
+) -> UpdateSubscription {
+    poll(
+        interval,
+        move || snapshot(&paths),
+        move |_, _| {
+            trace!(info!("system tzdata changed"));
+            on_change();
+        },
+    )
+}
+
+/// Call `callback` with the zone named by `name` in the database `load` returns whenever the
+/// name or the snapshot of `paths` changes. `load` is told whether the files changed.
+#[cfg(feature = "tzif")]
+fn watch_zone(
+    paths: Vec<PathBuf>,
+    interval: Duration,
+    mut name: impl FnMut() -> Option<String> + Send + 'static,
+    mut load: impl FnMut(bool) -> Result<Arc<ZoneInfoDb>> + Send + 'static,
+    mut callback: impl FnMut(&str, ZoneInfo) + Send + 'static,
+) -> UpdateSubscription {
+    let state = move || (snapshot(&paths), name());
+    poll(interval, state, move |(last_files, _), (files, name)| {
+        trace!(info!(zone = ?name, "system timezone or tzdata changed"));
+        let Some(name) = name else { return };
+        let Ok(db) = load(files != last_files) else { return };
+        if let Ok(Some((_, zone_info))) = db.default_zone_for(name) {
+            callback(name, zone_info);
+        }
+    })
+}
+
+/// Call `on_change` with the previous and current value from a background thread whenever the
+/// value `state` returns changes.
+fn poll<S: PartialEq + Send + 'static>(
+    interval: Duration,
+    mut state: impl FnMut() -> S + Send + 'static,
+    mut on_change: impl FnMut(&S, &S) + Send + 'static,
+) -> UpdateSubscription {
+    let (stop, stopped) = mpsc::channel();
+    thread::spawn(move || {
+        let mut last = state();
+        // Wakes up on timeout only; the sender is never used, so dropping it ends the loop.
+        while let Err(mpsc::RecvTimeoutError::Timeout) = stopped.recv_timeout(interval) {
+            let current = state();
+            if current != last {
+                on_change(&last, &current);
+                last = current;
+            }
+        }
+    });
+    UpdateSubscription { _stop: stop }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_watch() {
+        let dir = std::env::temp_dir().join(format!("zoneinfo-db-watch-{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("tzdata");
+        let (changed, changes) = mpsc::channel();
+        let subscription =
+            watch(vec![path.clone()], Duration::from_millis(10), move || changed.send(()).unwrap());
+        let timeout = Duration::from_secs(5);
+
+        // A file appearing is a change.
+        thread::sleep(Duration::from_millis(50));
+        fs::copy("./tests/ohos/tzdata", &path).unwrap();
+        assert!(changes.recv_timeout(timeout).is_ok());
+
+        fs::copy("./tests/android/tzdata", &path).unwrap();
+        assert!(changes.recv_timeout(timeout).is_ok());
+
+        // Dropping the subscription ends the thread, dropping its end of the channel.
+        drop(subscription);
+        let ended = changes.recv_timeout(timeout);
+        fs::remove_dir_all(&dir).unwrap();
+        assert_eq!(ended, Err(mpsc::RecvTimeoutError::Disconnected));
+    }
+
+    #[cfg(feature = "tzif")]
+    #[test]
+    fn test_watch_zone() {
+        use std::{fs::File, sync::Mutex};
+
+        let dir =
+            std::env::temp_dir().join(format!("zoneinfo-db-watch-zone-{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("tzdata");
+        fs::copy("./tests/android/tzdata", &path).unwrap();
+        let zone = Arc::new(Mutex::new(Some("Asia/Shanghai".to_string())));
+        let reloads = Arc::new(Mutex::new(0));
+        let (changed, changes) = mpsc::channel();
+        let subscription = watch_zone(
+            vec![path.clone()],
+            Duration::from_millis(10),
+            {
+                let zone = zone.clone();
+                move || zone.lock().unwrap().clone()
+            },
+            {
+                let reloads = reloads.clone();
+                move |changed| {
+                    *reloads.lock().unwrap() += changed as usize;
+                    Ok(Arc::new(ZoneInfoDb::from_bytes(fs::read(&path)?)?))
+                }
+            },
+            move |name, zone_info| changed.send((name.to_string(), zone_info)).unwrap(),
+        );
+        let timeout = Duration::from_secs(5);
+        let db = ZoneInfoDb::new_android(File::open("./tests/android/tzdata").unwrap()).unwrap();
+
+        // A zone missing from the database isn't reported.
+        thread::sleep(Duration::from_millis(50));
+        *zone.lock().unwrap() = Some("Asia/Nowhere".into());
+        thread::sleep(Duration::from_millis(50));
+        *zone.lock().unwrap() = Some("America/New_York".into());
+        let (name, zone_info) = changes.recv_timeout(timeout).unwrap();
+        assert_eq!(name, "America/New_York");
+        assert_eq!(zone_info, db.zone_info(b"America/New_York").unwrap().unwrap());
+        assert_eq!(*reloads.lock().unwrap(), 0);
+
+        // The same zone is reported again when the tzdata file changes.
+        fs::copy("./tests/ohos/tzdata", dir.join("tzdata")).unwrap();
+        let (name, _) = changes.recv_timeout(timeout).unwrap();
+        assert_eq!(name, "America/New_York");
+        assert_eq!(*reloads.lock().unwrap(), 1);
+
+        drop(subscription);
+        let ended = changes.recv_timeout(timeout);
+        fs::remove_dir_all(&dir).unwrap();
+        assert!(matches!(ended, Err(mpsc::RecvTimeoutError::Disconnected)));
+    }
+}
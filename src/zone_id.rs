@@ -0,0 +1,70 @@
+//! Syntax checks of zone IDs, independent of any `tzdata` file.
+use crate::SIZEOF_TZNAME;
+#[cfg(feature = "std")]
+use crate::ZoneInfoDb;
+
+/// Longest component of a zone ID the tzdb allows, e.g. `ComodRivadavia`.
+const MAX_COMPONENT_LEN: usize = 14;
+
+/// Whether a name follows the tzdb's rules for zone IDs, so it could name a zone of some
+/// `tzdata` file.
+///
+/// Components separated by `/` must be 1 to 14 ASCII letters, digits, `.`, `_`, `-` or `+`, and
+/// not start with `.` or `-`; the whole name must fit the 40-byte name slot of an index entry.
+/// This only checks the syntax, see [`ZoneInfoDb::contains`] for whether a zone exists.
+pub fn is_valid_zone_id(name: &[u8]) -> bool {
+    let is_valid_component = |component: &[u8]| {
+        (1..=MAX_COMPONENT_LEN).contains(&component.len())
+            && !component.starts_with(b".")
+            && !component.starts_with(b"-")
+            && component.iter().all(|&x| x.is_ascii_alphanumeric() || b"._-+".contains(&x))
+    };
+    name.len() < SIZEOF_TZNAME && name.split(|&x| x == b'/').all(is_valid_component)
+}
+
+#[cfg(feature = "std")]
+impl ZoneInfoDb {
+    /// Whether the database has a zone named `tz_name`, rejecting invalid IDs without searching
+    /// the index, see [`is_valid_zone_id`].
+    pub fn contains(&self, tz_name: &[u8]) -> bool {
+        is_valid_zone_id(tz_name) && self.indexes().find_timezone(tz_name).is_some()
+    }
+}
+
+#[cfg(all(test, feature = "std"))]
+mod tests {
+    use std::fs::File;
+
+    use super::*;
+
+    #[test]
+    fn test_is_valid_zone_id() {
+        let android =
+            ZoneInfoDb::new_android(File::open("./tests/android/tzdata").unwrap()).unwrap();
+        let ohos = ZoneInfoDb::new_ohos(File::open("./tests/ohos/tzdata").unwrap()).unwrap();
+        for db in [&android, &ohos] {
+            for index in db.indexes().timezones() {
+                assert!(is_valid_zone_id(&index.name), "{:?}", index);
+                assert!(db.contains(&index.name));
+            }
+        }
+        for name in [
+            &b""[..],
+            b"/",
+            b"Asia/",
+            b"/Asia/Shanghai",
+            b"Asia//Shanghai",
+            b"../etc/passwd",
+            b"Asia/.hidden",
+            b"-Asia",
+            b"Asia/Shang hai",
+            b"Asia/Shanghai\0",
+            b"America/Argentina/Comod_Rivadavia",
+            b"Europe/Z\xc3\xbcrich",
+        ] {
+            assert!(!is_valid_zone_id(name), "{}", name.escape_ascii());
+        }
+        assert!(!android.contains(b"Asia/Nowhere"));
+        assert!(!android.contains(b"asia/shanghai"));
+    }
+}
@@ -0,0 +1,591 @@
+//! Local time queries over the decoded data of a zone.
+use alloc::{string::String, vec::Vec};
+use core::{fmt::Write, ops::RangeInclusive};
+
+#[cfg(feature = "std")]
+use crate::ZoneInfoDb;
+use crate::{LeapSecond, LocalTimeType, Result, TzIf, ZoneInfoError, posix::PosixTz};
+
+/// Offset from UTC in effect at an instant.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct UtcOffset<'a> {
+    /// Offset from UTC in seconds, east positive.
+    pub seconds: i32,
+    pub is_dst: bool,
+    /// Abbreviation of the local time, e.g. `CST`.
+    pub abbreviation: &'a str,
+}
+
+/// A change of the offset from UTC, or of whether it's daylight saving time or its
+/// abbreviation.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct OffsetTransition<'a> {
+    /// Unix timestamp at which `after` takes effect.
+    pub time: i64,
+    #[cfg_attr(feature = "serde", serde(borrow))]
+    pub before: UtcOffset<'a>,
+    #[cfg_attr(feature = "serde", serde(borrow))]
+    pub after: UtcOffset<'a>,
+}
+
+/// A transition with the wall clock times on either side, for telling people about it, see
+/// [`ZoneInfo::upcoming_changes`].
+///
+/// Displays as e.g. `2024-03-10 02:00:00 EST -> 2024-03-10 03:00:00 EDT (+01:00)`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct OffsetChange<'a> {
+    #[cfg_attr(feature = "serde", serde(borrow))]
+    pub transition: OffsetTransition<'a>,
+    /// Local time the clocks reach just as they change, in seconds since 1970-01-01 00:00:00
+    /// local time, e.g. 02:00 when they spring forward.
+    pub local_before: i64,
+    /// Local time the clocks show from the change on, e.g. 03:00 when they spring forward.
+    pub local_after: i64,
+    /// Seconds the clocks move, positive when they go forward.
+    pub delta: i32,
+}
+
+impl<'a> From<OffsetTransition<'a>> for OffsetChange<'a> {
+    fn from(transition: OffsetTransition<'a>) -> Self {
+        let OffsetTransition { time, before, after } = transition;
+        Self {
+            transition,
+            local_before: time + i64::from(before.seconds),
+            local_after: time + i64::from(after.seconds),
+            delta: after.seconds - before.seconds,
+        }
+    }
+}
+
+impl core::fmt::Display for OffsetChange<'_> {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        let OffsetTransition { before, after, .. } = self.transition;
+        write!(
+            f,
+            "{} {} -> {} {} ({})",
+            DateTime(self.local_before),
+            before.abbreviation,
+            DateTime(self.local_after),
+            after.abbreviation,
+            Offset(self.delta)
+        )
+    }
+}
+
+/// The local time rules of a zone: its transitions, and the POSIX TZ footer extrapolating past
+/// the last one.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ZoneInfo {
+    tzif: TzIf,
+    posix: Option<PosixTz>,
+}
+
+impl ZoneInfo {
+    /// Decode the TZif data of a zone.
+    pub fn new(tzif: &[u8]) -> Result<Self> {
+        Self::from_tzif(TzIf::new(tzif)?)
+    }
+
+    /// Wrap already decoded TZif data, parsing its footer.
+    ///
+    /// As the fields of [`TzIf`] are public, the same checks as [`TzIf::new`] are done again:
+    /// there must be a local time type, and transitions must be ascending and refer to existing
+    /// types.
+    pub fn from_tzif(tzif: TzIf) -> Result<Self> {
+        if tzif.local_time_types.is_empty() {
+            return Err(ZoneInfoError::InvalidTzif("no local time types"));
+        }
+        if tzif.transitions.iter().any(|x| x.local_time_type >= tzif.local_time_types.len()) {
+            return Err(ZoneInfoError::InvalidTzif("transition type out of range"));
+        }
+        if !tzif.transitions.is_sorted_by(|a, b| a.time < b.time) {
+            return Err(ZoneInfoError::InvalidTzif("transitions are not in ascending order"));
+        }
+        let posix = match tzif.footer.as_deref() {
+            None | Some("") => None,
+            Some(footer) => Some(
+                PosixTz::new(footer)
+                    .ok_or(ZoneInfoError::InvalidTzif("invalid POSIX TZ string footer"))?,
+            ),
+        };
+        Ok(Self { tzif, posix })
+    }
+
+    /// The decoded TZif data.
+    pub fn tzif(&self) -> &TzIf {
+        &self.tzif
+    }
+
+    /// Leap second records of the zone, empty unless it's of the `right/` timescale, whose
+    /// timestamps count leap seconds.
+    pub fn leap_seconds(&self) -> &[LeapSecond] {
+        &self.tzif.leap_seconds
+    }
+
+    /// Serialize the zone as minimal version 3 TZif data, see [`TzIf::to_bytes_v3`], e.g. to
+    /// normalize vendor data or shrink custom builds.
+    pub fn to_tzif_v3(&self) -> Result<Vec<u8>> {
+        self.tzif.to_bytes_v3()
+    }
+
+    /// Offset from UTC at a Unix timestamp.
+    ///
+    /// Before the first transition, the first local time type applies. From the last transition
+    /// on, or at any time if there are none, the footer's rules apply when present.
+    pub fn offset_at(&self, unix_seconds: i64) -> UtcOffset<'_> {
+        let transitions = &self.tzif.transitions;
+        if let Some(posix) = &self.posix
+            && transitions.last().is_none_or(|last| unix_seconds >= last.time)
+        {
+            let (local_type, is_dst) = posix.local_type_at(unix_seconds);
+            return UtcOffset {
+                seconds: local_type.utoff,
+                is_dst,
+                abbreviation: &local_type.abbreviation,
+            };
+        }
+        let local_type = match transitions.partition_point(|x| x.time <= unix_seconds) {
+            0 => 0,
+            i => transitions[i - 1].local_time_type,
+        };
+        let local_type = &self.tzif.local_time_types[local_type];
+        UtcOffset {
+            seconds: local_type.utoff,
+            is_dst: local_type.is_dst,
+            abbreviation: &local_type.abbreviation,
+        }
+    }
+
+    /// Offset from UTC of standard time in seconds, like Android's `ZoneInfo.getRawOffset`
+    /// (which is in milliseconds).
+    ///
+    /// That's the footer's standard time, or else the latest non-DST local time type.
+    pub fn raw_offset(&self) -> i32 {
+        if let Some(posix) = &self.posix {
+            return posix.std.utoff;
+        }
+        self.latest_type(false).map_or(self.tzif.local_time_types[0].utoff, |x| x.utoff)
+    }
+
+    /// Seconds that daylight saving time adds to the raw offset, like Android's
+    /// `ZoneInfo.getDSTSavings`, or 0 if the zone doesn't use it any more.
+    ///
+    /// That's from the footer's daylight saving time, or else the latest DST local time type.
+    pub fn dst_savings(&self) -> i32 {
+        match &self.posix {
+            Some(posix) => posix.dst.as_ref().map_or(0, |(dst, _)| dst.utoff - posix.std.utoff),
+            None => self.latest_type(true).map_or(0, |x| x.utoff - self.raw_offset()),
+        }
+    }
+
+    /// Whether daylight saving time is observed at or after a Unix timestamp, like Android's
+    /// `ZoneInfo.useDaylightTime` given the current time.
+    pub fn uses_dst(&self, from: i64) -> bool {
+        if self.posix.as_ref().is_some_and(|x| x.dst.is_some()) {
+            return true;
+        }
+        let transitions = &self.tzif.transitions;
+        self.offset_at(from).is_dst
+            || transitions[transitions.partition_point(|x| x.time <= from)..]
+                .iter()
+                .any(|x| self.tzif.local_time_types[x.local_time_type].is_dst)
+    }
+
+    /// Whether two zones have the same local time, i.e. the same offset, DST flag and
+    /// abbreviation, at every instant from a Unix timestamp on.
+    pub fn same_rules_since(&self, other: &ZoneInfo, since: i64) -> bool {
+        if self.offset_at(since) != other.offset_at(since) {
+            return false;
+        }
+        // Past both zones' explicit transitions, only the footers apply.
+        let last = |x: &ZoneInfo| x.tzif.transitions.last().map_or(i64::MIN, |x| x.time);
+        let end = last(self).max(last(other));
+        let mut time = since;
+        loop {
+            let next = self.next_transition(time);
+            if next != other.next_transition(time) {
+                return false;
+            }
+            match next {
+                None => return true,
+                Some(next) if next.time > end => return self.posix == other.posix,
+                Some(next) => time = next.time,
+            }
+        }
+    }
+
+    /// A table of the zone's transitions for reading by people: the UTC and local time of each,
+    /// the offset before and after, and the abbreviation and DST flag after, followed by the
+    /// footer's rules, e.g.
+    ///
+    /// ```text
+    /// UTC                  local time           before     after      abbr    dst
+    /// 1900-12-31 15:54:17  1900-12-31 23:54:17  +08:05:43  +08:00     CST     0
+    /// 1919-04-12 16:00:00  1919-04-13 01:00:00  +08:00     +09:00     CDT     1
+    /// 1919-09-30 15:00:00  1919-09-30 23:00:00  +09:00     +08:00     CST     0
+    /// footer: CST-8
+    /// ```
+    ///
+    /// Transitions that change nothing are skipped.
+    pub fn describe(&self) -> String {
+        let mut out = String::new();
+        let _ = writeln!(
+            out,
+            "{:<19}  {:<19}  {:<9}  {:<9}  {:<6}  dst",
+            "UTC", "local time", "before", "after", "abbr"
+        );
+        for transition in self.tzif.transitions.iter().filter_map(|x| self.transition_at(x.time)) {
+            let OffsetTransition { time, before, after } = transition;
+            let _ = writeln!(
+                out,
+                "{}  {}  {:<9}  {:<9}  {:<6}  {}",
+                DateTime(time),
+                DateTime(time + i64::from(after.seconds)),
+                Offset(before.seconds),
+                Offset(after.seconds),
+                after.abbreviation,
+                u8::from(after.is_dst),
+            );
+        }
+        if let Some(footer) = self.tzif.footer.as_deref().filter(|x| !x.is_empty()) {
+            let _ = writeln!(out, "footer: {footer}");
+        }
+        out
+    }
+
+    /// Local time type of the latest transition to a type with the given DST flag.
+    fn latest_type(&self, is_dst: bool) -> Option<&LocalTimeType> {
+        self.tzif
+            .transitions
+            .iter()
+            .rev()
+            .map(|x| &self.tzif.local_time_types[x.local_time_type])
+            .find(|x| x.is_dst == is_dst)
+    }
+
+    /// The first transition strictly after a Unix timestamp, including those the footer's rules
+    /// extrapolate. Transitions that change nothing are skipped.
+    pub fn next_transition(&self, after: i64) -> Option<OffsetTransition<'_>> {
+        let transitions = &self.tzif.transitions;
+        let explicit = &transitions[transitions.partition_point(|x| x.time <= after)..];
+        let mut footer = self.footer_transitions(after, 0..=2);
+        footer.sort_unstable();
+        explicit
+            .iter()
+            .map(|x| x.time)
+            .chain(footer.into_iter().filter(|&x| x > after))
+            .find_map(|time| self.transition_at(time))
+    }
+
+    /// The transitions strictly after a Unix timestamp with their wall clock times, in order,
+    /// e.g. `upcoming_changes(now).next()` for a "clocks change this Sunday" notice.
+    ///
+    /// The footer's rules may extrapolate transitions without end, so bound the iterator, e.g.
+    /// with [`Iterator::take`].
+    pub fn upcoming_changes(&self, after: i64) -> impl Iterator<Item = OffsetChange<'_>> {
+        core::iter::successors(self.next_transition(after), |x| self.next_transition(x.time))
+            .map(OffsetChange::from)
+    }
+
+    /// The last transition strictly before a Unix timestamp, including those the footer's rules
+    /// extrapolate. Transitions that change nothing are skipped.
+    pub fn previous_transition(&self, before: i64) -> Option<OffsetTransition<'_>> {
+        let transitions = &self.tzif.transitions;
+        let explicit = &transitions[..transitions.partition_point(|x| x.time < before)];
+        let mut footer = self.footer_transitions(before, -2..=0);
+        footer.sort_unstable_by(|a, b| b.cmp(a));
+        footer
+            .into_iter()
+            .filter(|&x| x < before)
+            .chain(explicit.iter().rev().map(|x| x.time))
+            .find_map(|time| self.transition_at(time))
+    }
+
+    /// Transitions of the footer's rules after the last explicit transition, in the years `years`
+    /// relative to that of `time` or of the last explicit transition, whichever is later.
+    fn footer_transitions(&self, time: i64, years: RangeInclusive<i64>) -> Vec<i64> {
+        let Some(posix) = &self.posix else {
+            return Vec::new();
+        };
+        let start = self.tzif.transitions.last().map_or(i64::MIN, |x| x.time);
+        let year = posix.year_at(time.max(start));
+        years
+            .filter_map(|x| posix.transitions_in(year.checked_add(x)?))
+            .flatten()
+            .filter_map(|x| i64::try_from(x).ok())
+            .filter(|&x| x > start)
+            .collect()
+    }
+
+    /// The transition at a Unix timestamp, if the offset changes there.
+    fn transition_at(&self, time: i64) -> Option<OffsetTransition<'_>> {
+        let (before, after) = (self.offset_at(time.checked_sub(1)?), self.offset_at(time));
+        (before != after).then_some(OffsetTransition { time, before, after })
+    }
+}
+
+/// Formats seconds since the epoch as `YYYY-MM-DD hh:mm:ss`.
+struct DateTime(i64);
+
+impl core::fmt::Display for DateTime {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        let (year, month, day) = crate::posix::civil_from_days(self.0.div_euclid(86400));
+        let seconds = self.0.rem_euclid(86400);
+        let (hours, minutes, seconds) = (seconds / 3600, seconds / 60 % 60, seconds % 60);
+        write!(f, "{year:04}-{month:02}-{day:02} {hours:02}:{minutes:02}:{seconds:02}")
+    }
+}
+
+/// Formats an offset from UTC in seconds as `±hh:mm`, or `±hh:mm:ss` if it has seconds.
+struct Offset(i32);
+
+impl core::fmt::Display for Offset {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        let sign = if self.0 < 0 { '-' } else { '+' };
+        let seconds = self.0.unsigned_abs();
+        let (hours, minutes, seconds) = (seconds / 3600, seconds / 60 % 60, seconds % 60);
+        let text = match seconds {
+            0 => alloc::format!("{sign}{hours:02}:{minutes:02}"),
+            _ => alloc::format!("{sign}{hours:02}:{minutes:02}:{seconds:02}"),
+        };
+        f.pad(&text)
+    }
+}
+
+#[cfg(feature = "std")]
+impl ZoneInfoDb {
+    /// Decode the data of a zone, or `None` if it isn't in the database.
+    pub fn zone_info(&self, tz_name: &[u8]) -> Result<Option<ZoneInfo>> {
+        self.get(tz_name).map(ZoneInfo::new).transpose()
+    }
+
+    /// Names of the zones with the same local time as `tz_name` from a Unix timestamp on,
+    /// including `tz_name` itself, in index order, or `None` if it isn't in the database.
+    ///
+    /// See [`ZoneInfo::same_rules_since`].
+    pub fn equivalent_zones(&self, tz_name: &[u8], since: i64) -> Result<Option<Vec<&[u8]>>> {
+        let Some(zone) = self.zone_info(tz_name)? else {
+            return Ok(None);
+        };
+        let mut names = Vec::new();
+        for (name, tzif) in self.iter() {
+            if ZoneInfo::new(tzif)?.same_rules_since(&zone, since) {
+                names.push(name);
+            }
+        }
+        Ok(Some(names))
+    }
+
+    /// Whether any zone has leap second records, i.e. the database was built from the `right/`
+    /// zones rather than the POSIX ones. Only the TZif headers are read.
+    pub fn uses_leap_seconds(&self) -> bool {
+        self.iter().any(|(_, tzif)| crate::tzif::leap_second_count(tzif).is_ok_and(|x| x > 0))
+    }
+}
+
+#[cfg(all(test, feature = "std"))]
+mod tests {
+    use std::fs::File;
+
+    use super::*;
+
+    #[test]
+    fn test_from_tzif_checks() {
+        let db = ZoneInfoDb::new_android(File::open("./tests/android/tzdata").unwrap()).unwrap();
+        let tzif = db.zone_info(b"America/New_York").unwrap().unwrap().tzif().clone();
+        assert!(ZoneInfo::from_tzif(tzif.clone()).is_ok());
+        let invalid = |edit: fn(&mut TzIf)| {
+            let mut tzif = tzif.clone();
+            edit(&mut tzif);
+            match ZoneInfo::from_tzif(tzif) {
+                Err(ZoneInfoError::InvalidTzif(reason)) => reason,
+                x => panic!("{x:?}"),
+            }
+        };
+        assert_eq!(invalid(|x| x.local_time_types.clear()), "no local time types");
+        assert_eq!(
+            invalid(|x| x.transitions[3].local_time_type = 99),
+            "transition type out of range"
+        );
+        assert_eq!(invalid(|x| x.transitions.swap(3, 4)), "transitions are not in ascending order");
+    }
+
+    #[test]
+    fn test_next_and_previous_transition() {
+        let db = ZoneInfoDb::new_android(File::open("./tests/android/tzdata").unwrap()).unwrap();
+        let new_york = db.zone_info(b"America/New_York").unwrap().unwrap();
+        let est = UtcOffset { seconds: -5 * 3600, is_dst: false, abbreviation: "EST" };
+        let edt = UtcOffset { seconds: -4 * 3600, is_dst: true, abbreviation: "EDT" };
+        // 2024-01-01: 2024-03-10 and 2023-11-05.
+        let next = new_york.next_transition(1704067200).unwrap();
+        assert_eq!(next, OffsetTransition { time: 1710054000, before: est, after: edt });
+        let previous = new_york.previous_transition(1704067200).unwrap();
+        assert_eq!(previous, OffsetTransition { time: 1699164000, before: edt, after: est });
+        assert_eq!(new_york.next_transition(1699164000 - 1).unwrap().time, 1699164000);
+        // 2023-03-12.
+        assert_eq!(new_york.previous_transition(1699164000).unwrap().time, 1678604400);
+
+        // 2100-01-01, extrapolated from the footer: 2100-03-14 and 2099-11-01.
+        assert_eq!(new_york.next_transition(4102444800).unwrap().time, 4108690800);
+        assert_eq!(new_york.previous_transition(4102444800).unwrap().time, 4097196000);
+
+        let shanghai = db.zone_info(b"Asia/Shanghai").unwrap().unwrap();
+        assert_eq!(shanghai.next_transition(1704067200), None);
+        let previous = shanghai.previous_transition(1704067200).unwrap();
+        assert_eq!(previous.after.abbreviation, "CST");
+        assert!(previous.before.is_dst);
+        assert_eq!(shanghai.previous_transition(i64::MIN), None);
+    }
+
+    #[test]
+    fn test_upcoming_changes() {
+        let db = ZoneInfoDb::new_android(File::open("./tests/android/tzdata").unwrap()).unwrap();
+        let new_york = db.zone_info(b"America/New_York").unwrap().unwrap();
+        // 2024-01-01: 2024-03-10 and 2024-11-03.
+        let changes: Vec<_> = new_york.upcoming_changes(1704067200).take(3).collect();
+        assert_eq!(changes.len(), 3);
+        assert_eq!(changes[0].transition, new_york.next_transition(1704067200).unwrap());
+        assert_eq!((changes[0].local_before, changes[0].local_after), (1710036000, 1710039600));
+        assert_eq!(changes[0].delta, 3600);
+        assert_eq!(
+            changes[0].to_string(),
+            "2024-03-10 02:00:00 EST -> 2024-03-10 03:00:00 EDT (+01:00)"
+        );
+        assert_eq!(changes[1].transition.time, 1730613600);
+        assert_eq!(changes[1].delta, -3600);
+        assert_eq!(
+            changes[1].to_string(),
+            "2024-11-03 02:00:00 EDT -> 2024-11-03 01:00:00 EST (-01:00)"
+        );
+        // 2025-03-09.
+        assert_eq!(changes[2].transition.time, 1741503600);
+
+        let shanghai = db.zone_info(b"Asia/Shanghai").unwrap().unwrap();
+        assert_eq!(shanghai.upcoming_changes(1704067200).next(), None);
+    }
+
+    #[test]
+    fn test_equivalent_zones() {
+        let db = ZoneInfoDb::new_android(File::open("./tests/android/tzdata").unwrap()).unwrap();
+        // 2000-01-01.
+        let zones = db.equivalent_zones(b"Asia/Shanghai", 946684800).unwrap().unwrap();
+        assert!(zones.contains(&&b"Asia/Shanghai"[..]));
+        assert!(zones.contains(&&b"Asia/Chongqing"[..]));
+        assert!(zones.contains(&&b"Asia/Taipei"[..]));
+        // Same offset, but a different abbreviation.
+        assert!(!zones.contains(&&b"Asia/Hong_Kong"[..]));
+
+        // Detroit and Toronto only differed from New York in the 1970s and earlier.
+        let since_2000 = db.equivalent_zones(b"America/New_York", 946684800).unwrap().unwrap();
+        assert!(since_2000.contains(&&b"America/Detroit"[..]));
+        assert!(since_2000.contains(&&b"America/Toronto"[..]));
+        assert!(!since_2000.contains(&&b"America/Chicago"[..]));
+        let since_1970 = db.equivalent_zones(b"America/New_York", 0).unwrap().unwrap();
+        assert_eq!(since_1970, [&b"America/New_York"[..], b"EST5EDT", b"US/Eastern"]);
+        assert_eq!(db.equivalent_zones(b"Mars/Olympus_Mons", 0).unwrap(), None);
+    }
+
+    #[test]
+    fn test_describe() {
+        let db = ZoneInfoDb::new_android(File::open("./tests/android/tzdata").unwrap()).unwrap();
+        let shanghai = db.zone_info(b"Asia/Shanghai").unwrap().unwrap().describe();
+        let lines: Vec<_> = shanghai.lines().collect();
+        assert_eq!(
+            lines[0],
+            "UTC                  local time           before     after      abbr    dst"
+        );
+        assert_eq!(
+            lines[1],
+            "1900-12-31 15:54:17  1900-12-31 23:54:17  +08:05:43  +08:00     CST     0"
+        );
+        assert_eq!(
+            lines[2],
+            "1919-04-12 16:00:00  1919-04-13 01:00:00  +08:00     +09:00     CDT     1"
+        );
+        assert_eq!(
+            lines[lines.len() - 2],
+            "1991-09-14 17:00:00  1991-09-15 01:00:00  +09:00     +08:00     CST     0"
+        );
+        assert_eq!(lines[lines.len() - 1], "footer: CST-8");
+
+        let new_york = db.zone_info(b"America/New_York").unwrap().unwrap().describe();
+        assert!(new_york.contains(
+            "\n1883-11-18 17:00:00  1883-11-18 12:00:00  -04:56:02  -05:00     EST     0\n"
+        ));
+        assert!(new_york.ends_with("EST     0\nfooter: EST5EDT,M3.2.0,M11.1.0\n"));
+    }
+
+    #[test]
+    fn test_raw_offset_and_dst() {
+        let db = ZoneInfoDb::new_android(File::open("./tests/android/tzdata").unwrap()).unwrap();
+        let new_york = db.zone_info(b"America/New_York").unwrap().unwrap();
+        assert_eq!(new_york.raw_offset(), -5 * 3600);
+        assert_eq!(new_york.dst_savings(), 3600);
+        assert!(new_york.uses_dst(1704067200));
+
+        let shanghai = db.zone_info(b"Asia/Shanghai").unwrap().unwrap();
+        assert_eq!(shanghai.raw_offset(), 8 * 3600);
+        assert_eq!(shanghai.dst_savings(), 0);
+        assert!(!shanghai.uses_dst(1704067200));
+        // Daylight saving time ended on 1991-09-15.
+        assert!(shanghai.uses_dst(662688000));
+
+        let lord_howe = db.zone_info(b"Australia/Lord_Howe").unwrap().unwrap();
+        assert_eq!(lord_howe.raw_offset(), 10 * 3600 + 1800);
+        assert_eq!(lord_howe.dst_savings(), 1800);
+    }
+
+    #[test]
+    fn test_offset_at() {
+        let db = ZoneInfoDb::new_android(File::open("./tests/android/tzdata").unwrap()).unwrap();
+        let shanghai = db.zone_info(b"Asia/Shanghai").unwrap().unwrap();
+        // 1991-09-15, the last transition, and 2024-07-01.
+        let cst = UtcOffset { seconds: 8 * 3600, is_dst: false, abbreviation: "CST" };
+        assert_eq!(shanghai.offset_at(684867600), cst);
+        assert_eq!(shanghai.offset_at(1719792000), cst);
+        // 1990-07-01, during daylight saving time.
+        assert_eq!(
+            shanghai.offset_at(646790400),
+            UtcOffset { seconds: 9 * 3600, is_dst: true, abbreviation: "CDT" }
+        );
+        assert_eq!(shanghai.offset_at(i64::MIN).abbreviation, "LMT");
+
+        let new_york = db.zone_info(b"America/New_York").unwrap().unwrap();
+        // 2024-07-01 and 2124-07-01, the latter past any transition in the file.
+        for time in [1719792000, 4875465600] {
+            assert_eq!(
+                new_york.offset_at(time),
+                UtcOffset { seconds: -4 * 3600, is_dst: true, abbreviation: "EDT" }
+            );
+        }
+        assert_eq!(new_york.offset_at(1704067200).abbreviation, "EST");
+        assert_eq!(db.zone_info(b"Asia/Nowhere").unwrap(), None);
+    }
+
+    #[test]
+    fn test_footer_matches_last_transition() {
+        // RFC 8536 requires the footer to agree with the local time type of the last transition.
+        for db in [
+            ZoneInfoDb::new_android(File::open("./tests/android/tzdata").unwrap()).unwrap(),
+            ZoneInfoDb::new_ohos(File::open("./tests/ohos/tzdata").unwrap()).unwrap(),
+        ] {
+            for index in db.indexes().timezones() {
+                let zone_info = ZoneInfo::new(db.tzdata(index).unwrap()).unwrap();
+                let tzif = zone_info.tzif();
+                let (Some(posix), Some(last)) = (&zone_info.posix, tzif.transitions.last()) else {
+                    continue;
+                };
+                let expected = &tzif.local_time_types[last.local_time_type];
+                let (actual, is_dst) = posix.local_type_at(last.time);
+                assert_eq!(
+                    (actual.utoff, is_dst, actual.abbreviation.as_str()),
+                    (expected.utoff, expected.is_dst, expected.abbreviation.as_str()),
+                    "{index:?}"
+                );
+            }
+        }
+    }
+}
@@ -0,0 +1,72 @@
+//! What the index and zonetab say about a zone, without reading its TZif data.
+use alloc::string::String;
+
+use crate::{ZoneInfoDb, entry_range};
+
+/// Metadata of a zone, see [`ZoneInfoDb::zone_meta`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct ZoneMeta {
+    /// Size of the TZif data in bytes.
+    pub size: u32,
+    /// Offset of the TZif data from the start of the file.
+    pub offset: u64,
+    /// See [`TzDataIndex::raw_utc_offset`](crate::TzDataIndex::raw_utc_offset).
+    pub raw_utc_offset: Option<i32>,
+    /// First country of the zone's zonetab row, e.g. `CN`.
+    pub country: Option<String>,
+    /// The zonetab comment, e.g. `Beijing Time`.
+    pub comment: Option<String>,
+}
+
+impl ZoneInfoDb {
+    /// Metadata of a zone from its index entry and zonetab row, or `None` if it isn't in the
+    /// index.
+    ///
+    /// The TZif data isn't read. If the zonetab is missing or fails to parse, `country` and
+    /// `comment` are `None`.
+    pub fn zone_meta(&self, tz_name: &[u8]) -> Option<ZoneMeta> {
+        let index = self.indexes.find_timezone(tz_name)?;
+        let row = self.zone_tab().ok().and_then(|x| x.find_zone(tz_name));
+        Some(ZoneMeta {
+            size: index.length,
+            offset: entry_range(&self.header, index).start,
+            raw_utc_offset: index.raw_utc_offset,
+            country: row.and_then(|x| x.countries.first().cloned()),
+            comment: row.and_then(|x| x.comment.clone()),
+        })
+    }
+}
+
+#[cfg(all(test, feature = "builder"))]
+mod tests {
+    use std::fs::File;
+
+    use super::*;
+    use crate::{TzDataBuilder, TzDataFlavor};
+
+    #[test]
+    fn test_zone_meta() {
+        let db = ZoneInfoDb::new_android(File::open("./tests/android/tzdata").unwrap()).unwrap();
+        let meta = db.zone_meta(b"Asia/Shanghai").unwrap();
+        assert_eq!(meta.size as usize, db.get(b"Asia/Shanghai").unwrap().len());
+        let range = meta.offset as usize..meta.offset as usize + meta.size as usize;
+        assert_eq!(
+            std::fs::read("./tests/android/tzdata").unwrap()[range],
+            *db.get(b"Asia/Shanghai").unwrap()
+        );
+        assert_eq!(meta.raw_utc_offset, Some(0));
+        assert_eq!((meta.country, meta.comment), (None, None));
+        assert_eq!(db.zone_meta(b"Mars/Olympus_Mons"), None);
+
+        let mut builder = TzDataBuilder::new(db.header().version);
+        builder
+            .zone("Asia/Shanghai", db.get(b"Asia/Shanghai").unwrap())
+            .zone_tab("CN\t+3114+12128\tAsia/Shanghai\tBeijing Time\n");
+        let db = ZoneInfoDb::from_bytes(builder.build(TzDataFlavor::Ohos).unwrap()).unwrap();
+        let meta = db.zone_meta(b"Asia/Shanghai").unwrap();
+        assert_eq!(meta.raw_utc_offset, None);
+        assert_eq!(meta.country.as_deref(), Some("CN"));
+        assert_eq!(meta.comment.as_deref(), Some("Beijing Time"));
+    }
+}
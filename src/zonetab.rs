@@ -0,0 +1,317 @@
+//! Parser of the zonetab section: the `zone.tab` (or `zone1970.tab`) table mapping countries to
+//! zones.
+use alloc::{
+    string::{String, ToString},
+    sync::Arc,
+    vec::Vec,
+};
+#[cfg(feature = "std")]
+use std::io::{Read, Seek, SeekFrom};
+
+use crate::{Result, TzDataIndexes, ZoneInfoError};
+#[cfg(feature = "std")]
+use crate::{TzDataHeader, ZoneInfoDb};
+
+/// ISO 6709 coordinates of a zone's principal location, in degrees.
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Coordinates {
+    /// Degrees north of the equator.
+    pub latitude: f64,
+    /// Degrees east of Greenwich.
+    pub longitude: f64,
+}
+
+impl Coordinates {
+    /// Great-circle distance to `other` in kilometres, on a spherical Earth.
+    #[cfg(feature = "std")]
+    pub fn distance_km(&self, other: &Coordinates) -> f64 {
+        const EARTH_RADIUS_KM: f64 = 6371.0;
+        let (lat1, lat2) = (self.latitude.to_radians(), other.latitude.to_radians());
+        let half_lat = (lat2 - lat1) / 2.0;
+        let half_lon = (other.longitude - self.longitude).to_radians() / 2.0;
+        let a = half_lat.sin().powi(2) + lat1.cos() * lat2.cos() * half_lon.sin().powi(2);
+        2.0 * EARTH_RADIUS_KM * a.sqrt().min(1.0).asin()
+    }
+}
+
+/// A row of the zone table.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct ZoneTabEntry {
+    /// ISO 3166 alpha-2 country codes; `zone1970.tab` rows may list several.
+    pub countries: Vec<String>,
+    pub coordinates: Coordinates,
+    pub zone: Arc<[u8]>,
+    /// Description of the zone within its country, e.g. `Pacific`, if the row has one.
+    pub comment: Option<String>,
+}
+
+/// The zone table of the `tzdata` file.
+#[derive(Debug, Clone, Default, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct ZoneTab {
+    entries: Vec<ZoneTabEntry>,
+}
+
+impl ZoneTab {
+    /// Parse the text of a zone table.
+    ///
+    /// Lines starting with `#` and blank lines are skipped. Every other line must be
+    /// `codes<TAB>coordinates<TAB>zone`, optionally followed by a tab and comments.
+    pub fn new(text: &[u8]) -> Result<Self> {
+        let text = core::str::from_utf8(text)
+            .map_err(|_| ZoneInfoError::InvalidZoneTab("text is not UTF-8"))?;
+        let entries = text
+            .lines()
+            .filter(|line| !line.is_empty() && !line.starts_with('#'))
+            .map(|line| {
+                let mut fields = line.split('\t');
+                let (Some(countries), Some(coordinates), Some(zone)) =
+                    (fields.next(), fields.next(), fields.next())
+                else {
+                    return Err(ZoneInfoError::InvalidZoneTab("line has fewer than 3 fields"));
+                };
+                let countries: Vec<_> = countries.split(',').map(str::to_string).collect();
+                if !countries
+                    .iter()
+                    .all(|x| x.len() == 2 && x.bytes().all(|x| x.is_ascii_uppercase()))
+                {
+                    return Err(ZoneInfoError::InvalidZoneTab("bad country code"));
+                }
+                let coordinates = parse_coordinates(coordinates)
+                    .ok_or(ZoneInfoError::InvalidZoneTab("bad coordinates"))?;
+                let comment = fields.next().filter(|x| !x.is_empty()).map(str::to_string);
+                Ok(ZoneTabEntry {
+                    countries,
+                    coordinates,
+                    zone: Arc::from(zone.as_bytes()),
+                    comment,
+                })
+            })
+            .collect::<Result<_>>()?;
+        Ok(Self { entries })
+    }
+
+    /// Read and parse the zonetab section, which runs from `zonetab_offset` to the end of the file.
+    #[cfg(feature = "std")]
+    pub fn from_reader<R: Read + Seek>(mut reader: R, header: &TzDataHeader) -> Result<Self> {
+        reader.seek(SeekFrom::Start(header.zonetab_offset.into()))?;
+        let mut text = Vec::new();
+        reader.read_to_end(&mut text)?;
+        Self::new(&text)
+    }
+
+    /// All rows, in file order.
+    pub fn entries(&self) -> &[ZoneTabEntry] {
+        &self.entries
+    }
+
+    /// Zones of a country, e.g. `CN`, in file order.
+    pub fn zones_for_country<'a>(&'a self, country: &str) -> impl Iterator<Item = &'a [u8]> {
+        self.entries
+            .iter()
+            .filter(move |x| x.countries.iter().any(|x| x.eq_ignore_ascii_case(country)))
+            .map(|x| &*x.zone)
+    }
+
+    /// Find the row of a zone.
+    pub fn find_zone(&self, zone: &[u8]) -> Option<&ZoneTabEntry> {
+        self.entries.iter().find(|x| &*x.zone == zone)
+    }
+
+    /// Country of a zone; the first one for `zone1970.tab` rows listing several.
+    pub fn country_for_zone(&self, zone: &[u8]) -> Option<&str> {
+        self.find_zone(zone)?.countries.first().map(String::as_str)
+    }
+
+    /// Comment of a zone's row, e.g. `Eastern (most areas)`, for telling apart the zones of a
+    /// country in a picker.
+    pub fn comment_for(&self, zone: &[u8]) -> Option<&str> {
+        self.find_zone(zone)?.comment.as_deref()
+    }
+
+    /// Replace the zone names of rows with those of the matching entries of `indexes`, so both
+    /// share one allocation per name.
+    pub fn share_names(&mut self, indexes: &TzDataIndexes) {
+        for entry in &mut self.entries {
+            if let Some(index) = indexes.find_timezone(&entry.zone) {
+                entry.zone = index.name.clone();
+            }
+        }
+    }
+
+    /// Coordinates of a zone's principal location.
+    pub fn coordinates(&self, zone: &[u8]) -> Option<Coordinates> {
+        self.find_zone(zone).map(|x| x.coordinates)
+    }
+
+    /// Zone whose principal location is nearest to the given point, in degrees; the first one
+    /// in file order among equally near ones.
+    ///
+    /// Principal locations are only a rough proxy for zone boundaries, so near a border this
+    /// can pick the zone across it.
+    #[cfg(feature = "std")]
+    pub fn nearest_zone(&self, latitude: f64, longitude: f64) -> Option<&[u8]> {
+        let point = Coordinates { latitude, longitude };
+        self.entries
+            .iter()
+            .map(|x| (x.coordinates.distance_km(&point), &*x.zone))
+            .min_by(|a, b| a.0.total_cmp(&b.0))
+            .map(|(_, zone)| zone)
+    }
+
+    /// Zones of a country, e.g. `US`, nearest to the given point first.
+    #[cfg(feature = "std")]
+    pub fn zones_for_country_sorted_by_distance(
+        &self,
+        country: &str,
+        latitude: f64,
+        longitude: f64,
+    ) -> Vec<&[u8]> {
+        let point = Coordinates { latitude, longitude };
+        let mut zones: Vec<_> = self
+            .entries
+            .iter()
+            .filter(|x| x.countries.iter().any(|x| x.eq_ignore_ascii_case(country)))
+            .map(|x| (x.coordinates.distance_km(&point), &*x.zone))
+            .collect();
+        zones.sort_by(|a, b| a.0.total_cmp(&b.0));
+        zones.into_iter().map(|(_, zone)| zone).collect()
+    }
+}
+
+/// Parse ISO 6709 `±DDMM±DDDMM` or `±DDMMSS±DDDMMSS` coordinates.
+fn parse_coordinates(text: &str) -> Option<Coordinates> {
+    let split = text.get(1..)?.find(['+', '-'])? + 1;
+    let (latitude, longitude) = text.split_at(split);
+    Some(Coordinates {
+        latitude: parse_degrees(latitude, 2)?,
+        longitude: parse_degrees(longitude, 3)?,
+    })
+}
+
+/// Parse a signed `DD[D]MM[SS]` angle whose degrees take `degree_digits` digits.
+fn parse_degrees(text: &str, degree_digits: usize) -> Option<f64> {
+    let sign = match text.as_bytes().first()? {
+        b'+' => 1.0,
+        b'-' => -1.0,
+        _ => return None,
+    };
+    let digits = &text[1..];
+    if !digits.bytes().all(|x| x.is_ascii_digit())
+        || (digits.len() != degree_digits + 2 && digits.len() != degree_digits + 4)
+    {
+        return None;
+    }
+    let field =
+        |range: core::ops::Range<usize>| digits.get(range).map_or(Some(0.0), |x| x.parse().ok());
+    let degrees: f64 = field(0..degree_digits)?;
+    let minutes: f64 = field(degree_digits..degree_digits + 2)?;
+    let seconds: f64 = field(degree_digits + 2..degree_digits + 4)?;
+    Some(sign * (degrees + minutes / 60.0 + seconds / 3600.0))
+}
+
+#[cfg(feature = "std")]
+impl ZoneInfoDb {
+    /// The zonetab section of the database, parsed on first use and sharing zone names with the
+    /// index, see [`ZoneTab::share_names`]. Failures aren't cached.
+    pub fn zone_tab(&self) -> Result<&ZoneTab> {
+        if let Some(zone_tab) = self.zone_tab.get() {
+            return Ok(zone_tab);
+        }
+        let mut zone_tab = ZoneTab::new(self.zone_tab_bytes())?;
+        zone_tab.share_names(&self.indexes);
+        Ok(self.zone_tab.get_or_init(|| zone_tab))
+    }
+}
+
+#[cfg(all(test, feature = "std"))]
+mod tests {
+    use std::fs::File;
+
+    use super::*;
+
+    const ZONE_TAB: &str = "\
+# tzdb timezone descriptions
+#
+CN\t+3114+12128\tAsia/Shanghai\tBeijing Time
+US\t+404251-0740023\tAmerica/New_York\tEastern (most areas)
+US\t+340308-1181434\tAmerica/Los_Angeles\tPacific
+AU\t-3352+15113\tAustralia/Sydney\tNew South Wales (most areas)
+CA,US\t+4339-07923\tAmerica/Toronto
+";
+
+    #[test]
+    fn test_zone_tab() {
+        let zone_tab = ZoneTab::new(ZONE_TAB.as_bytes()).unwrap();
+        assert_eq!(zone_tab.entries().len(), 5);
+        let zones: Vec<_> = zone_tab.zones_for_country("US").collect();
+        assert_eq!(zones, [&b"America/New_York"[..], b"America/Los_Angeles", b"America/Toronto"]);
+        assert_eq!(zone_tab.country_for_zone(b"Asia/Shanghai"), Some("CN"));
+        assert_eq!(zone_tab.country_for_zone(b"America/Toronto"), Some("CA"));
+        assert_eq!(zone_tab.country_for_zone(b"Asia/Nowhere"), None);
+        assert_eq!(zone_tab.comment_for(b"America/Los_Angeles"), Some("Pacific"));
+        assert_eq!(zone_tab.comment_for(b"America/Toronto"), None);
+        assert_eq!(zone_tab.comment_for(b"Asia/Nowhere"), None);
+
+        let shanghai = zone_tab.coordinates(b"Asia/Shanghai").unwrap();
+        assert!((shanghai.latitude - (31.0 + 14.0 / 60.0)).abs() < 1e-9);
+        assert!((shanghai.longitude - (121.0 + 28.0 / 60.0)).abs() < 1e-9);
+        let new_york = zone_tab.coordinates(b"America/New_York").unwrap();
+        assert!((new_york.latitude - (40.0 + 42.0 / 60.0 + 51.0 / 3600.0)).abs() < 1e-9);
+        assert!((new_york.longitude + (74.0 + 0.0 / 60.0 + 23.0 / 3600.0)).abs() < 1e-9);
+        let sydney = zone_tab.coordinates(b"Australia/Sydney").unwrap();
+        assert!(sydney.latitude < 0.0 && sydney.longitude > 0.0);
+    }
+
+    #[test]
+    fn test_zone_tab_nearest_zone() {
+        let zone_tab = ZoneTab::new(ZONE_TAB.as_bytes()).unwrap();
+        // Hangzhou, Boston and San Diego.
+        assert_eq!(zone_tab.nearest_zone(30.27, 120.15), Some(&b"Asia/Shanghai"[..]));
+        assert_eq!(zone_tab.nearest_zone(42.36, -71.06), Some(&b"America/New_York"[..]));
+        let zones = zone_tab.zones_for_country_sorted_by_distance("us", 32.72, -117.16);
+        assert_eq!(zones, [&b"America/Los_Angeles"[..], b"America/Toronto", b"America/New_York"]);
+        assert!(zone_tab.zones_for_country_sorted_by_distance("FR", 0.0, 0.0).is_empty());
+        assert_eq!(ZoneTab::default().nearest_zone(0.0, 0.0), None);
+
+        let shanghai = zone_tab.coordinates(b"Asia/Shanghai").unwrap();
+        let sydney = zone_tab.coordinates(b"Australia/Sydney").unwrap();
+        assert!((shanghai.distance_km(&sydney) - 7880.0).abs() < 20.0);
+    }
+
+    #[test]
+    fn test_zone_tab_malformed() {
+        assert!(ZoneTab::new(b"CN\tAsia/Shanghai\n").is_err());
+        assert!(ZoneTab::new(b"cn\t+3114+12128\tAsia/Shanghai\n").is_err());
+        assert!(ZoneTab::new(b"CN\t+3114+1212\tAsia/Shanghai\n").is_err());
+        assert!(ZoneTab::new(b"CN\t\tAsia/Shanghai\n").is_err());
+        assert!(ZoneTab::new(b"").unwrap().entries().is_empty());
+    }
+
+    #[test]
+    fn test_db_zone_tab() {
+        // The bundled databases have an empty zonetab section, which ends the file.
+        let mut data = std::fs::read("./tests/android/tzdata").unwrap();
+        let db = ZoneInfoDb::new_android(&data[..]).unwrap();
+        assert!(db.zone_tab().unwrap().entries().is_empty());
+        let header = TzDataHeader::new(File::open("./tests/android/tzdata").unwrap()).unwrap();
+        let from_reader =
+            ZoneTab::from_reader(File::open("./tests/android/tzdata").unwrap(), &header);
+        assert!(from_reader.unwrap().entries().is_empty());
+
+        data.extend_from_slice(ZONE_TAB.as_bytes());
+        let db = ZoneInfoDb::new_android(&data[..]).unwrap();
+        let zones: Vec<_> =
+            db.zone_tab().unwrap().zones_for_country("cn").map(|x| x.to_vec()).collect();
+        assert_eq!(zones, [b"Asia/Shanghai".to_vec()]);
+        let zone_tab = db.zone_tab().unwrap();
+        let entry = zone_tab.find_zone(b"Asia/Shanghai").unwrap();
+        let index = db.indexes().find_timezone(b"Asia/Shanghai").unwrap();
+        assert!(Arc::ptr_eq(&entry.zone, &index.name));
+        // Parsed once.
+        assert!(core::ptr::eq(zone_tab, db.zone_tab().unwrap()));
+        assert_eq!(db.zone_tab_bytes(), ZONE_TAB.as_bytes());
+    }
+}